@@ -1,6 +0,0 @@
-mod gameboard;
-
-fn main() {
-    println!("Hello, Sudoku!");
-
-}
@@ -0,0 +1,39 @@
+//! 编译时记下当前提交的短哈希（关于界面显示），供 `env!("SUDOKU_GIT_HASH")` 读取。
+//!
+//! 打包时没有 git 仓库（如源码压缩包）可以直接设置环境变量 `SUDOKU_GIT_HASH`；
+//! 两者都没有时为 "unknown"。
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=SUDOKU_GIT_HASH");
+    // 提交或切换分支时重新运行：HEAD 与它指向的分支文件（分支已打包时为 packed-refs）。
+    // 只监视存在的文件，否则 Cargo 每次都当作有变化而重新编译
+    let head = Path::new(".git/HEAD");
+    let mut watched = vec![head.to_path_buf()];
+    if let Some(branch) = fs::read_to_string(head)
+        .ok()
+        .and_then(|head| Some(head.strip_prefix("ref:")?.trim().to_string()))
+    {
+        watched.push(Path::new(".git").join(branch));
+        watched.push(Path::new(".git/packed-refs").to_path_buf());
+    }
+    for path in watched.iter().filter(|path| path.is_file()) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    let hash = std::env::var("SUDOKU_GIT_HASH")
+        .ok()
+        .filter(|hash| !hash.trim().is_empty())
+        .or_else(|| {
+            let output = Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()?;
+            let hash = String::from_utf8(output.stdout).ok()?;
+            (output.status.success() && !hash.trim().is_empty()).then_some(hash)
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=SUDOKU_GIT_HASH={}", hash.trim());
+}
@@ -0,0 +1,136 @@
+//! 关于界面：版本、构建信息、界面字体的许可证与第三方库的署名。
+//!
+//! 提交哈希由 `build.rs` 在编译时写入。分发单个可执行文件时没有 assets/ 目录，
+//! 所以字体许可证全文也编译进来，在关于界面按 L 显示。
+
+/// 程序版本
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 构建时所在提交的短哈希（未知时为 "unknown"）
+pub const GIT_HASH: &str = env!("SUDOKU_GIT_HASH");
+
+/// 界面字体的许可证全文
+const FONT_LICENSE: &str = include_str!("../assets/LICENSE");
+
+/// 直接依赖的第三方库：(名称, 许可证, 是否编译进了本程序)。
+/// 在 Cargo.toml 中添加依赖时也要加在这里，测试会检查两边一致
+const LIBRARIES: &[(&str, &str, bool)] = &[
+    ("piston", "MIT", true),
+    ("piston2d-graphics", "MIT", true),
+    ("piston2d-opengl_graphics", "MIT", true),
+    ("pistoncore-glutin_window", "MIT", cfg!(feature = "glutin")),
+    ("pistoncore-sdl2_window", "MIT", cfg!(feature = "sdl2")),
+    ("SDL2", "zlib", cfg!(feature = "sdl2")),
+    ("clap", "MIT / Apache-2.0", true),
+    ("crossterm", "MIT", true),
+    ("directories", "MIT / Apache-2.0", true),
+    ("png", "MIT / Apache-2.0", true),
+    ("rand", "MIT / Apache-2.0", true),
+    ("rusqlite", "MIT", true),
+    ("SQLite", "public domain", true),
+    ("rusttype", "MIT / Apache-2.0", true),
+    ("serde", "MIT / Apache-2.0", true),
+    ("serde_json", "MIT / Apache-2.0", true),
+    ("toml", "MIT / Apache-2.0", true),
+    ("ureq", "MIT / Apache-2.0", cfg!(feature = "online")),
+    ("rodio", "MIT / Apache-2.0", cfg!(feature = "audio")),
+    ("copypasta", "MIT / Apache-2.0", cfg!(feature = "clipboard")),
+    ("image", "MIT", cfg!(feature = "ocr")),
+    ("rhai", "MIT / Apache-2.0", cfg!(feature = "scripting")),
+    (
+        "accesskit",
+        "MIT / Apache-2.0",
+        cfg!(feature = "screen-reader"),
+    ),
+    (
+        "accesskit_unix",
+        "MIT / Apache-2.0",
+        cfg!(all(
+            feature = "screen-reader",
+            unix,
+            not(target_os = "macos")
+        )),
+    ),
+    (
+        "accesskit_windows",
+        "MIT / Apache-2.0",
+        cfg!(all(feature = "screen-reader", windows)),
+    ),
+    (
+        "accesskit_macos",
+        "MIT / Apache-2.0",
+        cfg!(all(feature = "screen-reader-macos", target_os = "macos")),
+    ),
+    ("raw-window-handle", "MIT", cfg!(feature = "screen-reader")),
+];
+
+/// 关于界面的文字：版本与构建信息、字体，随后是编译进来的第三方库
+pub fn lines() -> Vec<String> {
+    let mut lines = vec![
+        format!("Sudoku {} (commit {})", VERSION, GIT_HASH),
+        format!(
+            "Built for {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ),
+        "Font: Fira Sans (SIL Open Font License 1.1)".to_string(),
+        "Third-party libraries:".to_string(),
+    ];
+    lines.extend(
+        LIBRARIES
+            .iter()
+            .filter(|&&(_, _, used)| used)
+            .map(|(name, license, _)| format!("{} ({})", name, license)),
+    );
+    lines
+}
+
+/// 许可证每行最多的字符数，面板宽度内放得下
+const LICENSE_COLUMNS: usize = 44;
+
+/// 关于界面按 L 显示的字体许可证全文，按面板宽度重新折行（空行保留为段落间隔）
+pub fn license_lines() -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in FONT_LICENSE.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > LICENSE_COLUMNS
+            {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LIBRARIES;
+
+    /// Cargo.toml 中的每个直接依赖（含各平台的依赖，不含本仓库的 sudoku-core）都在关于界面中列出
+    #[test]
+    fn libraries_cover_every_dependency() {
+        let manifest: toml::Table = include_str!("../Cargo.toml").parse().unwrap();
+        let mut tables = vec![&manifest["dependencies"]];
+        if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+            tables.extend(
+                targets
+                    .values()
+                    .filter_map(|target| target.get("dependencies")),
+            );
+        }
+        let missing: Vec<&String> = tables
+            .iter()
+            .filter_map(|table| table.as_table())
+            .flat_map(|table| table.keys())
+            .filter(|name| *name != "sudoku-core")
+            .filter(|name| LIBRARIES.iter().all(|(library, _, _)| library != name))
+            .collect();
+        assert!(missing.is_empty(), "missing from LIBRARIES: {:?}", missing);
+    }
+}
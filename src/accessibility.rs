@@ -0,0 +1,160 @@
+//! 无障碍树：把棋盘、选中格和底部按钮描述成读屏软件使用的节点树。
+//!
+//! 节点结构与 AccessKit 的模型对应（角色、名称、值、焦点），
+//! 每次输入后由当前标签页重新生成，与上一次比较得出需要朗读的变化。
+//! 同一棵树经 `screen_reader.rs` 交给系统读屏软件；
+//! 在配置中开启 `assist.screen_reader` 后，变化还会以文字逐行输出到终端，供终端读屏软件朗读。
+
+use crate::action;
+use crate::gameboard_controller::{Flash, GameboardController};
+
+/// 节点角色
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Grid,
+    Cell,
+    Button,
+}
+
+impl Role {
+    fn name(self) -> &'static str {
+        match self {
+            Role::Grid => "grid",
+            Role::Cell => "cell",
+            Role::Button => "button",
+        }
+    }
+}
+
+/// 一个节点；`id` 为在 `Tree::nodes` 中的下标
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub role: Role,
+    pub name: String,
+    pub value: String,
+}
+
+impl Node {
+    /// 朗读的文字，如 "Row 3, column 5 cell: 7, given"
+    pub fn describe(&self) -> String {
+        if self.value.is_empty() {
+            format!("{} {}", self.name, self.role.name())
+        } else {
+            format!("{} {}: {}", self.name, self.role.name(), self.value)
+        }
+    }
+}
+
+/// 整个界面的节点树
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tree {
+    pub nodes: Vec<Node>,
+    /// 获得焦点的节点（选中的格子，没有时为棋盘）
+    pub focus: usize,
+}
+
+/// 棋盘节点的下标；之后依次为 81 个格子和底部按钮
+pub const GRID: usize = 0;
+pub const FIRST_CELL: usize = 1;
+
+/// 一次最多逐个朗读的格子变化数
+const MAX_CHANGES: usize = 2;
+
+/// 格子 `[x, y]` 的节点下标
+fn cell_id([x, y]: [usize; 2]) -> usize {
+    FIRST_CELL + y * 9 + x
+}
+
+/// 由当前标签页生成节点树
+pub fn build(controller: &GameboardController) -> Tree {
+    let mut nodes = vec![Node {
+        role: Role::Grid,
+        name: "Board".to_string(),
+        value: format!("{} of 81 cells filled", controller.filled_count()),
+    }];
+    for y in 0..9 {
+        for x in 0..9 {
+            nodes.push(Node {
+                role: Role::Cell,
+                name: format!("Row {}, column {}", y + 1, x + 1),
+                value: cell_value(controller, [x, y]),
+            });
+        }
+    }
+    nodes.extend(action::buttons().map(|button| Node {
+        role: Role::Button,
+        name: button.name().to_string(),
+        value: String::new(),
+    }));
+    Tree {
+        nodes,
+        focus: controller.selected_cell.map_or(GRID, cell_id),
+    }
+}
+
+/// 格子的值与状态，如 "7, given"、"empty, hint 4"、"3, wrong"；
+/// Wordoku 盘面上读界面中显示的字母
+fn cell_value(controller: &GameboardController, [x, y]: [usize; 2]) -> String {
+    let board = &controller.gameboard;
+    let mut text = match board.symbol(board.cells[y][x]) {
+        Some(symbol) => symbol.to_string(),
+        None => "empty".to_string(),
+    };
+    if controller.initial_cells[y][x] != 0 {
+        text.push_str(", given");
+    } else if controller.wrong.contains([x, y]) {
+        text.push_str(", checked wrong");
+    } else if controller.checked.contains([x, y]) {
+        text.push_str(", checked correct");
+    } else if controller.invalid_cells.contains([x, y]) {
+        text.push_str(if controller.submitted {
+            ", wrong"
+        } else {
+            ", conflict"
+        });
+    }
+    match controller.flash.filter(|f| f.0 == [x, y]) {
+        Some((_, Flash::Warning, _)) => text.push_str(", not a candidate"),
+        Some((_, Flash::Rejected, _)) => text.push_str(", rejected"),
+        None => {}
+    }
+    if let Some((_, hint)) = controller.hint.filter(|&(cell, _)| cell == [x, y]) {
+        if let Some(symbol) = board.symbol(hint) {
+            text.push_str(&format!(", hint {}", symbol));
+        }
+    }
+    text
+}
+
+/// 记录上一次的节点树，得出需要朗读的变化
+#[derive(Default)]
+pub struct Accessibility {
+    last: Option<Tree>,
+}
+
+impl Accessibility {
+    /// 更新节点树，返回要朗读的文字：焦点移动或焦点节点改变时朗读焦点；
+    /// 其它格子的变化（如出现提示）少量时逐个朗读，较多时（重置、新题）只朗读棋盘概况
+    pub fn update(&mut self, tree: Tree) -> Option<String> {
+        let last = self.last.replace(tree);
+        let tree = self.last.as_ref()?;
+        let focus = &tree.nodes[tree.focus];
+        let Some(last) = last else {
+            return Some(focus.describe());
+        };
+        let mut parts = Vec::new();
+        if last.focus != tree.focus || last.nodes[last.focus] != *focus {
+            parts.push(focus.describe());
+        }
+        let others: Vec<&Node> = (FIRST_CELL..FIRST_CELL + 81)
+            .filter(|&i| i != tree.focus && last.nodes[i] != tree.nodes[i])
+            .map(|i| &tree.nodes[i])
+            .collect();
+        if others.len() > MAX_CHANGES {
+            parts.push(tree.nodes[GRID].describe());
+        } else {
+            parts.extend(others.iter().map(|node| node.describe()));
+        }
+        (!parts.is_empty()).then(|| parts.join("; "))
+    }
+}
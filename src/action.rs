@@ -0,0 +1,449 @@
+//! 动作注册表：游戏中可执行的每个操作都对应一个 `Action`，并在 `REGISTRY` 中登记名称、
+//! 快捷键、底部按钮文字、能否执行的条件，以及交给棋盘的对局操作。
+//!
+//! 底部按钮、快捷键和命令面板都只产生 `Action`，统一交给 `App::dispatch` 执行：
+//! 不能执行时忽略，对局操作交给当前标签页的 `GameboardController::apply`，其余由 `App` 处理。
+//! 新增操作时只需在这里登记（不是对局操作的再在 dispatch 中处理）。
+
+use crate::config::KeyBindings;
+use crate::gameboard_controller::{GameboardController, InputAction};
+use piston::input::Key;
+
+/// 可执行的操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    NewGame,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    NextPuzzle,
+    PrevPuzzle,
+    Undo,
+    Redo,
+    Reset,
+    Hint,
+    ConfirmHint,
+    ShowAll,
+    CheckCell,
+    CheckUnit,
+    CheckAll,
+    ClearRegion,
+    Explain,
+    FindMistake,
+    Branch,
+    CommitBranch,
+    DiscardBranch,
+    Diagnostics,
+    Conflicts,
+    Submit,
+    Save,
+    CopyPuzzle,
+    PasteLink,
+    RunScript,
+    ToggleTheme,
+    ColorMode,
+    ZoomIn,
+    ZoomOut,
+    CommandPalette,
+    Help,
+    Pause,
+    Menu,
+    Quit,
+}
+
+/// 快捷键：(修饰键前缀, 按键)，按键从配置 `[keys]` 中读取或固定
+pub type Shortcut = (&'static str, fn(&KeyBindings) -> Key);
+
+/// 一个动作的登记信息
+pub struct Spec {
+    pub action: Action,
+    /// 命令面板中显示的名称（界面字体只含西文字符）
+    pub name: &'static str,
+    /// 游戏中的快捷键（第一个显示在命令面板中）
+    pub shortcuts: &'static [Shortcut],
+    /// 底部按钮上的文字；None 表示没有按钮（按钮按登记顺序从左到右排列）
+    pub button: Option<&'static str>,
+    /// 当前标签页上能否执行；不能执行时按钮显示为灰色，按钮与快捷键不响应，命令面板中不列出
+    pub enabled: fn(&GameboardController) -> bool,
+    /// 交给当前标签页的对局操作；None 时由 `App::dispatch` 处理
+    pub input: Option<InputAction>,
+}
+
+fn always(_: &GameboardController) -> bool {
+    true
+}
+
+/// 未提交时才能执行
+fn unsubmitted(controller: &GameboardController) -> bool {
+    !controller.submitted
+}
+
+/// 题目来自题库时才能执行
+fn in_pack(controller: &GameboardController) -> bool {
+    controller.pack.is_some()
+}
+
+/// 在假设分支中时才能执行
+fn in_branch(controller: &GameboardController) -> bool {
+    !controller.branches.is_empty()
+}
+
+/// 全部动作（命令面板按此顺序列出）
+pub static REGISTRY: [Spec; 38] = [
+    Spec {
+        action: Action::Undo,
+        name: "Undo",
+        shortcuts: &[("", |keys| keys.undo)],
+        button: Some("Undo"),
+        enabled: GameboardController::can_undo,
+        input: Some(InputAction::Undo),
+    },
+    Spec {
+        action: Action::Redo,
+        name: "Redo",
+        shortcuts: &[("", |keys| keys.redo)],
+        button: Some("Redo"),
+        enabled: GameboardController::can_redo,
+        input: Some(InputAction::Redo),
+    },
+    Spec {
+        action: Action::Reset,
+        name: "Reset board",
+        shortcuts: &[("", |keys| keys.reset)],
+        button: Some("Reset"),
+        enabled: |controller| unsubmitted(controller) && controller.has_user_input(),
+        input: Some(InputAction::Reset),
+    },
+    Spec {
+        action: Action::NewGame,
+        name: "New game",
+        shortcuts: &[("", |keys| keys.new_game)],
+        button: Some("Random"),
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::NewTab,
+        name: "New game in new tab",
+        shortcuts: &[("Ctrl+", |_| Key::N)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::CloseTab,
+        name: "Close tab",
+        shortcuts: &[("Ctrl+", |_| Key::W)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::NextTab,
+        name: "Next tab",
+        shortcuts: &[("Ctrl+", |_| Key::Tab)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::PrevTab,
+        name: "Previous tab",
+        shortcuts: &[("Ctrl+Shift+", |_| Key::Tab)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::NextPuzzle,
+        name: "Next puzzle in pack",
+        shortcuts: &[("Ctrl+", |_| Key::Right)],
+        button: None,
+        enabled: in_pack,
+        input: None,
+    },
+    Spec {
+        action: Action::PrevPuzzle,
+        name: "Previous puzzle in pack",
+        shortcuts: &[("Ctrl+", |_| Key::Left)],
+        button: None,
+        enabled: in_pack,
+        input: None,
+    },
+    Spec {
+        action: Action::Hint,
+        name: "Show hint",
+        shortcuts: &[("", |keys| keys.hint)],
+        button: Some("Hint"),
+        enabled: unsubmitted,
+        input: Some(InputAction::Hint),
+    },
+    Spec {
+        action: Action::ConfirmHint,
+        name: "Confirm hint",
+        shortcuts: &[("", |keys| keys.confirm_hint)],
+        button: None,
+        enabled: |controller| controller.hint.is_some(),
+        input: Some(InputAction::ConfirmHint),
+    },
+    Spec {
+        action: Action::ShowAll,
+        name: "Show / hide solution",
+        shortcuts: &[("", |keys| keys.show_all)],
+        button: Some("Show All"),
+        enabled: always,
+        input: Some(InputAction::ToggleShowAll),
+    },
+    Spec {
+        action: Action::CheckCell,
+        name: "Check selected cell",
+        shortcuts: &[("", |keys| keys.check_cell)],
+        button: None,
+        enabled: |controller| controller.selected_editable().is_some(),
+        input: Some(InputAction::CheckCell),
+    },
+    Spec {
+        action: Action::CheckUnit,
+        name: "Check selected row / column / box",
+        shortcuts: &[("Shift+", |keys| keys.check_cell)],
+        button: None,
+        enabled: |controller| unsubmitted(controller) && controller.selected_cell.is_some(),
+        input: Some(InputAction::CheckUnit),
+    },
+    Spec {
+        action: Action::CheckAll,
+        name: "Check all entries",
+        shortcuts: &[("", |keys| keys.check_all)],
+        button: None,
+        enabled: unsubmitted,
+        input: Some(InputAction::CheckAll),
+    },
+    Spec {
+        action: Action::ClearRegion,
+        name: "Clear selection / row / column / box...",
+        shortcuts: &[("", |keys| keys.clear_region)],
+        button: None,
+        enabled: |controller| unsubmitted(controller) && controller.selected_cell.is_some(),
+        input: None,
+    },
+    Spec {
+        action: Action::Explain,
+        name: "Explain selected cell",
+        shortcuts: &[("", |keys| keys.explain)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::FindMistake,
+        name: "Find my mistake",
+        shortcuts: &[("", |keys| keys.find_mistake)],
+        button: None,
+        enabled: unsubmitted,
+        input: Some(InputAction::FindMistake),
+    },
+    Spec {
+        action: Action::Branch,
+        name: "Start what-if branch",
+        shortcuts: &[("", |keys| keys.branch)],
+        button: None,
+        enabled: unsubmitted,
+        input: Some(InputAction::Branch),
+    },
+    Spec {
+        action: Action::CommitBranch,
+        name: "Keep what-if branch",
+        shortcuts: &[("", |keys| keys.commit_branch)],
+        button: None,
+        enabled: in_branch,
+        input: Some(InputAction::CommitBranch),
+    },
+    Spec {
+        action: Action::DiscardBranch,
+        name: "Discard what-if branch",
+        shortcuts: &[("", |keys| keys.discard_branch)],
+        button: None,
+        enabled: in_branch,
+        input: Some(InputAction::DiscardBranch),
+    },
+    Spec {
+        action: Action::Diagnostics,
+        name: "Solver diagnostics",
+        shortcuts: &[("", |keys| keys.diagnostics)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::Conflicts,
+        name: "Conflicts panel",
+        shortcuts: &[("", |keys| keys.conflicts)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::Submit,
+        name: "Submit",
+        shortcuts: &[("", |keys| keys.submit)],
+        button: Some("Submit"),
+        enabled: unsubmitted,
+        input: Some(InputAction::Submit),
+    },
+    Spec {
+        action: Action::Save,
+        name: "Save game",
+        shortcuts: &[("", |keys| keys.save)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::CopyPuzzle,
+        name: "Copy puzzle...",
+        shortcuts: &[("Ctrl+", |_| Key::C)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::PasteLink,
+        name: "Open puzzle link from clipboard",
+        shortcuts: &[("Ctrl+", |_| Key::V)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::RunScript,
+        name: "Run script...",
+        shortcuts: &[],
+        button: None,
+        enabled: |_| cfg!(feature = "scripting"),
+        input: None,
+    },
+    Spec {
+        action: Action::ToggleTheme,
+        name: "Toggle theme",
+        shortcuts: &[("", |keys| keys.toggle_theme)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::ColorMode,
+        name: "Color mode",
+        shortcuts: &[],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::ZoomIn,
+        name: "Larger interface",
+        shortcuts: &[("Ctrl+", |_| Key::Equals)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::ZoomOut,
+        name: "Smaller interface",
+        shortcuts: &[("Ctrl+", |_| Key::Minus)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::CommandPalette,
+        name: "Command palette",
+        shortcuts: &[("Ctrl+", |_| Key::P)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::Help,
+        name: "Keyboard shortcuts",
+        shortcuts: &[("", |keys| keys.help), ("Shift+", |_| Key::H)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::Pause,
+        name: "Pause",
+        shortcuts: &[("", |keys| keys.pause), ("", |_| Key::Escape)],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::Menu,
+        name: "Main menu",
+        shortcuts: &[],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+    Spec {
+        action: Action::Quit,
+        name: "Quit",
+        shortcuts: &[],
+        button: None,
+        enabled: always,
+        input: None,
+    },
+];
+
+impl Action {
+    /// 登记信息
+    pub fn spec(self) -> &'static Spec {
+        REGISTRY
+            .iter()
+            .find(|spec| spec.action == self)
+            .expect("every action is registered")
+    }
+
+    /// 命令面板中显示的名称
+    pub fn name(self) -> &'static str {
+        self.spec().name
+    }
+}
+
+/// 底部按钮从左到右对应的动作
+pub fn buttons() -> impl Iterator<Item = Action> {
+    REGISTRY
+        .iter()
+        .filter(|spec| spec.button.is_some())
+        .map(|spec| spec.action)
+}
+
+/// 模糊匹配：`query` 的字符按顺序出现在 `text` 中（忽略大小写）时返回得分，越高越好。
+/// 连续匹配和单词开头的匹配加分，越靠后开始的匹配扣分。
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut prev: Option<usize> = None;
+    for q in query.chars().flat_map(char::to_lowercase) {
+        if q.is_whitespace() {
+            continue;
+        }
+        let i = next + text[next..].iter().position(|&t| t == q)?;
+        score += 1;
+        if prev.is_some_and(|p| p + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if prev.is_none() {
+            score -= i as i32;
+        }
+        prev = Some(i);
+        next = i + 1;
+    }
+    Some(score)
+}
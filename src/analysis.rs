@@ -0,0 +1,135 @@
+//! 赛后分析：由带时间戳的事件流算出每个数字花的时间、最长的停顿、改过的格子与用过的提示。
+//!
+//! 每次填数花的时间记为与上一次盘面变化的间隔，计入所填的数字；清空格子的时间不计入任何数字。
+
+use crate::gameboard_controller::{format_time, GameEvent, GameboardController};
+
+/// 改过的格子与提示最多列出的个数（面板高度有限）
+const SHOWN: usize = 5;
+
+/// 一局的分析结果
+#[derive(Clone, Debug, Default)]
+pub struct Analysis {
+    /// 每个数字（1..=9）填写所花的总时间（秒）与填写次数
+    pub digits: [(f64, usize); 9],
+    /// 最长的停顿：(开始时刻, 时长)，两次盘面变化之间（含开局到第一次、最后一次到结束）
+    pub stuck: Option<(f64, f64)>,
+    /// 填过数字后又被改掉或清空的格子 `[x, y]`，按第一次修改的顺序
+    pub corrected: Vec<[usize; 2]>,
+    /// 显示过的提示，以及之后是否按提示填入
+    pub hints: Vec<(GameEvent, bool)>,
+}
+
+impl Analysis {
+    pub fn new(controller: &GameboardController) -> Self {
+        let events = &controller.events;
+        let mut analysis = Analysis::default();
+
+        let mut last = 0.0;
+        let mut current = controller.initial_cells;
+        for event in events.iter() {
+            let gap = event.time - last;
+            if analysis.stuck.is_none_or(|(_, longest)| gap > longest) {
+                analysis.stuck = Some((last, gap));
+            }
+            last = event.time;
+
+            let [x, y] = [event.x, event.y];
+            if event.value != 0 {
+                let digit = &mut analysis.digits[event.value as usize - 1];
+                digit.0 += gap;
+                digit.1 += 1;
+            }
+            let corrected = current[y][x] != 0 && current[y][x] != event.value;
+            if corrected && !analysis.corrected.contains(&[x, y]) {
+                analysis.corrected.push([x, y]);
+            }
+            current[y][x] = event.value;
+        }
+        let gap = controller.elapsed - last;
+        if analysis.stuck.is_none_or(|(_, longest)| gap > longest) {
+            analysis.stuck = Some((last, gap));
+        }
+
+        analysis.hints = controller
+            .hints
+            .iter()
+            .map(|hint| {
+                let taken = events.iter().any(|e| {
+                    e.time >= hint.time && [e.x, e.y] == [hint.x, hint.y] && e.value == hint.value
+                });
+                (*hint, taken)
+            })
+            .collect();
+        analysis
+    }
+
+    /// 分析界面的文字
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec!["Time per digit:".to_string()];
+        for (i, digits) in self.digits.chunks(3).enumerate() {
+            let text: Vec<String> = digits
+                .iter()
+                .enumerate()
+                .map(|(j, &(seconds, count))| {
+                    let digit = i * 3 + j + 1;
+                    if count == 0 {
+                        format!("{}: -", digit)
+                    } else {
+                        format!("{}: {}", digit, format_time(seconds))
+                    }
+                })
+                .collect();
+            lines.push(text.join("     "));
+        }
+        if let Some((start, length)) = self.stuck {
+            lines.push(format!(
+                "Longest pause: {} from {}",
+                format_time(length),
+                format_time(start)
+            ));
+        }
+        lines.push(if self.corrected.is_empty() {
+            "No cells corrected".to_string()
+        } else {
+            let cells: Vec<String> = self
+                .corrected
+                .iter()
+                .take(SHOWN)
+                .map(|&c| cell_name(c))
+                .collect();
+            let more = if self.corrected.len() > SHOWN {
+                " ..."
+            } else {
+                ""
+            };
+            format!(
+                "Corrected ({}): {}{}",
+                self.corrected.len(),
+                cells.join(" "),
+                more
+            )
+        });
+        if self.hints.is_empty() {
+            lines.push("No hints used".to_string());
+        }
+        for (hint, taken) in self.hints.iter().take(SHOWN) {
+            lines.push(format!(
+                "Hint at {}: {} = {}{}",
+                format_time(hint.time),
+                cell_name([hint.x, hint.y]),
+                hint.value,
+                if *taken { ", filled in" } else { "" }
+            ));
+        }
+        if self.hints.len() > SHOWN {
+            lines.push(format!("... and {} more hints", self.hints.len() - SHOWN));
+        }
+        lines
+    }
+}
+
+/// 格子名，如 "r3c5"
+fn cell_name([x, y]: [usize; 2]) -> String {
+    format!("r{}c{}", y + 1, x + 1)
+}
@@ -0,0 +1,74 @@
+//! 资源文件（字体等）的定位与加载。
+//!
+//! 依次在当前目录、可执行文件所在目录、源码目录下的 `assets/` 中查找，
+//! 因此从其它工作目录启动或直接运行编译产物时也能找到字体。
+//! 开启 `embed-assets` feature 时资源同时编译进可执行文件，找不到文件时使用内置副本；
+//! 磁盘上的文件始终优先，方便开发时替换。
+
+use opengl_graphics::{GlyphCache, TextureSettings};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// 界面字体文件名
+pub const FONT_FILE: &str = "FiraSans-Regular.ttf";
+
+/// 编译进可执行文件的资源：(文件名, 内容)
+#[cfg(feature = "embed-assets")]
+const EMBEDDED: &[(&str, &[u8])] = &[(FONT_FILE, include_bytes!("../assets/FiraSans-Regular.ttf"))];
+
+/// 内置资源；未开启 `embed-assets` 时总是 None
+pub fn embedded(name: &str) -> Option<&'static [u8]> {
+    #[cfg(feature = "embed-assets")]
+    {
+        EMBEDDED
+            .iter()
+            .find(|(file, _)| *file == name)
+            .map(|&(_, bytes)| bytes)
+    }
+    #[cfg(not(feature = "embed-assets"))]
+    {
+        let _ = name;
+        None
+    }
+}
+
+/// 资源文件的候选路径（按优先级）
+fn candidates(name: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("assets")];
+    if let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        dirs.push(dir.join("assets"));
+    }
+    dirs.push(Path::new(env!("CARGO_MANIFEST_DIR")).join("assets"));
+    dirs.into_iter().map(|dir| dir.join(name)).collect()
+}
+
+/// 读取资源文件的内容（磁盘上的优先，其次是内置副本）
+pub fn read(name: &str) -> Option<Vec<u8>> {
+    candidates(name)
+        .iter()
+        .find_map(|path| std::fs::read(path).ok())
+        .or_else(|| embedded(name).map(<[u8]>::to_vec))
+}
+
+/// 加载界面字体；所有候选路径都失败时返回列出尝试过的路径的错误信息
+pub fn load_font(settings: TextureSettings) -> Result<GlyphCache<'static>, String> {
+    let mut tried = Vec::new();
+    for path in candidates(FONT_FILE) {
+        match GlyphCache::new(&path, (), settings) {
+            Ok(glyphs) => return Ok(glyphs),
+            Err(err) => tried.push(format!("  {}: {}", path.display(), err)),
+        }
+    }
+    if let Some(bytes) = embedded(FONT_FILE) {
+        return GlyphCache::from_bytes(bytes, (), settings)
+            .map_err(|()| format!("内置字体 {} 无效", FONT_FILE));
+    }
+    Err(format!(
+        "无法加载字体 {}，已尝试：\n{}",
+        FONT_FILE,
+        tried.join("\n")
+    ))
+}
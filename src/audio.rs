@@ -0,0 +1,176 @@
+//! 声音：打开音频输出并播放背景音乐。
+//!
+//! 背景音乐循环播放配置中的音乐文件（Ogg Vorbis 或 WAV），未指定文件时播放程序合成的环境音。
+//! 暂停游戏或窗口失去焦点时自动暂停。所有声音共用同一个输出流，以后的音效各自用一个 `Sink`
+//! 挂在同一个流上即可。需要开启 `audio` feature（依赖系统音频库，Linux 上为 ALSA）；
+//! 未开启或打开音频设备失败时静默运行。
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "audio")]
+use std::fs::File;
+#[cfg(feature = "audio")]
+use std::io::BufReader;
+#[cfg(feature = "audio")]
+use std::time::Duration;
+
+/// 声音设置（配置文件 `[audio]`）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// 是否播放背景音乐
+    pub music: bool,
+    /// 音乐音量（0.0 – 1.0）
+    pub volume: f32,
+    /// 音乐文件；为空时播放内置的合成环境音
+    pub music_file: String,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            music: false,
+            volume: 0.5,
+            music_file: String::new(),
+        }
+    }
+}
+
+/// 音频输出与正在播放的背景音乐
+#[derive(Default)]
+pub struct Audio {
+    #[cfg(feature = "audio")]
+    output: Option<(rodio::OutputStream, rodio::OutputStreamHandle)>,
+    #[cfg(feature = "audio")]
+    music: Option<rodio::Sink>,
+    /// 当前是否应当暂停（游戏暂停或窗口不在前台）
+    paused: bool,
+}
+
+impl Audio {
+    /// 按配置开始（或停止）背景音乐；音量改变时只调整音量
+    pub fn apply(&mut self, config: &AudioConfig) {
+        #[cfg(feature = "audio")]
+        {
+            if !config.music {
+                self.music = None;
+                return;
+            }
+            if let Some(sink) = &self.music {
+                sink.set_volume(config.volume);
+                return;
+            }
+            match self.start_music(config) {
+                Ok(sink) => self.music = Some(sink),
+                Err(err) => eprintln!("无法播放背景音乐: {}", err),
+            }
+        }
+        #[cfg(not(feature = "audio"))]
+        {
+            let _ = config;
+        }
+    }
+
+    /// 暂停 / 继续背景音乐
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        #[cfg(feature = "audio")]
+        if let Some(sink) = &self.music {
+            if paused {
+                sink.pause();
+            } else {
+                sink.play();
+            }
+        }
+    }
+
+    #[cfg(feature = "audio")]
+    fn start_music(&mut self, config: &AudioConfig) -> Result<rodio::Sink, String> {
+        use rodio::Source;
+
+        if self.output.is_none() {
+            self.output = Some(rodio::OutputStream::try_default().map_err(|e| e.to_string())?);
+        }
+        let (_, handle) = self.output.as_ref().unwrap();
+        let sink = rodio::Sink::try_new(handle).map_err(|e| e.to_string())?;
+        if config.music_file.is_empty() {
+            sink.append(Ambient::default());
+        } else {
+            let file = File::open(&config.music_file)
+                .map_err(|e| format!("{}: {}", config.music_file, e))?;
+            let decoder = rodio::Decoder::new(BufReader::new(file))
+                .map_err(|e| format!("{}: {}", config.music_file, e))?;
+            sink.append(decoder.repeat_infinite());
+        }
+        sink.set_volume(config.volume);
+        if self.paused {
+            sink.pause();
+        }
+        Ok(sink)
+    }
+}
+
+/// 内置环境音的采样率
+#[cfg(feature = "audio")]
+const SAMPLE_RATE: u32 = 44_100;
+
+/// 内置环境音：A 大三和弦的几个正弦波，各自以不同的慢速起伏，叠加成不断变化的长音
+#[cfg(feature = "audio")]
+#[derive(Default)]
+struct Ambient {
+    /// 各声部的相位与起伏相位（弧度，保持在一周以内以免 f32 精度下降产生杂音）
+    tone: [f32; 5],
+    swell: [f32; 5],
+}
+
+#[cfg(feature = "audio")]
+impl Ambient {
+    /// (频率 Hz, 起伏周期秒)
+    const VOICES: [(f32, f32); 5] = [
+        (110.0, 23.0),
+        (164.81, 17.0),
+        (220.0, 29.0),
+        (277.18, 13.0),
+        (329.63, 19.0),
+    ];
+}
+
+#[cfg(feature = "audio")]
+impl Iterator for Ambient {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        use std::f32::consts::TAU;
+
+        let step = TAU / SAMPLE_RATE as f32;
+        let mut value = 0.0;
+        for (i, &(freq, period)) in Self::VOICES.iter().enumerate() {
+            let swell = 0.5 + 0.5 * (self.swell[i] + i as f32).sin();
+            value += self.tone[i].sin() * swell;
+            self.tone[i] = (self.tone[i] + step * freq) % TAU;
+            self.swell[i] = (self.swell[i] + step / period) % TAU;
+        }
+        Some(value * 0.08)
+    }
+}
+
+#[cfg(feature = "audio")]
+impl rodio::Source for Ambient {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
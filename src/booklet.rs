@@ -0,0 +1,209 @@
+//! 题目小册子（`sudoku booklet`）：把一组题目排成可打印的多页 PDF，前面是题目，后面是答案。
+//!
+//! 只用到 PDF 的很小一部分：A4 页面、画线与 Helvetica 文字（PDF 阅读器自带的 14 种基本字体之一，
+//! 不需要嵌入字体），所以直接手写文件结构，不依赖 PDF 库。题目每页 2 × 2 道，标出序号与难度；
+//! 答案每页 3 × 4 道，给出的数字用粗体。
+
+use crate::persist;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+use sudoku_core::{Difficulty, Gameboard, SIZE};
+
+/// A4 页面大小（pt）
+const PAGE_W: f64 = 595.0;
+const PAGE_H: f64 = 842.0;
+
+/// 页边距（pt）
+const MARGIN: f64 = 48.0;
+
+/// 题目页与答案页的列数、行数
+const PUZZLE_GRID: (usize, usize) = (2, 2);
+const ANSWER_GRID: (usize, usize) = (3, 4);
+
+/// 格子之间的间距与格子上方标题的高度（pt）
+const GAP: f64 = 28.0;
+const LABEL_H: f64 = 22.0;
+
+/// Helvetica 中数字的宽度（em 的比例），用于把数字居中
+const DIGIT_WIDTH: f64 = 0.556;
+
+/// 一道题及其难度标签
+pub struct Entry {
+    pub puzzle: Gameboard,
+    pub solution: Gameboard,
+    pub difficulty: Difficulty,
+}
+
+/// 排版并写入 PDF
+pub fn write(path: &Path, title: &str, entries: &[Entry]) -> io::Result<()> {
+    let mut pages = Vec::new();
+    let per_page = PUZZLE_GRID.0 * PUZZLE_GRID.1;
+    for (page, chunk) in entries.chunks(per_page).enumerate() {
+        let mut content = header(&format!("{} - page {}", title, page + 1));
+        for (i, entry) in chunk.iter().enumerate() {
+            let number = page * per_page + i + 1;
+            let label = format!("{}. {}", number, entry.difficulty);
+            draw_grid(
+                &mut content,
+                PUZZLE_GRID,
+                i,
+                &label,
+                &entry.puzzle,
+                &entry.puzzle,
+            );
+        }
+        pages.push(content);
+    }
+    let per_page = ANSWER_GRID.0 * ANSWER_GRID.1;
+    for (page, chunk) in entries.chunks(per_page).enumerate() {
+        let mut content = header(&format!("{} - answers", title));
+        for (i, entry) in chunk.iter().enumerate() {
+            let label = format!("{}.", page * per_page + i + 1);
+            draw_grid(
+                &mut content,
+                ANSWER_GRID,
+                i,
+                &label,
+                &entry.solution,
+                &entry.puzzle,
+            );
+        }
+        pages.push(content);
+    }
+    persist::write_atomic(path, document(&pages))
+}
+
+/// 页面顶部的标题
+fn header(text: &str) -> String {
+    let mut content = String::new();
+    text_at(
+        &mut content,
+        "F1",
+        10.0,
+        MARGIN,
+        PAGE_H - MARGIN + 16.0,
+        text,
+    );
+    content
+}
+
+/// 在 `(columns, rows)` 排布的第 `slot` 个位置画一个盘面：`board` 中的数字，`givens` 中
+/// 非空的格子用粗体（题目页全是粗体，答案页标出原题）
+fn draw_grid(
+    content: &mut String,
+    (columns, rows): (usize, usize),
+    slot: usize,
+    label: &str,
+    board: &Gameboard,
+    givens: &Gameboard,
+) {
+    let slot_w = (PAGE_W - 2.0 * MARGIN - (columns - 1) as f64 * GAP) / columns as f64;
+    let slot_h = (PAGE_H - 2.0 * MARGIN - (rows - 1) as f64 * GAP) / rows as f64;
+    let side = slot_w.min(slot_h - LABEL_H);
+    let cell = side / SIZE as f64;
+    let (col, row) = (slot % columns, slot / columns);
+    let left = MARGIN + col as f64 * (slot_w + GAP) + (slot_w - side) / 2.0;
+    let top = PAGE_H - MARGIN - row as f64 * (slot_h + GAP);
+    let bottom = top - LABEL_H - side;
+
+    text_at(content, "F2", 11.0, left, top - 14.0, label);
+    for i in 0..=SIZE {
+        let width = if i % 3 == 0 { 1.5 } else { 0.4 };
+        let offset = i as f64 * cell;
+        let _ = writeln!(content, "{:.2} w", width);
+        let _ = writeln!(
+            content,
+            "{:.2} {:.2} m {:.2} {:.2} l S",
+            left + offset,
+            bottom,
+            left + offset,
+            bottom + side
+        );
+        let _ = writeln!(
+            content,
+            "{:.2} {:.2} m {:.2} {:.2} l S",
+            left,
+            bottom + offset,
+            left + side,
+            bottom + offset
+        );
+    }
+    let size = cell * 0.6;
+    for (y, cells) in board.cells.iter().enumerate() {
+        for (x, &value) in cells.iter().enumerate() {
+            if value == 0 {
+                continue;
+            }
+            let font = if givens.cells[y][x] != 0 { "F2" } else { "F1" };
+            let cx = left + (x as f64 + 0.5) * cell - DIGIT_WIDTH * size / 2.0;
+            let cy = bottom + side - (y as f64 + 0.5) * cell - 0.35 * size;
+            text_at(content, font, size, cx, cy, &value.to_string());
+        }
+    }
+}
+
+/// 在 (x, y) 写一行文字（PDF 坐标，原点在左下角）
+fn text_at(content: &mut String, font: &str, size: f64, x: f64, y: f64, text: &str) {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('(', "\\(")
+        .replace(')', "\\)");
+    let _ = writeln!(
+        content,
+        "BT /{} {:.2} Tf {:.2} {:.2} Td ({}) Tj ET",
+        font, size, x, y, escaped
+    );
+}
+
+/// 组装 PDF 文件：目录、页树、两种字体，再是每页的页面对象与内容流，最后是交叉引用表
+fn document(pages: &[String]) -> Vec<u8> {
+    // 对象编号：1 目录、2 页树、3 / 4 字体，之后每页占两个（页面、内容流）
+    let page_id = |i: usize| 5 + 2 * i;
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|i| format!("{} 0 R", page_id(i)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>".to_string(),
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_W,
+            PAGE_H,
+            page_id(i) + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+    let xref = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+    pdf.into_bytes()
+}
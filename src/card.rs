@@ -0,0 +1,199 @@
+//! 成绩卡片：解出后生成一张小 PNG（难度、用时、错误与提示次数），发到聊天里炫耀而不剧透盘面。
+//!
+//! 文字用界面字体（rusttype 栅格化）画在浅色底上，左侧是一个空的九宫格图标，
+//! 左边缘的色条随难度变化。系统剪贴板只支持文字，所以复制的是同样内容的一行文字，
+//! 图片从保存的文件发送。
+
+use crate::assets::{self, FONT_FILE};
+use crate::gameboard_controller::{format_time, GameboardController};
+use crate::paths;
+use crate::persist;
+use rusttype::{point, Font, Scale};
+use std::io;
+use std::path::{Path, PathBuf};
+use sudoku_core::Difficulty;
+
+/// 默认的卡片文件
+const DEFAULT_CARD_FILE: &str = "sudoku-card.png";
+
+/// 卡片大小（像素）
+const WIDTH: usize = 480;
+const HEIGHT: usize = 200;
+
+const BACKGROUND: [u8; 3] = [250, 250, 252];
+const INK: [u8; 3] = [20, 20, 28];
+const MUTED: [u8; 3] = [100, 100, 112];
+const GRID: [u8; 3] = [170, 172, 190];
+
+/// 默认的卡片位置（数据目录中）
+pub fn default_path() -> PathBuf {
+    paths::data_file(DEFAULT_CARD_FILE)
+}
+
+/// 一局的成绩（不含盘面）
+#[derive(Clone, Debug, PartialEq)]
+pub struct Card {
+    /// 标题：Sudoku，每日题加上日期，变体与单词数独加上名称
+    pub title: String,
+    pub difficulty: Difficulty,
+    pub seconds: f64,
+    pub mistakes: u32,
+    pub hints: usize,
+}
+
+impl Card {
+    /// 当前标签页刚解出的一局；`difficulty` 为题目的难度评级
+    pub fn new(tab: &GameboardController, difficulty: Difficulty) -> Self {
+        let mut title = "Sudoku".to_string();
+        if let Some(variant) = &tab.variant {
+            title = format!("{} {}", capitalized(variant.kind().name()), title);
+        } else if tab.gameboard.letters.is_some() {
+            title = "Wordoku".to_string();
+        }
+        if let Some(date) = &tab.daily {
+            title = format!("{} {}", title, date);
+        }
+        Card {
+            title,
+            difficulty,
+            seconds: tab.elapsed,
+            mistakes: tab.mistakes,
+            hints: tab.hints.len(),
+        }
+    }
+
+    /// 错误与提示次数，如 `0 mistakes · 1 hint`
+    fn counts(&self) -> String {
+        let count = |n: usize, what: &str| match n {
+            1 => format!("1 {}", what),
+            n => format!("{} {}s", n, what),
+        };
+        format!(
+            "{} · {}",
+            count(self.mistakes as usize, "mistake"),
+            count(self.hints, "hint")
+        )
+    }
+
+    /// 复制到剪贴板的一行文字，如 `Sudoku · Hard · 05:32 · 0 mistakes · 1 hint`
+    pub fn text(&self) -> String {
+        format!(
+            "{} · {} · {} · {}",
+            self.title,
+            capitalized(self.difficulty.name()),
+            format_time(self.seconds),
+            self.counts()
+        )
+    }
+
+    /// 画出卡片（RGB，按行存放）
+    fn render(&self, font: &Font) -> Vec<u8> {
+        let mut pixels = BACKGROUND.repeat(WIDTH * HEIGHT);
+        fill(&mut pixels, [0, 0, 8, HEIGHT], accent(self.difficulty));
+
+        // 空的九宫格图标：外框与宫线粗，格线细
+        let (left, top, cell) = (40, 52, 11);
+        for i in 0..=9 {
+            let width = if i % 3 == 0 { 2 } else { 1 };
+            let offset = i * cell;
+            fill(&mut pixels, [left + offset, top, width, 9 * cell + 2], GRID);
+            fill(&mut pixels, [left, top + offset, 9 * cell + 2, width], GRID);
+        }
+
+        let x = 172.0;
+        draw_text(&mut pixels, font, &self.title, 26.0, [x, 58.0], MUTED);
+        let time = format_time(self.seconds);
+        draw_text(&mut pixels, font, &time, 64.0, [x, 126.0], INK);
+        let details = format!(
+            "{} · {}",
+            capitalized(self.difficulty.name()),
+            self.counts()
+        );
+        draw_text(&mut pixels, font, &details, 20.0, [x, 160.0], MUTED);
+        pixels
+    }
+
+    /// 用界面字体画出卡片并保存为 PNG
+    pub fn save_png(&self, path: &Path) -> io::Result<()> {
+        let not_found = || {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("font {} not found", FONT_FILE),
+            )
+        };
+        let bytes = assets::read(FONT_FILE).ok_or_else(not_found)?;
+        let font = Font::try_from_vec(bytes).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid font {}", FONT_FILE),
+            )
+        })?;
+        let pixels = self.render(&font);
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, WIDTH as u32, HEIGHT as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(io::Error::other)?;
+        persist::write_atomic(path, png)
+    }
+}
+
+/// 首字母大写
+fn capitalized(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// 左边缘色条的颜色：容易为绿色，越难越偏红
+fn accent(difficulty: Difficulty) -> [u8; 3] {
+    match difficulty {
+        Difficulty::Easy => [70, 170, 90],
+        Difficulty::Medium => [70, 120, 220],
+        Difficulty::Hard => [235, 145, 40],
+        Difficulty::Expert => [210, 60, 60],
+    }
+}
+
+/// 用 `color` 填满矩形 [x, y, w, h]（超出卡片的部分忽略）
+fn fill(pixels: &mut [u8], [x, y, w, h]: [usize; 4], color: [u8; 3]) {
+    for py in y..(y + h).min(HEIGHT) {
+        for px in x..(x + w).min(WIDTH) {
+            let i = (py * WIDTH + px) * 3;
+            pixels[i..i + 3].copy_from_slice(&color);
+        }
+    }
+}
+
+/// 从基线左端 `origin` 起写一行字，按字形覆盖率与底色混合
+fn draw_text(
+    pixels: &mut [u8],
+    font: &Font,
+    text: &str,
+    size: f32,
+    origin: [f32; 2],
+    color: [u8; 3],
+) {
+    let start = point(origin[0], origin[1]);
+    for glyph in font.layout(text, Scale::uniform(size), start) {
+        let Some(bounds) = glyph.pixel_bounding_box() else {
+            continue;
+        };
+        glyph.draw(|gx, gy, coverage| {
+            let (px, py) = (bounds.min.x + gx as i32, bounds.min.y + gy as i32);
+            if px < 0 || py < 0 || px as usize >= WIDTH || py as usize >= HEIGHT {
+                return;
+            }
+            let i = (py as usize * WIDTH + px as usize) * 3;
+            for (channel, &ink) in pixels[i..i + 3].iter_mut().zip(&color) {
+                let blended = *channel as f32 + (ink as f32 - *channel as f32) * coverage;
+                *channel = blended.round() as u8;
+            }
+        });
+    }
+}
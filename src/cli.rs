@@ -0,0 +1,247 @@
+//! 命令行参数解析（clap）。
+
+use crate::gameboard_view::Theme;
+use crate::server;
+use crate::share;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use sudoku_core::wordoku;
+use sudoku_core::{Difficulty, Format, Gameboard, Gem, VariantKind, MAX_HOLES};
+
+/// Sudoku game
+#[derive(Parser, Debug)]
+#[command(name = "sudoku", version, about)]
+pub struct Args {
+    /// Run a headless command instead of opening the game window
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Difficulty of the generated puzzle (easy, medium, hard, expert)
+    #[arg(long)]
+    pub difficulty: Option<Difficulty>,
+
+    /// Seed for reproducible puzzle generation
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Number of empty cells (overrides --difficulty)
+    #[arg(long, value_parser = parse_holes)]
+    pub holes: Option<usize>,
+
+    /// Start with this puzzle (81 chars, `0` or `.` for blanks)
+    #[arg(long, value_parser = parse_puzzle, conflicts_with = "load")]
+    pub puzzle: Option<Gameboard>,
+
+    /// Open a shared puzzle link (sudoku://puzzle/CODE, a web link or a bare share code)
+    #[arg(value_name = "LINK", value_parser = share::parse)]
+    #[arg(conflicts_with_all = ["puzzle", "load", "daily", "join"])]
+    pub link: Option<Gameboard>,
+
+    /// Start with the daily (or weekly) puzzle from the configured URL
+    #[arg(long, conflicts_with_all = ["load", "puzzle", "join"])]
+    pub daily: bool,
+
+    /// Play a Sudoku variant: sandwich (clues outside the board give the sum of the
+    /// digits between each row's and column's 1 and 9) or thermo (digits increase
+    /// along each thermometer from the bulb)
+    #[arg(long, value_name = "KIND")]
+    #[arg(conflicts_with_all = ["load", "puzzle", "link", "daily", "host", "join", "tui"])]
+    pub variant: Option<VariantKind>,
+
+    /// Play Wordoku: the letters of a nine-letter word (all different) replace the
+    /// digits, and the word shows up on the diagonal once solved. Without WORD a
+    /// random word is used
+    #[arg(long, value_name = "WORD", num_args = 0..=1, value_parser = wordoku::parse_word)]
+    #[arg(conflicts_with_all = ["load", "puzzle", "link", "daily", "variant", "host", "join"])]
+    pub wordoku: Option<Option<[char; 9]>>,
+
+    /// Resume a game from a save file
+    #[arg(long, value_name = "SAVEFILE")]
+    pub load: Option<PathBuf>,
+
+    /// Watch a recorded game from a .sdreplay file
+    #[arg(long, value_name = "REPLAY")]
+    pub replay: Option<PathBuf>,
+
+    /// Import a puzzle from a photo and open it in the editor for correction
+    #[cfg(feature = "ocr")]
+    #[arg(long, value_name = "IMAGE")]
+    pub photo: Option<PathBuf>,
+
+    /// Play in the terminal instead of opening a window
+    #[arg(long, conflicts_with_all = ["host", "join"])]
+    pub tui: bool,
+
+    /// Host a LAN race on PORT (default 7878); others join with --join
+    #[arg(long, value_name = "PORT", num_args = 0..=1, default_missing_value = "7878")]
+    pub host: Option<u16>,
+
+    /// With --host: everyone edits one shared board instead of racing
+    #[arg(long, requires = "host")]
+    pub coop: bool,
+
+    /// Join a LAN game at HOST[:PORT]
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["host", "load", "puzzle"])]
+    pub join: Option<String>,
+
+    /// Player name shown to other players
+    #[arg(long, default_value = "player")]
+    pub name: String,
+
+    /// Color theme (light, dark); defaults to the config file setting
+    #[arg(long)]
+    pub theme: Option<Theme>,
+
+    /// Fill cells with nine colors instead of digits and pick them from a palette
+    /// (saved to the config file like the "Color mode" command)
+    #[arg(long)]
+    pub colors: bool,
+
+    /// Extra puzzle pack file or directory (can be repeated)
+    #[arg(long, value_name = "PATH")]
+    pub pack: Vec<PathBuf>,
+}
+
+/// Headless subcommands (no window). PUZZLE arguments accept an 81-char
+/// string, a share link or code, a file with one puzzle per line (or a single
+/// `.ss` grid or CSV board), or `-` for stdin.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate puzzles and print them (81-char lines by default)
+    Generate {
+        /// Difficulty (easy, medium, hard, expert)
+        #[arg(long)]
+        difficulty: Option<Difficulty>,
+        /// Number of empty cells (overrides --difficulty)
+        #[arg(long, value_parser = parse_holes)]
+        holes: Option<usize>,
+        /// Only minimal puzzles of this class (pearl, diamond); can take a while
+        #[arg(long, conflicts_with_all = ["difficulty", "holes"])]
+        gem: Option<Gem>,
+        /// Seed for reproducible output
+        #[arg(long)]
+        seed: Option<u64>,
+        /// How many puzzles to generate
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// Output format (line, grid, ss, csv, code)
+        #[arg(long, default_value_t = Format::Line)]
+        format: Format,
+        /// Write distinct puzzles to FILE (generated in parallel) and their solutions
+        /// to FILE-solutions, instead of printing them
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Solve puzzles and print the solutions
+    Solve {
+        #[arg(value_name = "PUZZLE")]
+        input: String,
+        /// Output format (line, grid, ss, csv, code)
+        #[arg(long, default_value_t = Format::Line)]
+        format: Format,
+        /// Also print solver statistics (nodes, backtracks, time)
+        #[arg(long)]
+        stats: bool,
+        /// Also print the number of solutions (counted up to 1000)
+        #[arg(long)]
+        count: bool,
+    },
+    /// Print the difficulty rating of puzzles
+    Rate {
+        #[arg(value_name = "PUZZLE")]
+        input: String,
+        /// Also print the backdoor size: cells to guess before singles finish the puzzle
+        #[arg(long)]
+        backdoor: bool,
+        /// Let the solver use unique rectangles (only valid for single-solution puzzles)
+        #[arg(long)]
+        unique_rectangles: bool,
+        /// Also print the hardest technique, clue count and whether the solution is unique
+        #[arg(long)]
+        details: bool,
+    },
+    /// Check grids for conflicts and solvability (exit code 1 on problems)
+    Check {
+        #[arg(value_name = "PUZZLE")]
+        input: String,
+        /// Also print solver statistics (nodes, backtracks, time)
+        #[arg(long)]
+        stats: bool,
+    },
+    /// Print the current daily (or weekly) puzzle
+    Daily,
+    /// Print the leaderboard for the current daily (or weekly) puzzle
+    Leaderboard,
+    /// Generate puzzles and lay them out with their answers as a printable PDF booklet
+    Booklet {
+        /// PDF file to write
+        #[arg(value_name = "FILE")]
+        out: PathBuf,
+        /// How many puzzles
+        #[arg(long, default_value_t = 12)]
+        count: usize,
+        /// Difficulty of every puzzle (default: from easy to expert)
+        #[arg(long)]
+        difficulty: Option<Difficulty>,
+        /// Seed for a reproducible booklet
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Print share links for puzzles
+    Share {
+        #[arg(value_name = "PUZZLE")]
+        input: String,
+    },
+    /// Serve generate / solve / rate / validate as a JSON HTTP API
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = server::DEFAULT_PORT)]
+        port: u16,
+        /// Address to listen on (use 0.0.0.0 to accept other machines)
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+    /// Write theme, key bindings and assistance settings to a file
+    ExportSettings {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Load settings written by export-settings into the config file
+    ImportSettings {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+}
+
+impl Args {
+    /// 生成新题时使用的空格数量：--holes 优先，其次 --difficulty，最后是配置中的默认难度
+    pub fn holes(&self, default: Difficulty) -> usize {
+        self.holes
+            .unwrap_or_else(|| self.difficulty.unwrap_or(default).holes())
+    }
+
+    /// 生成新题时要求的难度评级：指定了 --holes 时不评级
+    pub fn target(&self, default: Difficulty) -> Option<Difficulty> {
+        self.holes
+            .is_none()
+            .then(|| self.difficulty.unwrap_or(default))
+    }
+
+    /// 指定的题面：--puzzle 或分享链接
+    pub fn puzzle(&self) -> Option<&Gameboard> {
+        self.puzzle.as_ref().or(self.link.as_ref())
+    }
+}
+
+fn parse_holes(s: &str) -> Result<usize, String> {
+    let holes: usize = s.parse().map_err(|e| format!("{}", e))?;
+    // 至少保留 17 个提示数（唯一解的理论下限）
+    if holes > MAX_HOLES {
+        return Err(format!("holes must be at most {}", MAX_HOLES));
+    }
+    Ok(holes)
+}
+
+fn parse_puzzle(s: &str) -> Result<Gameboard, String> {
+    Gameboard::from_line(s).map_err(|e| e.to_string())
+}
@@ -0,0 +1,433 @@
+//! 无窗口的命令行子命令：generate / solve / rate / check / booklet / daily / leaderboard / share / serve，以及设置的导出 / 导入。
+
+use crate::booklet;
+use crate::cli::Command;
+use crate::config::Config;
+use crate::daily::{self, Date};
+use crate::history::History;
+use crate::leaderboard::Leaderboard;
+use crate::pack;
+use crate::persist;
+use crate::server;
+use crate::share;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use sudoku_core::{
+    backdoor_size, rate_with, Difficulty, Format, Gameboard, Gem, SolveStats, GEM_BUDGET,
+    MAX_BACKDOOR, RATED_BUDGET,
+};
+
+/// `generate --out` 去重后仍不够数时最多补生成的轮数
+const MAX_ROUNDS: usize = 10;
+
+/// `solve --count` 最多数到的解的个数
+const COUNT_LIMIT: usize = 1000;
+
+/// 执行子命令，返回进程退出码
+pub fn run(command: Command) -> i32 {
+    let result = match command {
+        Command::Generate {
+            difficulty,
+            holes,
+            gem,
+            seed,
+            count,
+            format,
+            out,
+        } => {
+            let spec = match (gem, holes) {
+                (Some(gem), _) => Spec::Gem(gem),
+                (None, Some(holes)) => Spec::Holes(holes),
+                (None, None) => Spec::Rated(difficulty.unwrap_or_default()),
+            };
+            match out {
+                Some(path) => generate_to_file(spec, seed, count, format, &path),
+                None => Ok(generate(spec, seed, count, format)),
+            }
+        }
+        Command::Solve {
+            input,
+            format,
+            stats,
+            count,
+        } => read_puzzles(&input).map(|p| solve(&p, format, stats, count)),
+        Command::Rate {
+            input,
+            backdoor,
+            unique_rectangles,
+            details,
+        } => read_puzzles(&input).map(|p| rate_all(&p, backdoor, unique_rectangles, details)),
+        Command::Check { input, stats } => read_puzzles(&input).map(|p| check(&p, stats)),
+        Command::Daily => {
+            let daily = daily::fetch(&Config::load().online, Date::today());
+            let source = if daily.online { "online" } else { "local" };
+            println!("# {} ({})", daily.key, source);
+            println!("{}", daily.puzzle.to_line());
+            Ok(0)
+        }
+        Command::Leaderboard => {
+            leaderboard();
+            Ok(0)
+        }
+        Command::Booklet {
+            out,
+            count,
+            difficulty,
+            seed,
+        } => booklet(&out, count, difficulty, seed),
+        Command::Share { input } => read_puzzles(&input).map(|puzzles| {
+            let online = Config::load().online;
+            for puzzle in &puzzles {
+                println!("{}", share::link(&online, puzzle));
+            }
+            0
+        }),
+        Command::Serve { port, bind } => server::run(&bind, port)
+            .map(|()| 0)
+            .map_err(|e| format!("{}:{}: {}", bind, port, e)),
+        Command::ExportSettings { file } => export_settings(&file),
+        Command::ImportSettings { file } => import_settings(&file),
+    };
+    result.unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        2
+    })
+}
+
+/// 读取题目：81 字符字符串、分享链接、文件路径（每行一题）或 `-`（标准输入）；
+/// 文件或标准输入不是每行一题时按单个 `.ss` 网格或 CSV 读取
+pub fn read_puzzles(input: &str) -> Result<Vec<Gameboard>, String> {
+    let text = if input == "-" {
+        let mut buf = String::new();
+        io::stdin()
+            .read_to_string(&mut buf)
+            .map_err(|e| format!("stdin: {}", e))?;
+        buf
+    } else if Path::new(input).is_file() {
+        fs::read_to_string(input).map_err(|e| format!("{}: {}", input, e))?
+    } else {
+        return share::parse(input).map(|b| vec![b]);
+    };
+    pack::parse_lines(&text).or_else(|err| {
+        Gameboard::from_ss(&text)
+            .or_else(|_| Gameboard::from_csv(&text))
+            .map(|b| vec![b])
+            .map_err(|_| err)
+    })
+}
+
+/// 打印当期排行榜（格式同服务器返回的文本，首行注明来源）
+fn leaderboard() {
+    let online = Config::load().online;
+    let daily = daily::fetch(&online, Date::today());
+    let history = History::open_default().ok();
+    let mut board = Leaderboard::load(&online, daily.key, &daily.puzzle, None, history.as_ref());
+    while board.is_loading() {
+        thread::sleep(Duration::from_millis(50));
+        board.poll();
+    }
+    let source = if board.online { "online" } else { "local" };
+    println!("# {} ({})", board.key, source);
+    for entry in &board.entries {
+        println!("{:.1} {}", entry.seconds, entry.name);
+    }
+}
+
+/// 要生成的题目
+#[derive(Clone, Copy)]
+enum Spec {
+    /// 指定空格数，不评级（`--holes`）
+    Holes(usize),
+    /// 按难度评级生成，与游戏中的新题相同（每题最多尝试 `RATED_BUDGET`）
+    Rated(Difficulty),
+    /// 只要珍珠 / 钻石题（极少见，每题最多找 `GEM_BUDGET`）
+    Gem(Gem),
+}
+
+/// 珍珠 / 钻石题找不到时退出码为 1
+fn generate(spec: Spec, seed: Option<u64>, count: usize, format: Format) -> i32 {
+    let mut seeded = seed.map(StdRng::seed_from_u64);
+    for _ in 0..count {
+        let board = match seeded.as_mut() {
+            Some(rng) => generate_one(spec, rng),
+            None => generate_one(spec, &mut thread_rng()),
+        };
+        match board {
+            Ok(board) => println!("{}", board.to_format(format)),
+            Err(err) => {
+                eprintln!("{}", err);
+                return 1;
+            }
+        }
+    }
+    0
+}
+
+/// 并行生成 `count` 道互不相同的题目，写入 `path`，答案写入同目录的 `<名字>-solutions` 文件。
+/// 指定 `seed` 时第 i 道题的随机数由 `seed + i` 决定，结果与线程数无关
+fn generate_to_file(
+    spec: Spec,
+    seed: Option<u64>,
+    count: usize,
+    format: Format,
+    path: &Path,
+) -> Result<i32, String> {
+    let mut seen = HashSet::new();
+    let mut puzzles = Vec::with_capacity(count);
+    let mut next = 0;
+    for _ in 0..MAX_ROUNDS {
+        if puzzles.len() == count {
+            break;
+        }
+        let missing = count - puzzles.len();
+        let batch = parallel(next..next + missing, |i| match seed {
+            Some(seed) => generate_one(
+                spec,
+                &mut StdRng::seed_from_u64(seed.wrapping_add(i as u64)),
+            ),
+            None => generate_one(spec, &mut thread_rng()),
+        });
+        for board in batch.into_iter().collect::<Result<Vec<_>, _>>()? {
+            if seen.insert(board.to_line()) {
+                puzzles.push(board);
+            }
+        }
+        next += missing;
+    }
+    if puzzles.len() < count {
+        eprintln!("only {} distinct puzzles generated", puzzles.len());
+    }
+
+    let solutions: Vec<Gameboard> = puzzles
+        .iter()
+        .map(|puzzle| {
+            let mut solution = puzzle.clone();
+            solution.solve();
+            solution
+        })
+        .collect();
+    let solutions_path = solutions_path(path);
+    let write = |path: &Path, boards: &[Gameboard]| {
+        let text: String = boards
+            .iter()
+            .map(|board| board.to_format(format) + "\n")
+            .collect();
+        persist::write_atomic(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    };
+    write(path, &puzzles)?;
+    write(&solutions_path, &solutions)?;
+    println!(
+        "{} puzzles written to {}, solutions to {}",
+        puzzles.len(),
+        path.display(),
+        solutions_path.display()
+    );
+    Ok(0)
+}
+
+/// 用全部 CPU 核心对 `indices` 中的每个序号调用 `make`（生成题目），按序号返回
+fn parallel<T, F>(indices: Range<usize>, make: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+{
+    let indices: Vec<usize> = indices.collect();
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = indices.len().div_ceil(workers).max(1);
+    let make = &make;
+    thread::scope(|scope| {
+        let handles: Vec<_> = indices
+            .chunks(chunk)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(|&i| make(i)).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("generator thread panicked"))
+            .collect()
+    })
+}
+
+/// 生成 `count` 道题排成 PDF 小册子；没有指定难度时从 easy 逐渐到 expert
+fn booklet(
+    path: &Path,
+    count: usize,
+    difficulty: Option<Difficulty>,
+    seed: Option<u64>,
+) -> Result<i32, String> {
+    let entries = parallel(0..count, |i| {
+        let target = difficulty.unwrap_or(Difficulty::ALL[i * Difficulty::ALL.len() / count]);
+        let (puzzle, difficulty) = match seed {
+            Some(seed) => {
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
+                Gameboard::generate_rated_with_rng(target, RATED_BUDGET, &mut rng)
+            }
+            None => Gameboard::generate_rated_with_rng(target, RATED_BUDGET, &mut thread_rng()),
+        };
+        let mut solution = puzzle.clone();
+        solution.solve();
+        booklet::Entry {
+            puzzle,
+            solution,
+            difficulty,
+        }
+    });
+    booklet::write(path, "Sudoku", &entries).map_err(|e| format!("{}: {}", path.display(), e))?;
+    println!("{} puzzles written to {}", entries.len(), path.display());
+    Ok(0)
+}
+
+/// 答案文件：`pack.sdm` → `pack-solutions.sdm`
+fn solutions_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}-solutions.{}", stem, ext.to_string_lossy()),
+        None => format!("{}-solutions", stem),
+    };
+    path.with_file_name(name)
+}
+
+fn generate_one<R: Rng>(spec: Spec, rng: &mut R) -> Result<Gameboard, String> {
+    match spec {
+        Spec::Holes(holes) => Ok(Gameboard::generate_with_rng(holes, rng)),
+        Spec::Rated(target) => Ok(Gameboard::generate_rated_with_rng(target, RATED_BUDGET, rng).0),
+        Spec::Gem(gem) => Gameboard::generate_gem_with_rng(gem, GEM_BUDGET, rng)
+            .ok_or_else(|| format!("no {} found within {}s", gem, GEM_BUDGET.as_secs())),
+    }
+}
+
+/// `stats` 时在每个结果后输出求解器的统计（以 `#` 开头，与每日题的标题行相同）
+/// `count` 时在每个结果后输出解的个数（最多数到 `COUNT_LIMIT`）
+fn solve(puzzles: &[Gameboard], format: Format, stats: bool, count: bool) -> i32 {
+    let mut code = 0;
+    for puzzle in puzzles {
+        let mut board = puzzle.clone();
+        let (solved, solver) = if puzzle.conflicting_cells().is_empty() {
+            board.solve_with_stats()
+        } else {
+            (false, SolveStats::default())
+        };
+        if solved {
+            println!("{}", board.to_format(format));
+        } else {
+            println!("no solution");
+            code = 1;
+        }
+        if stats {
+            println!("# solve: {}", solver);
+        }
+        if count {
+            match puzzle.count_solutions(COUNT_LIMIT) {
+                COUNT_LIMIT => println!("# solutions: {}+", COUNT_LIMIT),
+                n => println!("# solutions: {}", n),
+            }
+        }
+    }
+    code
+}
+
+/// `backdoor` 时在难度后加上后门大小（搜索较慢，默认不算）；`details` 时再加上最难的技巧、
+/// 提示数与解是否唯一，方便批量筛选题库
+fn rate_all(puzzles: &[Gameboard], backdoor: bool, unique_rectangles: bool, details: bool) -> i32 {
+    for puzzle in puzzles {
+        let rating = rate_with(puzzle, unique_rectangles);
+        let mut difficulty = match rating.gem {
+            Some(gem) => format!("{}, {}", rating.difficulty, gem),
+            None => rating.difficulty.to_string(),
+        };
+        if details {
+            let hardest = rating.hardest.map_or("none", |t| t.name());
+            let logic = if rating.solved_by_logic {
+                ""
+            } else {
+                " (then guessing)"
+            };
+            // count_solutions 对已有冲突的盘面返回 0
+            let solutions = puzzle.count_solutions(2);
+            difficulty = format!(
+                "{}, hardest {}{}, {} clues, {}",
+                difficulty,
+                hardest,
+                logic,
+                puzzle.filled_count(),
+                match solutions {
+                    0 => "no solution",
+                    1 => "unique solution",
+                    _ => "multiple solutions",
+                }
+            );
+        }
+        if !backdoor {
+            println!("{}", difficulty);
+            continue;
+        }
+        match backdoor_size(puzzle) {
+            Some(size) => println!("{}, backdoor {}", difficulty, size),
+            None if puzzle.has_unique_solution() => {
+                println!("{}, backdoor > {}", difficulty, MAX_BACKDOOR)
+            }
+            None => println!("{}, backdoor unknown (no unique solution)", difficulty),
+        }
+    }
+    0
+}
+
+fn check(puzzles: &[Gameboard], stats: bool) -> i32 {
+    let mut code = 0;
+    for puzzle in puzzles {
+        let conflicts = puzzle.conflicting_cells();
+        if !conflicts.is_empty() {
+            let cells: Vec<String> = conflicts
+                .iter()
+                .map(|&(r, c)| format!("r{}c{}", r + 1, c + 1))
+                .collect();
+            println!("conflicts: {}", cells.join(" "));
+            code = 1;
+        } else if puzzle.is_complete() {
+            println!("ok: complete");
+        } else {
+            let (count, counter) = puzzle.count_solutions_with_stats(2);
+            match count {
+                0 => {
+                    println!("no solution");
+                    code = 1;
+                }
+                1 => println!("ok: {}/81 filled, unique solution", puzzle.filled_count()),
+                _ => println!(
+                    "ok: {}/81 filled, multiple solutions",
+                    puzzle.filled_count()
+                ),
+            }
+            if stats {
+                println!("# count: {}", counter);
+            }
+        }
+    }
+    code
+}
+
+fn export_settings(path: &Path) -> Result<i32, String> {
+    Config::load()
+        .export_profile(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    println!("settings exported to {}", path.display());
+    Ok(0)
+}
+
+fn import_settings(path: &Path) -> Result<i32, String> {
+    let mut config = Config::load();
+    config
+        .import_profile(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    config
+        .save()
+        .map_err(|e| format!("cannot write config: {}", e))?;
+    println!("settings imported from {}", path.display());
+    Ok(0)
+}
@@ -0,0 +1,405 @@
+//! 配置文件：启动时从平台配置目录读取 `config.toml`，设置变化时写回。
+//!
+//! ```toml
+//! version = 1
+//! difficulty = "medium"
+//! theme = "light"
+//! scale = 1.0
+//! telemetry = false
+//! auto_pause = "blank"
+//! idle_timeout = 120.0
+//!
+//! [window]
+//! width = 640
+//! height = 750
+//! x = 100
+//! y = 80
+//!
+//! [assist]
+//! highlight_conflicts = true
+//! candidate_warning = true
+//! strict = false
+//! screen_reader = false
+//! unique_rectangles = false
+//! reduce_motion = false
+//! colors = false
+//!
+//! [keys]
+//! undo = "U"
+//! redo = "Y"
+//! reset = "R"
+//! new_game = "G"
+//! save = "S"
+//! toggle_theme = "T"
+//! pause = "P"
+//! hint = "H"
+//! confirm_hint = "Return"
+//! show_all = "A"
+//! check_cell = "C"
+//! check_all = "V"
+//! clear_region = "E"
+//! submit = "X"
+//! explain = "W"
+//! find_mistake = "F"
+//! branch = "B"
+//! commit_branch = "K"
+//! discard_branch = "D"
+//! diagnostics = "F3"
+//! conflicts = "L"
+//! help = "F1"
+//!
+//! [online]
+//! puzzle_url = "https://example.com/sudoku/{date}.txt"
+//! period = "daily"
+//! share_url = "https://example.com/sudoku/?p={code}"
+//! leaderboard_url = "https://example.com/leaderboard/{date}"
+//! submit_times = false
+//! name = "alice"
+//! check_updates = false
+//!
+//! [audio]
+//! music = false
+//! volume = 0.5
+//! music_file = ""
+//!
+//! [gamepad]
+//! place = 0
+//! clear = 1
+//! prev_digit = 2
+//! next_digit = 3
+//! undo = 4
+//! hint = 5
+//! pause = 7
+//! ```
+//!
+//! `sudoku export-settings FILE` / `sudoku import-settings FILE` 把难度、主题、界面缩放、辅助功能与快捷键
+//! 导出为单个文件（格式同上，不含窗口位置），方便在不同机器间迁移。
+
+use crate::audio::AudioConfig;
+use crate::daily::OnlineConfig;
+use crate::gameboard_view::Theme;
+use crate::gamepad::GamepadConfig;
+use crate::paths;
+use crate::persist;
+use piston::input::Key;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use sudoku_core::Difficulty;
+
+/// 配置文件名
+const CONFIG_FILE: &str = "config.toml";
+
+/// 配置文件格式版本
+const CONFIG_VERSION: u32 = 1;
+
+/// 设置导出文件的格式版本
+const PROFILE_VERSION: u32 = 1;
+
+/// 全部用户配置（缺失的字段使用默认值）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 格式版本（没有此字段的旧配置为 0）
+    pub version: u32,
+    /// 新题默认难度
+    pub difficulty: Difficulty,
+    /// 颜色主题
+    pub theme: Theme,
+    /// 界面缩放（0.75 – 2.0），作用于字体、按钮、HUD 与边距；游戏中 Ctrl+= / Ctrl+- 调整
+    pub scale: f64,
+    /// 在本机记录汇总的使用统计（默认关闭，见 `telemetry.rs`）
+    pub telemetry: bool,
+    /// 窗口失去焦点（切到别的程序或最小化）时是否自动暂停
+    pub auto_pause: AutoPause,
+    /// 多少秒没有任何输入后停止计时（离开座位时不计入用时），0 为不检测
+    pub idle_timeout: f64,
+    /// 窗口大小
+    pub window: WindowConfig,
+    /// 辅助功能
+    pub assist: Assist,
+    /// 快捷键
+    pub keys: KeyBindings,
+    /// 在线功能（每日题、分享链接、排行榜、检查更新）
+    pub online: OnlineConfig,
+    /// 背景音乐
+    pub audio: AudioConfig,
+    /// 手柄按键
+    pub gamepad: GamepadConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            difficulty: Difficulty::default(),
+            theme: Theme::Light,
+            scale: 1.0,
+            telemetry: false,
+            auto_pause: AutoPause::Blank,
+            idle_timeout: 120.0,
+            window: WindowConfig::default(),
+            assist: Assist::default(),
+            keys: KeyBindings::default(),
+            online: OnlineConfig::default(),
+            audio: AudioConfig::default(),
+            gamepad: GamepadConfig::default(),
+        }
+    }
+}
+
+/// 窗口失去焦点时的自动暂停方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoPause {
+    /// 进入暂停界面并遮住棋盘，回到窗口时自动继续
+    Blank,
+    /// 只停止计时，棋盘照常显示
+    Timer,
+    /// 不自动暂停
+    Off,
+}
+
+/// 窗口大小与位置（像素）；退出时自动记录，下次启动恢复
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    /// 窗口左上角位置（后端不支持时为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        // 初始窗口设置为纵向更高，确保棋盘下方的按钮可见
+        Self {
+            width: 640,
+            height: 750,
+            x: None,
+            y: None,
+        }
+    }
+}
+
+/// 辅助功能设置
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Assist {
+    /// 提交前用浅红背景标出与同行/列/宫冲突的输入
+    pub highlight_conflicts: bool,
+    /// 填入的数字符合规则、但已不在该格的候选数中时短暂闪烁提醒（不阻止输入）
+    pub candidate_warning: bool,
+    /// 严格模式：拒绝违反同行/列/宫规则的数字（不写入，该格闪烁红色）
+    pub strict: bool,
+    /// 把选中格与盘面的变化以文字输出到终端，供终端读屏软件朗读（见 `accessibility.rs`）；
+    /// 系统读屏软件经 `screen_reader.rs` 接入，不受此项影响
+    pub screen_reader: bool,
+    /// 逻辑推理（选中格解释、对局记录的难度）也用唯一矩形；只对唯一解的题目成立，默认关闭
+    pub unique_rectangles: bool,
+    /// 减少动画：选中格高亮直接跳到新格子，不滑动
+    pub reduce_motion: bool,
+    /// 色块模式：用九种颜色代替数字（规则不变），棋盘旁显示调色板供点选
+    pub colors: bool,
+}
+
+impl Default for Assist {
+    fn default() -> Self {
+        Self {
+            highlight_conflicts: true,
+            candidate_warning: true,
+            strict: false,
+            screen_reader: false,
+            unique_rectangles: false,
+            reduce_motion: false,
+            colors: false,
+        }
+    }
+}
+
+/// 全局快捷键（键名与 piston `Key` 枚举一致，如 "U"、"F1"）
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub undo: Key,
+    /// 重做撤销掉的输入
+    pub redo: Key,
+    pub reset: Key,
+    pub new_game: Key,
+    pub save: Key,
+    pub toggle_theme: Key,
+    /// 暂停（Esc 也可暂停）
+    pub pause: Key,
+    pub hint: Key,
+    /// 把显示的提示填入棋盘（也可以点击提示格）
+    pub confirm_hint: Key,
+    pub show_all: Key,
+    /// 只检查选中格的对错；Shift 加这个键只检查选中格所在的行（再按依次换成列、宫）
+    pub check_cell: Key,
+    /// 检查全部已填的格子（不锁定棋盘）
+    pub check_all: Key,
+    /// 清空多选的格子或选中格所在的行 / 列 / 宫中填写的数字（先确认）
+    pub clear_region: Key,
+    pub submit: Key,
+    /// 在侧栏解释选中格（哪些格子排除了哪些数字，求解器怎样推出这一格）
+    pub explain: Key,
+    /// 找出仍在棋盘上、最早填错的格子（只标出位置，不给正确值）
+    pub find_mistake: Key,
+    /// 开一个假设分支（之后的输入用紫色显示，可嵌套）
+    pub branch: Key,
+    /// 保留最内层假设分支中的输入
+    pub commit_branch: Key,
+    /// 放弃最内层假设分支，恢复开分支时的盘面
+    pub discard_branch: Key,
+    /// 在侧栏显示 / 隐藏求解器的统计（搜索节点数、回溯次数与用时）
+    pub diagnostics: Key,
+    /// 在侧栏显示 / 隐藏冲突列表（点击一条跳到对应的格子）
+    pub conflicts: Key,
+    /// 显示快捷键一览（Shift+H 也可以）
+    pub help: Key,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            undo: Key::U,
+            redo: Key::Y,
+            reset: Key::R,
+            new_game: Key::G,
+            save: Key::S,
+            toggle_theme: Key::T,
+            pause: Key::P,
+            hint: Key::H,
+            confirm_hint: Key::Return,
+            show_all: Key::A,
+            check_cell: Key::C,
+            check_all: Key::V,
+            clear_region: Key::E,
+            submit: Key::X,
+            explain: Key::W,
+            find_mistake: Key::F,
+            branch: Key::B,
+            commit_branch: Key::K,
+            discard_branch: Key::D,
+            diagnostics: Key::F3,
+            conflicts: Key::L,
+            help: Key::F1,
+        }
+    }
+}
+
+/// 导出 / 导入的设置包：与机器无关的部分（不含窗口大小与位置）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// 格式版本，比当前程序新的文件拒绝导入
+    pub version: u32,
+    pub difficulty: Difficulty,
+    pub theme: Theme,
+    pub scale: f64,
+    pub assist: Assist,
+    pub keys: KeyBindings,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Config::default().profile()
+    }
+}
+
+impl Config {
+    /// 当前配置中可导出的部分
+    pub fn profile(&self) -> Profile {
+        Profile {
+            version: PROFILE_VERSION,
+            difficulty: self.difficulty,
+            theme: self.theme,
+            scale: self.scale,
+            assist: self.assist,
+            keys: self.keys,
+        }
+    }
+
+    /// 用设置包覆盖对应的配置项
+    pub fn apply_profile(&mut self, profile: Profile) {
+        self.difficulty = profile.difficulty;
+        self.theme = profile.theme;
+        self.scale = profile.scale;
+        self.assist = profile.assist;
+        self.keys = profile.keys;
+    }
+
+    /// 把设置包写入 `path`
+    pub fn export_profile(&self, path: &Path) -> io::Result<()> {
+        let text = toml::to_string_pretty(&self.profile()).map_err(io::Error::other)?;
+        persist::write_atomic(path, text)
+    }
+
+    /// 读取 `path` 中的设置包并应用（文件中缺失的项使用默认值）
+    pub fn import_profile(&mut self, path: &Path) -> io::Result<()> {
+        let text = fs::read_to_string(path)?;
+        let profile: Profile =
+            toml::from_str(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        persist::check_version("settings", profile.version, PROFILE_VERSION)?;
+        self.apply_profile(profile);
+        Ok(())
+    }
+
+    /// 配置文件路径（Linux: ~/.config/sudoku/config.toml）
+    pub fn path() -> Option<PathBuf> {
+        paths::config_dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    /// 读取配置；文件不存在时返回默认值，格式错误时打印警告并返回默认值
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).unwrap_or_else(|err| {
+                eprintln!(
+                    "配置文件 {} 格式错误，使用默认配置: {}",
+                    path.display(),
+                    err
+                );
+                Self::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                eprintln!("无法读取配置文件 {}: {}", path.display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    /// 解析配置文本，旧版本迁移到当前版本
+    fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        let table: toml::Table = toml::from_str(text)?;
+        let version = table
+            .get("version")
+            .and_then(toml::Value::as_integer)
+            .unwrap_or(0);
+        if version > CONFIG_VERSION as i64 {
+            eprintln!(
+                "配置文件版本 {} 比程序支持的 {} 新，无法识别的设置将被忽略",
+                version, CONFIG_VERSION
+            );
+        }
+        // v0（没有 version 字段）与 v1 字段相同，无需转换；以后改动字段时在这里逐版本迁移
+        let mut config: Self = toml::Value::Table(table).try_into()?;
+        config.version = CONFIG_VERSION;
+        Ok(config)
+    }
+
+    /// 写回配置文件（自动创建目录，原子替换）
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        let text = toml::to_string_pretty(self).map_err(io::Error::other)?;
+        persist::write_atomic(&path, text)
+    }
+}
@@ -0,0 +1,102 @@
+//! 崩溃保护：主线程 panic（例如绘制或字体出错）时，先把正在进行的对局写入应急存档
+//! `sudoku-crash.sav`，再把崩溃报告写入 `sudoku-crash.txt`（都在数据目录，见 `paths.rs`），然后照常退出。
+//!
+//! 应急存档与 S 键保存的存档格式相同，用 `--load <数据目录>/sudoku-crash.sav` 继续。panic 时无法安全地访问
+//! 游戏状态，所以主循环每轮都通过 `remember` 记下当前标签页的存档内容（盘面没有变化时沿用上次的），
+//! panic 时只写出这份副本。
+//! 后台线程（生成题目、下载等）的 panic 不影响游戏，只按默认方式打印。
+
+use crate::gameboard_controller::GameboardController;
+use crate::paths;
+use crate::persist;
+use crate::savefile;
+use std::backtrace::Backtrace;
+use std::panic::{self, PanicHookInfo};
+use std::sync::Mutex;
+use std::thread;
+
+/// 应急存档文件名
+const CRASH_SAVE_FILE: &str = "sudoku-crash.sav";
+
+/// 崩溃报告文件名
+const CRASH_REPORT_FILE: &str = "sudoku-crash.txt";
+
+/// 最近一次记下的存档内容，与记下时的盘面
+static SNAPSHOT: Mutex<Option<(Fingerprint, String)>> = Mutex::new(None);
+
+/// 决定存档内容的盘面状态：题面、当前盘面（含 Wordoku 字母）与错误数。
+/// 用时每帧都在变，不计入；应急存档中的用时为最后一次改动盘面时的值
+#[derive(PartialEq)]
+struct Fingerprint {
+    initial: [[u8; 9]; 9],
+    cells: [[u8; 9]; 9],
+    letters: Option<[char; 9]>,
+    mistakes: u32,
+}
+
+impl Fingerprint {
+    fn of(controller: &GameboardController) -> Self {
+        Fingerprint {
+            initial: controller.initial_cells,
+            cells: controller.gameboard.cells,
+            letters: controller.gameboard.letters,
+            mistakes: controller.mistakes,
+        }
+    }
+}
+
+/// 安装 panic hook（只在窗口模式下调用）
+pub fn install() {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default(info);
+        if thread::current().name() == Some("main") {
+            rescue(info);
+        }
+    }));
+}
+
+/// 记下当前对局，panic 时写入应急存档；每个事件都会调用，盘面没有变化时什么也不做
+pub fn remember(controller: &GameboardController) {
+    let fingerprint = Fingerprint::of(controller);
+    // 只在这里短暂持有锁（panic 时用 try_lock，不会卡住）
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        if snapshot
+            .as_ref()
+            .is_none_or(|(last, _)| *last != fingerprint)
+        {
+            *snapshot = Some((fingerprint, savefile::contents(controller)));
+        }
+    }
+}
+
+/// 写出应急存档与崩溃报告；任何一步失败都只打印原因，不再 panic
+fn rescue(info: &PanicHookInfo) {
+    let snapshot = SNAPSHOT
+        .try_lock()
+        .ok()
+        .and_then(|snapshot| snapshot.as_ref().map(|(_, contents)| contents.clone()));
+    if let Some(contents) = snapshot {
+        let path = paths::data_file(CRASH_SAVE_FILE);
+        match persist::write_atomic(&path, contents) {
+            Ok(()) => eprintln!(
+                "游戏崩溃，当前对局已保存，用 --load {} 继续",
+                path.display()
+            ),
+            Err(err) => eprintln!("无法写入应急存档 {}: {}", path.display(), err),
+        }
+    }
+    let report = format!(
+        "sudoku {} ({} {})\n{}\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        info,
+        Backtrace::force_capture()
+    );
+    let path = paths::data_file(CRASH_REPORT_FILE);
+    match persist::write_atomic(&path, report) {
+        Ok(()) => eprintln!("崩溃报告已写入 {}", path.display()),
+        Err(err) => eprintln!("无法写入崩溃报告 {}: {}", path.display(), err),
+    }
+}
@@ -0,0 +1,218 @@
+//! 每日 / 每周题：从配置的网址下载当期题目，供社区进行同题挑战。
+//!
+//! 网址中的 `{date}` 替换为当期第一天（UTC，`YYYY-MM-DD`；每周题为当周周一），
+//! 返回的文本与题库文件相同，取第一道题：
+//! ```text
+//! # 2026-10-16
+//! 530070000600195000098000060800060003400803001700020006060000280000419005000080079
+//! ```
+//! 未配置网址、无法联网或未开启 `online` feature 时，按日期作为种子在本地生成，
+//! 所以离线的玩家拿到的同一天的题目也相同。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sudoku_core::{Difficulty, Gameboard};
+
+/// 下载超时（秒）
+#[cfg(feature = "online")]
+const TIMEOUT_SECS: u64 = 5;
+
+/// 出题周期
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+/// 日期（UTC），以 1970-01-01 起的天数表示
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date(pub i64);
+
+impl Date {
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Date((secs / 86_400) as i64)
+    }
+
+    /// 当期的第一天：每日题为当天，每周题为当周周一
+    pub fn period_start(self, period: Period) -> Self {
+        match period {
+            Period::Daily => self,
+            Period::Weekly => Date(self.0 - i64::from(self.weekday())),
+        }
+    }
+
+    /// 由 (年, 月, 日) 得到日期（Howard Hinnant 的 days_from_civil 算法）
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Self {
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y.rem_euclid(400);
+        let mp = i64::from((month + 9) % 12);
+        let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        Date(era * 146_097 + doe - 719_468)
+    }
+
+    /// 星期几，0 为星期一
+    pub fn weekday(self) -> u32 {
+        // 1970-01-01 是星期四
+        (self.0 + 3).rem_euclid(7) as u32
+    }
+
+    /// (年, 月, 日)
+    pub fn ymd(self) -> (i64, u32, u32) {
+        // Howard Hinnant 的 civil_from_days 算法
+        let z = self.0 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (y, m, d) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", y, m, d)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// 解析 `YYYY-MM-DD`
+    fn from_str(s: &str) -> Result<Self, String> {
+        let bad = || format!("bad date: {}", s);
+        let mut parts = s.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(bad);
+        let year: i64 = next()?.parse().map_err(|_| bad())?;
+        let month: u32 = next()?.parse().map_err(|_| bad())?;
+        let day: u32 = next()?.parse().map_err(|_| bad())?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(bad());
+        }
+        Ok(Date::from_ymd(year, month, day))
+    }
+}
+
+/// 连续完成的期数 (当前, 最长)；`done` 为已完成各期的第一天。
+/// 当前一期还没完成时从上一期往回数，不算中断
+pub fn streaks(done: &[Date], today: Date, period: Period) -> (usize, usize) {
+    let step = match period {
+        Period::Daily => 1,
+        Period::Weekly => 7,
+    };
+    let mut days = done.to_vec();
+    days.sort();
+    days.dedup();
+    let mut longest = 0;
+    let mut run = 0;
+    for (i, day) in days.iter().enumerate() {
+        run = if i > 0 && day.0 - days[i - 1].0 == step {
+            run + 1
+        } else {
+            1
+        };
+        longest = longest.max(run);
+    }
+    let mut day = today.period_start(period);
+    if days.binary_search(&day).is_err() {
+        day = Date(day.0 - step);
+    }
+    let mut current = 0;
+    while days.binary_search(&day).is_ok() {
+        current += 1;
+        day = Date(day.0 - step);
+    }
+    (current, longest)
+}
+
+/// 在线功能设置（配置文件 `[online]`）
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OnlineConfig {
+    /// 题目地址，`{date}` 替换为当期第一天；为空时只在本地生成
+    pub puzzle_url: String,
+    pub period: Period,
+    /// 分享链接模板，`{code}` 替换为分享码；为空时生成 `sudoku://` 链接（见 `share.rs`）
+    pub share_url: String,
+    /// 排行榜地址，`{date}` 替换为当期第一天；为空时只显示本机成绩（见 `leaderboard.rs`）
+    pub leaderboard_url: String,
+    /// 是否把每日题的用时提交到排行榜（默认关闭）
+    pub submit_times: bool,
+    /// 排行榜上显示的名字（也记入本机最佳成绩，可为空）
+    pub name: String,
+    /// 启动时检查 GitHub 上是否有新版本（默认关闭，见 `update.rs`）
+    pub check_updates: bool,
+}
+
+/// 当期题目
+#[derive(Clone, Debug)]
+pub struct DailyPuzzle {
+    /// 当期第一天（`YYYY-MM-DD`），用于区分不同期的成绩
+    pub key: String,
+    pub puzzle: Gameboard,
+    /// 是否来自网络（false 为本地按日期生成）
+    pub online: bool,
+}
+
+/// 获取 `today` 所在一期的题目；下载失败时打印原因并在本地生成
+pub fn fetch(config: &OnlineConfig, today: Date) -> DailyPuzzle {
+    let start = today.period_start(config.period);
+    let key = start.to_string();
+    if !config.puzzle_url.is_empty() {
+        let url = config.puzzle_url.replace("{date}", &key);
+        match download(&url) {
+            Ok(puzzle) => {
+                return DailyPuzzle {
+                    key,
+                    puzzle,
+                    online: true,
+                }
+            }
+            Err(err) => eprintln!("无法获取在线题目 {}，使用本地生成: {}", url, err),
+        }
+    }
+    DailyPuzzle {
+        puzzle: local(start),
+        key,
+        online: false,
+    }
+}
+
+/// 本地生成：以日期为种子，所有人同一天得到同一道中等难度的题
+fn local(start: Date) -> Gameboard {
+    Gameboard::generate_seeded(Difficulty::Medium.holes(), start.0 as u64)
+}
+
+#[cfg(feature = "online")]
+fn download(url: &str) -> Result<Gameboard, String> {
+    let text = ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(TIMEOUT_SECS))
+        .build()
+        .get(url)
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    crate::pack::parse_lines(&text)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "no puzzle in response".to_string())
+}
+
+#[cfg(not(feature = "online"))]
+fn download(_url: &str) -> Result<Gameboard, String> {
+    Err("built without the `online` feature".to_string())
+}
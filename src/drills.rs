@@ -0,0 +1,109 @@
+//! 技巧练习：只有指定技巧能推进的局面，填出该技巧推出的数字即完成。
+//!
+//! 局面由 `sudoku_core::Drill` 在后台生成；练习在普通标签页中进行，
+//! 每次填数后立即判断对错并在提示框中反馈。
+
+use crate::config::KeyBindings;
+use crate::gameboard_controller::GameboardController;
+use sudoku_core::{Drill, Technique};
+
+/// 可以练习的技巧（唯余法太简单；剑鱼在随机局面中太少见，限时内生成不出来）
+pub const TECHNIQUES: [Technique; 5] = [
+    Technique::HiddenSingle,
+    Technique::LockedCandidates,
+    Technique::NakedPair,
+    Technique::XWing,
+    Technique::XYWing,
+];
+
+/// 技巧的一句话说明（界面字体只含西文字符）
+pub fn explain(technique: Technique) -> &'static str {
+    match technique {
+        Technique::NakedSingle => "A cell with only one digit left.",
+        Technique::HiddenSingle => "A digit with one spot left in a unit.",
+        Technique::LockedCandidates => "A digit locked to one line of a box.",
+        Technique::NakedPair => "Two cells sharing the same two digits.",
+        Technique::XWing => "A digit on the same two columns twice.",
+        Technique::UniqueRectangle => "Avoid a pair that could swap in a rectangle.",
+        Technique::XYWing => "A pair cell seeing two pairs that share a digit.",
+        Technique::Swordfish => "A digit on the same three columns thrice.",
+        Technique::XYChain => "Pair cells linked from one digit back to it.",
+    }
+}
+
+/// 玩家最近一次填数的结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Feedback {
+    Correct,
+    /// 填在 `[x, y]` 的数字不是这一步能推出的
+    Wrong([usize; 2], u8),
+}
+
+/// 进行中的练习
+pub struct Trainer {
+    pub drill: Drill,
+    /// 已经判断过的盘面变化数（`GameboardController::events`）
+    seen: usize,
+    feedback: Option<Feedback>,
+}
+
+impl Trainer {
+    pub fn new(drill: Drill) -> Self {
+        Trainer {
+            drill,
+            seen: 0,
+            feedback: None,
+        }
+    }
+
+    /// 是否已经填出答案
+    pub fn is_done(&self) -> bool {
+        self.feedback == Some(Feedback::Correct)
+    }
+
+    /// 判断新填入的数字；反馈有变化时返回 true
+    pub fn check(&mut self, controller: &GameboardController) -> bool {
+        if self.seen > controller.events.len() {
+            // 撤销或重置后事件流变短，从头再数
+            self.seen = controller.events.len();
+            return false;
+        }
+        let before = self.feedback;
+        let new = controller.events.iter_from(self.seen);
+        for event in new.filter(|e| e.value != 0) {
+            if self.is_done() {
+                break;
+            }
+            let cell = [event.x, event.y];
+            self.feedback = Some(if self.drill.is_answer(cell, event.value) {
+                Feedback::Correct
+            } else {
+                Feedback::Wrong(cell, event.value)
+            });
+        }
+        self.seen = controller.events.len();
+        self.feedback != before
+    }
+
+    /// 提示框的标题与两行说明
+    pub fn text(&self, keys: &KeyBindings) -> [String; 3] {
+        let technique = self.drill.technique;
+        match self.feedback {
+            None => [
+                format!("Drill: {}", technique),
+                "Fill in the digit this step gives you.".to_string(),
+                explain(technique).to_string(),
+            ],
+            Some(Feedback::Correct) => [
+                format!("Correct! Technique: {}", technique),
+                explain(technique).to_string(),
+                "Enter: next drill".to_string(),
+            ],
+            Some(Feedback::Wrong([x, y], value)) => [
+                format!("Drill: {}", technique),
+                format!("{} at row {}, column {} is not it.", value, y + 1, x + 1),
+                format!("Look again; {:?} undoes the entry.", keys.undo),
+            ],
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! 单格解释（侧栏）：选中的空格里每个不能填的数字是被哪些同行 / 列 / 宫的格子排除的，
+//! 以及逻辑求解器从当前盘面出发能填出这一格时，一路推到这一格的步骤。
+//!
+//! 列出的步骤中有链（xy-wing、xy-chain）时，最后一条链交给视图画在棋盘上。
+//!
+//! 解释跟随选中格：换格或盘面变化后重新计算。
+
+use crate::gameboard_controller::GameboardController;
+use sudoku_core::logic::{Link, Pos, Step};
+use sudoku_core::peers::{is_peer, peers};
+use sudoku_core::{LogicSolver, SIZE};
+
+/// 推理步骤最多列出的条数（侧栏高度有限）
+const SHOWN_STEPS: usize = 6;
+
+/// 一个格子的解释
+pub struct Explanation {
+    /// 解释的格子 `[x, y]`
+    pub cell: [usize; 2],
+    /// 计算时的盘面，变化后需要重新计算
+    board: [[u8; SIZE]; SIZE],
+    /// 侧栏的文字，第一行为标题
+    pub lines: Vec<String>,
+    /// 画在棋盘上的链；列出的步骤都不是链时为空
+    pub links: Vec<Link>,
+}
+
+impl Explanation {
+    pub fn new(controller: &GameboardController, [x, y]: [usize; 2]) -> Self {
+        let board = controller.gameboard.cells;
+        let pos = (y, x);
+        let mut lines = vec![format!("Why {}?", cell_name(pos))];
+        let mut links = Vec::new();
+        if controller.initial_cells[y][x] != 0 {
+            lines.push(format!("Given: {}", board[y][x]));
+        } else if board[y][x] != 0 {
+            lines.push(format!("You entered {}", board[y][x]));
+            lines.push("Clear it to see the reasoning".to_string());
+        } else {
+            lines.extend(eliminations(&board, pos));
+            let (steps, chain) = reasoning(controller, pos);
+            lines.extend(steps);
+            links = chain;
+        }
+        Explanation {
+            cell: [x, y],
+            board,
+            lines,
+            links,
+        }
+    }
+
+    /// 选中格或盘面与计算时不同
+    pub fn is_stale(&self, controller: &GameboardController) -> bool {
+        controller.selected_cell != Some(self.cell) || controller.gameboard.cells != self.board
+    }
+}
+
+/// 每个被排除的数字与排除它的格子，最后一行为剩下的候选数
+fn eliminations(board: &[[u8; SIZE]; SIZE], (r, c): Pos) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut left = Vec::new();
+    for digit in 1..=9u8 {
+        let mut by = Vec::new();
+        for &(row, col) in peers((r, c)) {
+            if board[row][col] == digit {
+                let unit = if row == r {
+                    "row"
+                } else if col == c {
+                    "column"
+                } else {
+                    "box"
+                };
+                by.push(format!("{} {}", unit, cell_name((row, col))));
+            }
+        }
+        if by.is_empty() {
+            left.push(digit.to_string());
+        } else {
+            lines.push(format!("No {}: {}", digit, by.join(", ")));
+        }
+    }
+    lines.push(if left.is_empty() {
+        "No digit fits: an earlier entry is wrong".to_string()
+    } else {
+        format!("Candidates: {}", left.join(" "))
+    });
+    lines
+}
+
+/// 逻辑求解器从当前盘面推到这一格的过程：只列出影响这一格的步骤；
+/// 另外返回列出的步骤中最后一条链
+fn reasoning(controller: &GameboardController, target: Pos) -> (Vec<String>, Vec<Link>) {
+    if !controller.gameboard.conflicting_cells().is_empty() {
+        let line = "Fix the conflicts to see the solver's reasoning".to_string();
+        return (vec![line], Vec::new());
+    }
+    let mut solver =
+        LogicSolver::new(&controller.gameboard).assume_unique(controller.assist.unique_rectangles);
+    let mut relevant = Vec::new();
+    let mut total = 0;
+    while let Some(step) = solver.next_step() {
+        solver.apply(&step);
+        total += 1;
+        let places = step.placements.iter().any(|&(pos, _)| pos == target);
+        let touches = step.eliminations.iter().any(|&(pos, _)| pos == target)
+            || step.placements.iter().any(|&(pos, _)| is_peer(pos, target));
+        if places || touches {
+            relevant.push(step);
+        }
+        if places {
+            let value = solver.cells()[target.0][target.1];
+            let steps = if total == 1 { "step" } else { "steps" };
+            let mut lines = vec![format!(
+                "Solver: {} = {} after {} {}",
+                cell_name(target),
+                value,
+                total,
+                steps
+            )];
+            let skipped = relevant.len().saturating_sub(SHOWN_STEPS);
+            if skipped > 0 {
+                lines.push(format!("  ... {} earlier steps", skipped));
+            }
+            let shown = &relevant[skipped..];
+            let chain = shown.iter().rposition(|s| !s.links.is_empty());
+            for (i, step) in shown.iter().enumerate() {
+                let drawn = if chain == Some(i) { " (see board)" } else { "" };
+                lines.push(format!("  {}{}", describe(step, target), drawn));
+            }
+            let links = chain.map_or_else(Vec::new, |i| shown[i].links.clone());
+            return (lines, links);
+        }
+    }
+    let line = "The solver cannot place this cell yet".to_string();
+    (vec![line], Vec::new())
+}
+
+/// 一步推理的简短说明，以对 `target` 的影响为主
+fn describe(step: &Step, target: Pos) -> String {
+    if let Some(&(pos, value)) = step.placements.first() {
+        return format!("{}: {} = {}", step.technique, cell_name(pos), value);
+    }
+    let removed: Vec<String> = step
+        .eliminations
+        .iter()
+        .filter(|&&(pos, _)| pos == target)
+        .map(|(_, value)| value.to_string())
+        .collect();
+    if removed.is_empty() {
+        format!(
+            "{}: removes {} candidates",
+            step.technique,
+            step.eliminations.len()
+        )
+    } else {
+        format!("{}: not {} here", step.technique, removed.join(" "))
+    }
+}
+
+/// 格子名，如 "r3c5"
+fn cell_name((r, c): Pos) -> String {
+    format!("r{}c{}", r + 1, c + 1)
+}
@@ -1,59 +1,490 @@
-use crate::gameboard::{Gameboard, DEFAULT_HOLES};
+use crate::config::Assist;
+use crate::journal::Journal;
 use piston::input::GenericEvent;
 use piston::input::{Button, Key, MouseButton};
+use rand::thread_rng;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use sudoku_core::logic::Pos;
+use sudoku_core::peers;
+use sudoku_core::wordoku;
+use sudoku_core::{
+    Difficulty, Gameboard, LogicSolver, SolveStats, Variant, DEFAULT_HOLES, RATED_BUDGET,
+};
 
+/// 撤销记录中对单个格子的一次修改
 #[derive(Clone, Copy)]
 pub struct Change {
     pub x: usize,
     pub y: usize,
+    /// 修改前的值
     pub prev: u8,
+    /// 与前一条记录属于同一次操作（如重置），撤销 / 重做时一起处理
+    pub joined: bool,
+}
+
+/// 假设分支（what-if）：开分支时的盘面快照，与撤销历史分开保存
+#[derive(Clone, Copy)]
+pub struct Branch {
+    /// 开分支时的盘面，放弃分支时恢复
+    board: [[u8; 9]; 9],
+    /// 开分支时撤销记录的条数；分支中只能撤销分支里的修改
+    changes: usize,
+    /// 在分支中修改过的格子（用另一种颜色显示）
+    pub cells: CellSet,
+}
+
+/// 格子集合（81 位的位图），按 `[x, y]` 存取；绘制时每帧逐格查询，比线性查找的 Vec 快
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellSet(u128);
+
+impl CellSet {
+    fn bit([x, y]: [usize; 2]) -> u128 {
+        1 << (y * 9 + x)
+    }
+
+    pub fn contains(&self, cell: [usize; 2]) -> bool {
+        self.0 & Self::bit(cell) != 0
+    }
+
+    pub fn insert(&mut self, cell: [usize; 2]) {
+        self.0 |= Self::bit(cell);
+    }
+
+    pub fn remove(&mut self, cell: [usize; 2]) {
+        self.0 &= !Self::bit(cell);
+    }
+
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// 按行优先顺序列出集合中的格子
+    pub fn iter(&self) -> impl Iterator<Item = [usize; 2]> + '_ {
+        (0..81)
+            .map(|i| [i % 9, i / 9])
+            .filter(move |&cell| self.contains(cell))
+    }
+}
+
+/// 按区域检查的区域：选中格所在的行、列或宫
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Row,
+    Column,
+    Box,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 3] = [Unit::Row, Unit::Column, Unit::Box];
+
+    /// 在同一格上再次按区域检查时换到的区域
+    fn next(self) -> Self {
+        match self {
+            Unit::Row => Unit::Column,
+            Unit::Column => Unit::Box,
+            Unit::Box => Unit::Row,
+        }
+    }
+
+    /// `[x, y]` 所在的这个区域的编号（0 – 8，宫按行优先）
+    pub fn index(self, [x, y]: [usize; 2]) -> usize {
+        match self {
+            Unit::Row => y,
+            Unit::Column => x,
+            Unit::Box => peers::box_of((y, x)),
+        }
+    }
+
+    /// 这个区域中编号为 `index` 的一个的全部格子 `(row, col)`，从上到下、从左到右
+    fn members(self, index: usize) -> &'static [Pos; 9] {
+        &peers::UNITS[self as usize * 9 + index]
+    }
+
+    /// 界面中的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Row => "row",
+            Unit::Column => "column",
+            Unit::Box => "box",
+        }
+    }
+
+    /// `[x, y]` 所在的这个区域的全部格子
+    pub fn cells(self, [x, y]: [usize; 2]) -> CellSet {
+        let mut set = CellSet::default();
+        for &(row, col) in self.members(self.index([x, y])) {
+            set.insert([col, row]);
+        }
+        set
+    }
 }
 
 pub struct GameboardController {
     pub gameboard: Gameboard,
     pub selected_cell: Option<[usize; 2]>,
+    /// Shift / Ctrl 点击选出的多个格子（包括选中格），批量清空等操作作用于它们；
+    /// 为空时只有选中格
+    pub multi: CellSet,
     pub cursor_pos: [f64; 2],
     /// 鼠标左键当前是否按下（用于绘制按钮按下效果）
     pub mouse_pressed: bool,
     pub initial_cells: [[u8; 9]; 9],
-    pub invalid_cells: Vec<[usize; 2]>,
-    /// 操作历史，用于撤销（每项是整个棋盘的快照）
-    pub history: Vec<[[u8; 9]; 9]>,
-    /// 逐步变更历史：记录每次用户对单个格子的修改（用于精细撤销）
-    pub changes: Vec<Change>,
+    pub invalid_cells: CellSet,
+    /// 撤销历史：按顺序记录玩家对单个格子的每次修改（只存差异，不限条数，较早的部分压缩存放）
+    pub changes: Journal<Change>,
+    /// 撤销掉的操作，用于重做（每组按原顺序，`prev` 为撤销前的值）；有新的输入时清空
+    undone: Vec<Vec<Change>>,
+    /// 假设分支栈（最内层在末尾），为空时不在分支中
+    pub branches: Vec<Branch>,
     /// 当前提示（蓝色显示）：(x,y, 正确值)
     pub hint: Option<([usize; 2], u8)>,
     /// 是否显示全部答案（仅显示，不写入）
     pub show_all: bool,
-    /// 显示全部答案的求解缓存
-    pub solved_cache: Option<[[u8; 9]; 9]>,
+    /// 题目的解：载入题目时在后台求解一次，显示答案、提示与提交都读这里；
+    /// 无解或尚未求出时为 None
+    pub solution: Option<[[u8; 9]; 9]>,
+    /// 变体数独的额外提示（三明治数独的外侧和、温度计）；普通数独为 None
+    pub variant: Option<Variant>,
+    /// 求出解时求解器的统计；求解完成前为 None
+    pub diagnostics: Option<Diagnostics>,
+    /// 后台求解中的题目解（完成后在 `update` 中写入 `solution`）
+    solving: Option<Receiver<Solved>>,
+    /// 求解完成前请求的提示 / 提交，求出解后再执行
+    queued: Option<Queued>,
+    /// 检查过且填对的格子（提交前显示为绿色）；格子再被修改时清除
+    pub checked: CellSet,
+    /// 检查过但填错的格子（提交前用红底标出）；格子再被修改时清除
+    pub wrong: CellSet,
+    /// 本局是否检查过（检查选中格 / 区域 / 全部）；之后进度显示检查确认的格子数
+    pub has_checked: bool,
+    /// 最近一次按区域检查的 (选中格, 区域)；盘面变化后清除
+    pub unit_check: Option<([usize; 2], Unit)>,
+    /// 弹出的数字轮盘（选数字由 `App` 借助 view 的命中检测完成）
+    pub wheel: Option<Wheel>,
+    /// 左键在可编辑格上按住的 (格子, 已按住秒数)，用于长按弹出数字轮盘
+    press: Option<([usize; 2], f64)>,
+    /// 选中格高亮正在滑动：(出发位置 [x, y]，以格为单位, 剩余秒数)
+    slide: Option<([f64; 2], f64)>,
+    /// 正在闪烁的格子：(格子, 种类, 剩余秒数)
+    pub flash: Option<([usize; 2], Flash, f64)>,
     /// 是否已提交（提交后锁定，无法编辑/撤销/重置/提示）
     pub submitted: bool,
+    /// 计时暂停（窗口失去焦点时由 `App` 设置，不影响棋盘操作）
+    pub clock_paused: bool,
+    /// 长时间没有输入，计时暂停（由 `App` 设置，HUD 显示 Idle）
+    pub idle: bool,
+    /// "Random" 生成新题时使用的空格数量（由 --difficulty / --holes 决定）
+    pub holes: usize,
+    /// "Random" 生成新题时要求的难度评级；用 --holes 指定空格数时为 None（只挖空，不评级）
+    pub target: Option<Difficulty>,
+    /// 限时内没能生成目标难度时，当前题目实际的难度评级（在 HUD 中提示）
+    pub fallback: Option<Difficulty>,
+    /// 辅助功能设置（来自配置文件）
+    pub assist: Assist,
+    /// 本局已用时间（秒），提交后停止计时
+    pub elapsed: f64,
+    /// 本局填入冲突数字的次数
+    pub mistakes: u32,
+    /// 本局所有盘面变化，按发生顺序（较早的部分压缩存放）
+    pub events: Journal<GameEvent>,
+    /// 本局显示过的提示（显示时的已用时间、格子与提示值），用于赛后分析
+    pub hints: Vec<GameEvent>,
+    /// 题目来自题库时的 (题库名, 序号)
+    pub pack: Option<(String, usize)>,
+    /// 每日 / 每周题的日期（`YYYY-MM-DD`）
+    pub daily: Option<String>,
+    /// 后台生成中的新题（完成后在 `update` 中替换当前棋盘）
+    pub pending: Option<PendingPuzzle>,
+}
+
+/// 盘面上的一处冲突：同一行 / 列 / 宫中重复的数字
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub unit: Unit,
+    /// 区域编号（0 – 8）
+    pub index: usize,
+    pub digit: u8,
+    /// 重复的格子，按行优先顺序
+    pub cells: Vec<[usize; 2]>,
+}
+
+impl Conflict {
+    /// 文字描述，如 "two 7s in row 3"；Wordoku 盘面上用界面中显示的字母，如 "two Es in row 3"
+    pub fn describe(&self, gameboard: &Gameboard) -> String {
+        let count = match self.cells.len() {
+            2 => "two".to_string(),
+            3 => "three".to_string(),
+            4 => "four".to_string(),
+            n => n.to_string(),
+        };
+        format!(
+            "{} {}s in {} {}",
+            count,
+            gameboard.symbol(self.digit).unwrap_or('?'),
+            self.unit.name(),
+            self.index + 1
+        )
+    }
+}
+
+/// 对局中的一次盘面变化（带时间戳）；回放文件与联机同步都基于这条事件流
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameEvent {
+    /// 发生时的已用时间（秒）
+    pub time: f64,
+    pub x: usize,
+    pub y: usize,
+    /// 新值（0 表示清空）
+    pub value: u8,
+}
+
+/// 等待题目的解求出后再执行的操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Queued {
+    Hint,
+    Check(Option<CellSet>),
+    FindMistake,
+    Submit,
+}
+
+/// 求解器在当前题目上的统计（调试浮层显示）
+#[derive(Clone, Copy, Debug)]
+pub struct Diagnostics {
+    /// 回溯求解
+    pub solve: SolveStats,
+    /// 唯一性检查（数到第二个解为止）
+    pub count: SolveStats,
+    /// 唯一性检查找到的解的个数（最多 2）
+    pub solutions: usize,
+}
+
+/// 后台求解的结果
+struct Solved {
+    /// 题目的解；无解时为 None
+    solution: Option<[[u8; 9]; 9]>,
+    diagnostics: Diagnostics,
+}
+
+/// 后台线程准备好的题目；解与难度评级也在后台求出
+struct Loaded {
+    board: Gameboard,
+    variant: Option<Variant>,
+    solved: Solved,
+    /// 未达到目标难度时的实际难度
+    fallback: Option<Difficulty>,
+}
+
+/// 后台线程生成中的题目
+pub struct PendingPuzzle {
+    receiver: Receiver<Loaded>,
+    /// 已等待的时间（秒），用于绘制加载动画
+    pub waited: f64,
+    /// 载入后设置的每日题日期
+    daily: Option<String>,
+}
+
+/// 格子闪烁提示的时长（秒）
+pub const FLASH_TIME: f64 = 0.6;
+
+/// 左键在可编辑格上按住这么久（秒）弹出数字轮盘
+const LONG_PRESS: f64 = 0.5;
+
+/// 方向键移动时选中格高亮滑到新格子所用的时间（秒）
+const SLIDE_TIME: f64 = 0.1;
+
+/// 数字轮盘：右键或长按可编辑格时以该格为中心弹出，一个手势选出 1 – 9
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wheel {
+    pub cell: [usize; 2],
+    /// 弹出轮盘的鼠标键是否还按着：按着拖到数字上松开即填入；在中间松开则留着等点击
+    pub held: bool,
+}
+
+/// 格子闪烁提示的种类
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flash {
+    /// 填入的数字符合规则，但已不在该格的候选数中（仍然写入）
+    Warning,
+    /// 严格模式下拒绝了违反规则的数字（没有写入）
+    Rejected,
+}
+
+/// 对局的一次操作，与输入方式无关：窗口（`board_event`）、终端界面与手柄都先把输入翻译成
+/// `InputAction`，再交给 `GameboardController::apply`，所以游戏逻辑不依赖 piston 事件
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputAction {
+    /// 选中格子；点在显示中的提示格上时确认该提示（与鼠标点击相同）。取消多选
+    SelectCell([usize; 2]),
+    /// 把格子加入多选或从中去掉（Shift / Ctrl 点击），并选中它
+    ToggleMulti([usize; 2]),
+    /// 移动选中格 (dx, dy)，取消多选
+    MoveSelection(isize, isize),
+    /// 在选中格填入数字（1..=9）
+    EnterDigit(u8),
+    /// 清空选中格；有多选时清空多选的全部格子（整体一步撤销）
+    ClearCell,
+    /// 清空这些格子中玩家填写的数字（整体一步撤销）
+    ClearCells(CellSet),
+    Undo,
+    Redo,
+    Reset,
+    /// 生成一道同样题量 / 难度的新题
+    NewPuzzle,
+    Hint,
+    ConfirmHint,
+    ToggleShowAll,
+    CheckCell,
+    CheckUnit,
+    CheckAll,
+    FindMistake,
+    Submit,
+    Branch,
+    CommitBranch,
+    DiscardBranch,
+}
+
+impl InputAction {
+    /// 对局中按键对应的操作（方向键、数字键与删除键）
+    pub fn from_key(key: Key) -> Option<Self> {
+        let action = match key {
+            Key::Up => InputAction::MoveSelection(0, -1),
+            Key::Down => InputAction::MoveSelection(0, 1),
+            Key::Left => InputAction::MoveSelection(-1, 0),
+            Key::Right => InputAction::MoveSelection(1, 0),
+            Key::D1 | Key::NumPad1 => InputAction::EnterDigit(1),
+            Key::D2 | Key::NumPad2 => InputAction::EnterDigit(2),
+            Key::D3 | Key::NumPad3 => InputAction::EnterDigit(3),
+            Key::D4 | Key::NumPad4 => InputAction::EnterDigit(4),
+            Key::D5 | Key::NumPad5 => InputAction::EnterDigit(5),
+            Key::D6 | Key::NumPad6 => InputAction::EnterDigit(6),
+            Key::D7 | Key::NumPad7 => InputAction::EnterDigit(7),
+            Key::D8 | Key::NumPad8 => InputAction::EnterDigit(8),
+            Key::D9 | Key::NumPad9 => InputAction::EnterDigit(9),
+            Key::Backspace | Key::Delete | Key::D0 | Key::NumPad0 => InputAction::ClearCell,
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+/// 秒数格式化为 mm:ss（超过一小时为 h:mm:ss）
+pub fn format_time(seconds: f64) -> String {
+    let secs = seconds as u64;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// 求出题目的解（不修改题目），并检查解是否唯一；变体数独的解还要符合变体的提示
+fn solve_puzzle(puzzle: &Gameboard, variant: Option<&Variant>) -> Solved {
+    if let Some(variant) = variant {
+        let (solution, solve) = variant.solve_with_stats(puzzle);
+        let (solutions, count) = variant.count_solutions_with_stats(puzzle, 2);
+        return Solved {
+            solution,
+            diagnostics: Diagnostics {
+                solve,
+                count,
+                solutions,
+            },
+        };
+    }
+    let mut board = puzzle.clone();
+    let (solved, solve) = board.solve_with_stats();
+    let (solutions, count) = puzzle.count_solutions_with_stats(2);
+    Solved {
+        solution: solved.then_some(board.cells),
+        diagnostics: Diagnostics {
+            solve,
+            count,
+            solutions,
+        },
+    }
 }
 
 impl GameboardController {
     pub fn new(gameboard: Gameboard) -> Self {
         let initial_cells = gameboard.cells;
-        Self {
+        let mut controller = Self {
             gameboard,
             selected_cell: None,
+            multi: CellSet::default(),
             cursor_pos: [0.0; 2],
             mouse_pressed: false,
             initial_cells,
-            invalid_cells: Vec::new(),
-            history: Vec::new(),
-            changes: Vec::new(),
+            invalid_cells: CellSet::default(),
+            changes: Journal::default(),
+            undone: Vec::new(),
+            branches: Vec::new(),
             hint: None,
             show_all: false,
-            solved_cache: None,
+            solution: None,
+            variant: None,
+            diagnostics: None,
+            solving: None,
+            queued: None,
+            checked: CellSet::default(),
+            has_checked: false,
+            unit_check: None,
+            wheel: None,
+            press: None,
+            slide: None,
+            wrong: CellSet::default(),
+            flash: None,
             submitted: false,
+            clock_paused: false,
+            idle: false,
+            holes: DEFAULT_HOLES,
+            target: None,
+            fallback: None,
+            assist: Assist::default(),
+            elapsed: 0.0,
+            mistakes: 0,
+            events: Journal::default(),
+            hints: Vec::new(),
+            pack: None,
+            daily: None,
+            pending: None,
+        };
+        controller.start_solving();
+        controller
+    }
+
+    /// 从存档恢复：initial 为初始题面，current 为当前盘面
+    pub fn from_saved(initial: Gameboard, current: Gameboard) -> Self {
+        let mut controller = Self::new(initial);
+        // 存档不含事件流，已填的格子记为开局时的变化
+        controller.set_board(&current);
+        controller
+    }
+
+    /// 把盘面改为 `current`（题面不变），与当前盘面不同的格子记为玩家的修改
+    pub fn set_board(&mut self, current: &Gameboard) {
+        for y in 0..9 {
+            for x in 0..9 {
+                if current.cells[y][x] != self.gameboard.cells[y][x] {
+                    self.set_cell(x, y, current.cells[y][x]);
+                }
+            }
         }
+        self.recompute_invalid_cells();
     }
 
     // 单格变更记录类型见文件顶部 `Change`
 
     /// 是否存在玩家输入（与初始题面不同的格子）
-    fn has_user_input(&self) -> bool {
+    pub fn has_user_input(&self) -> bool {
         for y in 0..9 {
             for x in 0..9 {
                 if self.gameboard.cells[y][x] != self.initial_cells[y][x] {
@@ -64,217 +495,551 @@ impl GameboardController {
         false
     }
 
-    pub fn event<E: GenericEvent>(
-        &mut self,
-        pos: [f64; 2],
-        size: f64,
-        window_size: [f64; 2],
-        e: &E,
-    ) {
+    /// 计时器是否在走（需要持续的 update 事件）
+    pub fn timer_running(&self) -> bool {
+        !self.submitted && !self.clock_paused && !self.idle
+    }
+
+    /// 是否有需要连续刷新的动画（如生成题目时的加载动画），或在等待后台求解
+    pub fn is_animating(&self) -> bool {
+        self.pending.is_some()
+            || self.solving.is_some()
+            || self.flash.is_some()
+            || self.slide.is_some()
+            || self.press.is_some()
+    }
+
+    /// 在后台线程中求出当前题目的解；较难的题目暴力求解可能很久，不能阻塞输入
+    fn start_solving(&mut self) {
+        let puzzle = Gameboard::from_cells(self.initial_cells);
+        let variant = self.variant.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(solve_puzzle(&puzzle, variant.as_ref()));
+        });
+        self.solution = None;
+        self.diagnostics = None;
+        self.solving = Some(receiver);
+    }
+
+    /// 检查后台求解是否完成，完成后执行排队的操作
+    fn poll_solving(&mut self) {
+        let Some(receiver) = &self.solving else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(solved) => {
+                self.solution = solved.solution;
+                self.diagnostics = Some(solved.diagnostics);
+            }
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {}
+        }
+        self.solving = None;
+        match self.queued.take() {
+            Some(Queued::Hint) => self.show_hint(),
+            Some(Queued::Check(cells)) => self.check(cells),
+            Some(Queued::FindMistake) => self.find_mistake(),
+            Some(Queued::Submit) => self.submit(),
+            None => {}
+        }
+    }
+
+    /// 处理 update 事件：推进计时器，并检查后台生成是否完成
+    pub fn update(&mut self, dt: f64) {
+        if self.timer_running() {
+            self.elapsed += dt;
+        }
+        // 很长的对局中逐块压缩较早的撤销历史与事件
+        self.changes.compact();
+        self.events.compact();
+        self.poll_solving();
+        if let Some((cell, held)) = &mut self.press {
+            *held += dt;
+            if *held >= LONG_PRESS {
+                self.wheel = Some(Wheel {
+                    cell: *cell,
+                    held: true,
+                });
+                self.press = None;
+            }
+        }
+        if let Some((_, left)) = &mut self.slide {
+            *left -= dt;
+            if *left <= 0.0 {
+                self.slide = None;
+            }
+        }
+        if let Some((_, _, left)) = &mut self.flash {
+            *left -= dt;
+            if *left <= 0.0 {
+                self.flash = None;
+            }
+        }
+        if let Some(pending) = &mut self.pending {
+            pending.waited += dt;
+            match pending.receiver.try_recv() {
+                Ok(loaded) => {
+                    let daily = self.pending.take().and_then(|p| p.daily);
+                    self.load_solved_puzzle(loaded.board, Some(loaded.solved));
+                    self.variant = loaded.variant;
+                    self.daily = daily;
+                    self.fallback = loaded.fallback;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.pending = None,
+            }
+        }
+    }
+
+    /// 调试浮层中求解器统计的文字；求解完成前只显示第一行
+    pub fn diagnostics_lines(&self) -> Vec<String> {
+        let mut lines = vec!["Solver diagnostics".to_string()];
+        match &self.diagnostics {
+            Some(diagnostics) => {
+                let solutions = match diagnostics.solutions {
+                    0 => "no solution",
+                    1 => "unique",
+                    _ => "multiple solutions",
+                };
+                lines.push(format!("Solve: {}", diagnostics.solve));
+                lines.push(format!("Count: {}", diagnostics.count));
+                lines.push(format!("Result: {}", solutions));
+            }
+            None => lines.push("Solving...".to_string()),
+        }
+        lines
+    }
+
+    /// 假设分支的提示，如 "What-if 2"（嵌套两层）；不在分支中时为 None
+    pub fn branch_text(&self) -> Option<String> {
+        match self.branches.len() {
+            0 => None,
+            1 => Some("What-if".to_string()),
+            depth => Some(format!("What-if {}", depth)),
+        }
+    }
+
+    /// 没能生成目标难度时的提示，如 "medium instead of hard"
+    pub fn fallback_text(&self) -> Option<String> {
+        let target = self.target?;
+        self.fallback
+            .map(|rated| format!("{} instead of {}", rated, target))
+    }
+
+    /// 已用时间，格式 mm:ss（超过一小时为 h:mm:ss）
+    pub fn elapsed_text(&self) -> String {
+        format_time(self.elapsed)
+    }
+
+    /// 题目是否已正确完成（已提交、填满且没有错误）
+    pub fn is_solved(&self) -> bool {
+        self.submitted
+            && self.invalid_cells.is_empty()
+            && self.gameboard.is_complete()
+            && !self
+                .variant
+                .as_ref()
+                .is_some_and(|variant| variant.is_broken(&self.gameboard))
+    }
+
+    /// 单词数独解出后对角线上的隐藏单词；不是单词数独或还没解出时为 None
+    pub fn hidden_word(&self) -> Option<String> {
+        self.is_solved()
+            .then(|| self.gameboard.diagonal_word())
+            .flatten()
+    }
+
+    /// 单词数独中字母键代表的数字（不区分大小写）；普通数独中为 None
+    pub fn letter_digit(&self, key: Key) -> Option<u8> {
+        self.gameboard.letters?;
+        let ch = char::from_u32(u32::from(key)).filter(char::is_ascii_alphabetic)?;
+        self.gameboard.digit_of(ch)
+    }
+
+    /// 把当前题目改为变体数独并按变体的提示重新求解
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = Some(variant);
+        self.start_solving();
+    }
+
+    /// 已填写的格子数（含初始题面），用于进度显示
+    pub fn filled_count(&self) -> usize {
+        self.gameboard
+            .cells
+            .iter()
+            .flatten()
+            .filter(|&&v| v != 0)
+            .count()
+    }
+
+    /// 每个数字还要再填几个（下标 0 为数字 1）；已填满或填多的数字为 0
+    pub fn digit_counts(&self) -> [usize; 9] {
+        let mut counts = [9usize; 9];
+        for &v in self.gameboard.cells.iter().flatten().filter(|&&v| v != 0) {
+            let count = &mut counts[v as usize - 1];
+            *count = count.saturating_sub(1);
+        }
+        counts
+    }
+
+    /// 剩余数字的一行文字，如 "1:3  2:0  3:5 ..."（单词数独中为字母）
+    pub fn digit_counts_text(&self) -> String {
+        let counts = self.digit_counts();
+        let parts: Vec<String> = (1..)
+            .zip(counts)
+            .filter_map(|(d, n)| Some(format!("{}:{}", self.gameboard.symbol(d)?, n)))
+            .collect();
+        parts.join("  ")
+    }
+
+    /// 确认填对的格子数（不含题面数字）：提交后为已填且未被标红的格子，
+    /// 提交前为检查过且仍未修改的格子；两者都没有时为 None
+    pub fn verified_count(&self) -> Option<usize> {
+        if self.submitted {
+            let filled = (0..9)
+                .flat_map(|y| (0..9).map(move |x| [x, y]))
+                .filter(|&[x, y]| self.initial_cells[y][x] == 0 && self.gameboard.cells[y][x] != 0)
+                .count();
+            return Some(filled - self.invalid_cells.len());
+        }
+        self.has_checked.then(|| self.checked.len())
+    }
+
+    /// 棋盘（左上角 `pos`、边长 `size`）上鼠标所在的格子；点击选格与悬停高亮都用它
+    pub fn cell_at(&self, pos: [f64; 2], size: f64) -> Option<[usize; 2]> {
+        let x = self.cursor_pos[0] - pos[0];
+        let y = self.cursor_pos[1] - pos[1];
+        if x >= 0.0 && x < size && y >= 0.0 && y < size {
+            Some([(x / size * 9.0) as usize, (y / size * 9.0) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// 处理棋盘本身的输入（点击选格、方向键、数字键）；底部按钮由 view 做命中检测，
+    /// 点击后交给 `App::dispatch`
+    pub fn board_event<E: GenericEvent>(&mut self, pos: [f64; 2], size: f64, e: &E) {
         if let Some(p) = e.mouse_cursor_args() {
             self.cursor_pos = p;
         }
+        // 鼠标离开窗口后不再有格子在光标下
+        if e.cursor_args() == Some(false) {
+            self.cursor_pos = [-1.0; 2];
+        }
+        // 长按时移出按下的格子就不再弹出轮盘
+        if self
+            .press
+            .is_some_and(|(cell, _)| self.cell_at(pos, size) != Some(cell))
+        {
+            self.press = None;
+        }
 
         if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
-            // mark pressed for visual feedback
             self.mouse_pressed = true;
 
-            let mx = self.cursor_pos[0];
-            let my = self.cursor_pos[1];
-
-            // First: check if user clicked on one of the bottom buttons (Undo/Reset/Random)
-            // Use same layout math as view (with clamping), to keep hit-test aligned with drawing
-            let btn_w = 96.0_f64; // matches GameboardViewSettings defaults
-            let btn_h = (14u32 as f64) + 10.0; // hud_font_size 14 + padding
-            let btn_spacing = 12.0_f64; // spacing between buttons
-            let btn_count = 6.0;
-            let total_w = btn_count * btn_w + (btn_count - 1.0) * btn_spacing;
-            let preferred_start_x = pos[0] + (size - total_w) / 2.0;
-            let preferred_start_y = pos[1] + size + 12.0; // 固定在棋盘正下方
-            let margin = 8.0;
-            let start_x = preferred_start_x
-                .max(margin)
-                .min(window_size[0] - margin - total_w);
-            let start_y = preferred_start_y;
-
-            for i in 0..6 {
-                let bx = start_x + i as f64 * (btn_w + btn_spacing);
-                let by = start_y;
-                if mx >= bx && mx < bx + btn_w && my >= by && my < by + btn_h {
-                    match i {
-                        0 => {
-                            self.undo();
-                        }
-                        1 => {
-                            self.reset();
-                        }
-                        2 => {
-                            self.randomize(DEFAULT_HOLES);
-                        }
-                        3 => {
-                            self.show_hint();
-                        }
-                        4 => {
-                            self.toggle_show_all();
-                        }
-                        5 => {
-                            self.submit();
-                        }
-                        _ => {}
-                    }
-                    return;
+            // if inside board, update selected cell
+            if let Some(cell) = self.cell_at(pos, size) {
+                // 点击提示格子是确认提示，不开始长按
+                let confirms = self.hint.map(|(p, _)| p) == Some(cell);
+                self.apply(InputAction::SelectCell(cell));
+                if !confirms && self.is_editable(cell) {
+                    self.press = Some((cell, 0.0));
                 }
             }
-
-            // Otherwise, if inside board, update selected cell
-            let x = mx - pos[0];
-            let y = my - pos[1];
-            if x >= 0.0 && x < size && y >= 0.0 && y < size {
-                let cell_x = (x / size * 9.0) as usize;
-                let cell_y = (y / size * 9.0) as usize;
-                // 如果点击的是提示格子，则确认该提示为玩家输入
-                if let Some((pos, val)) = self.hint {
-                    if pos == [cell_x, cell_y] {
-                        // 仅当该格可编辑且当前为空时写入
-                        if self.initial_cells[cell_y][cell_x] == 0
-                            && self.gameboard.cells[cell_y][cell_x] == 0
-                        {
-                            let prev = 0;
-                            self.push_change(cell_x, cell_y, prev);
-                            self.gameboard.set([cell_x, cell_y], val);
-                            self.hint = None;
-                            self.invalid_cells.retain(|&p| p != [cell_x, cell_y]);
-                            if self.show_all {
-                                self.recompute_solution_cache();
-                            }
-                            // 若该值仍然非法，则加入 invalid（一般不会，因为来自解）
-                            if !self.gameboard.is_valid_move(cell_y, cell_x, val) {
-                                self.invalid_cells.push([cell_x, cell_y]);
-                            }
-                            return;
-                        }
-                    }
-                }
-                self.selected_cell = Some([cell_x, cell_y]);
-            }
         }
 
         // mouse release updates pressed flag so UI can show active state only while pressed
         if let Some(Button::Mouse(MouseButton::Left)) = e.release_args() {
             self.mouse_pressed = false;
+            self.press = None;
+        }
+
+        // 右键可编辑格：选中并弹出数字轮盘
+        if let Some(Button::Mouse(MouseButton::Right)) = e.press_args() {
+            if let Some(cell) = self
+                .cell_at(pos, size)
+                .filter(|&cell| self.is_editable(cell))
+            {
+                self.selected_cell = Some(cell);
+                self.wheel = Some(Wheel { cell, held: true });
+            }
         }
 
         if let Some(Button::Keyboard(key)) = e.press_args() {
-            // Movement: arrow keys move the selected cell (with boundary protection)
-            if let Some(ind) = self.selected_cell {
-                let (mut x, mut y) = (ind[0] as isize, ind[1] as isize);
-                match key {
-                    Key::Up => {
-                        y = (y - 1).max(0);
-                        self.selected_cell = Some([x as usize, y as usize]);
-                        return;
-                    }
-                    Key::Down => {
-                        y = (y + 1).min(8);
-                        self.selected_cell = Some([x as usize, y as usize]);
-                        return;
-                    }
-                    Key::Left => {
-                        x = (x - 1).max(0);
-                        self.selected_cell = Some([x as usize, y as usize]);
-                        return;
-                    }
-                    Key::Right => {
-                        x = (x + 1).min(8);
-                        self.selected_cell = Some([x as usize, y as usize]);
-                        return;
-                    }
-                    _ => {}
-                }
+            if let Some(action) = InputAction::from_key(key) {
+                self.apply(action);
             }
+        }
+    }
 
-            // For edits (digits/backspace/delete) operate on selected cell
-            if let Some(ind) = self.selected_cell {
-                let x = ind[0];
-                let y = ind[1];
-                // protect fixed initial cells and submitted state
-                if self.initial_cells[y][x] != 0 || self.submitted {
+    /// 执行一次对局操作（不依赖窗口，终端界面与手柄共用）
+    pub fn apply(&mut self, action: InputAction) {
+        match action {
+            InputAction::SelectCell(cell) => {
+                self.multi.clear();
+                // 如果点击的是提示格子，则确认该提示为玩家输入
+                if self.hint.map(|(p, _)| p) == Some(cell) && self.confirm_hint() {
                     return;
                 }
+                self.selected_cell = Some(cell);
+            }
+            InputAction::ToggleMulti(cell) => self.toggle_multi(cell),
+            InputAction::MoveSelection(dx, dy) => {
+                self.multi.clear();
+                self.move_selection(dx, dy);
+            }
+            InputAction::EnterDigit(val) => self.enter_digit(val),
+            InputAction::ClearCell if !self.multi.is_empty() => self.clear_cells(self.multi),
+            InputAction::ClearCell => self.clear_selected(),
+            InputAction::ClearCells(cells) => self.clear_cells(cells),
+            InputAction::Undo => self.undo(),
+            InputAction::Redo => self.redo(),
+            InputAction::Reset => self.reset(),
+            InputAction::NewPuzzle => self.randomize(self.holes, self.target),
+            InputAction::Hint => self.show_hint(),
+            InputAction::ConfirmHint => {
+                self.confirm_hint();
+            }
+            InputAction::ToggleShowAll => self.toggle_show_all(),
+            InputAction::CheckCell => self.check_selected(),
+            InputAction::CheckUnit => self.check_unit(),
+            InputAction::CheckAll => self.check_all(),
+            InputAction::FindMistake => self.find_mistake(),
+            InputAction::Submit => self.submit(),
+            InputAction::Branch => self.branch(),
+            InputAction::CommitBranch => self.commit_branch(),
+            InputAction::DiscardBranch => self.discard_branch(),
+        }
+    }
 
-                match key {
-                    Key::D1
-                    | Key::D2
-                    | Key::D3
-                    | Key::D4
-                    | Key::D5
-                    | Key::D6
-                    | Key::D7
-                    | Key::D8
-                    | Key::D9 => {
-                        let val = match key {
-                            Key::D1 => 1,
-                            Key::D2 => 2,
-                            Key::D3 => 3,
-                            Key::D4 => 4,
-                            Key::D5 => 5,
-                            Key::D6 => 6,
-                            Key::D7 => 7,
-                            Key::D8 => 8,
-                            Key::D9 => 9,
-                            _ => 0,
-                        };
-                        // only act if the value actually changes
-                        if self.gameboard.cells[y][x] != val {
-                            let prev = self.gameboard.cells[y][x];
-                            self.push_change(x, y, prev);
-                            self.gameboard.set([x, y], val);
-                            if self.show_all {
-                                self.recompute_solution_cache();
-                            }
-                        } else {
-                            return;
-                        }
-
-                        if self.gameboard.is_valid_move(y, x, val) {
-                            self.invalid_cells.retain(|&pos| pos != ind);
-                        } else if !self.invalid_cells.contains(&ind) {
-                            self.invalid_cells.push(ind);
-                        }
-                    }
-                    Key::Backspace | Key::Delete => {
-                        // only act if there is something to delete
-                        if self.gameboard.cells[y][x] != 0 {
-                            let prev = self.gameboard.cells[y][x];
-                            self.push_change(x, y, prev);
-                            self.gameboard.set([x, y], 0);
-                            self.invalid_cells.retain(|&pos| pos != ind);
-                            if self.show_all {
-                                self.recompute_solution_cache();
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+    /// 把格子加入多选或从中去掉；开始多选时原来的选中格也算在内
+    fn toggle_multi(&mut self, cell: [usize; 2]) {
+        if self.multi.is_empty() {
+            if let Some(selected) = self.selected_cell.filter(|&selected| selected != cell) {
+                self.multi.insert(selected);
             }
         }
+        if self.multi.contains(cell) {
+            self.multi.remove(cell);
+        } else {
+            self.multi.insert(cell);
+        }
+        self.selected_cell = Some(cell);
     }
 
-    /// 将当前棋盘状态压入历史（用于撤销）
-    fn push_history(&mut self) {
-        // cap history size to 100
-        if self.history.len() >= 100 {
-            self.history.remove(0);
+    /// 移动选中格（带边界保护）；未选中任何格子时先选中中间的格子，不用鼠标也能开始
+    pub fn move_selection(&mut self, dx: isize, dy: isize) {
+        let Some([x, y]) = self.selected_cell else {
+            self.selected_cell = Some([4, 4]);
+            return;
+        };
+        let from = self.selection_pos().unwrap_or([x as f64, y as f64]);
+        let x = (x as isize + dx).clamp(0, 8) as usize;
+        let y = (y as isize + dy).clamp(0, 8) as usize;
+        self.selected_cell = Some([x, y]);
+        // 高亮从当前显示的位置滑过去（连按时接着上一段滑动）；减少动画时直接跳到新格子
+        self.slide = (!self.assist.reduce_motion).then_some((from, SLIDE_TIME));
+    }
+
+    /// 选中格高亮当前的位置 [x, y]（以格为单位）；滑动中为两格之间的位置
+    pub fn selection_pos(&self) -> Option<[f64; 2]> {
+        let [x, y] = self.selected_cell?;
+        let to = [x as f64, y as f64];
+        let Some((from, left)) = self.slide else {
+            return Some(to);
+        };
+        // 先快后慢
+        let t = 1.0 - (left / SLIDE_TIME).clamp(0.0, 1.0);
+        let t = 1.0 - (1.0 - t) * (1.0 - t);
+        Some([
+            from[0] + (to[0] - from[0]) * t,
+            from[1] + (to[1] - from[1]) * t,
+        ])
+    }
+
+    /// 选中格是否可编辑（非初始题面且未提交）
+    pub fn selected_editable(&self) -> Option<[usize; 2]> {
+        self.selected_cell.filter(|&cell| self.is_editable(cell))
+    }
+
+    /// 格子是否可编辑
+    fn is_editable(&self, [x, y]: [usize; 2]) -> bool {
+        // protect fixed initial cells and submitted state
+        self.initial_cells[y][x] == 0 && !self.submitted
+    }
+
+    /// 在选中格填入数字（1..=9）；严格模式下违反规则的数字不写入，只闪烁该格
+    pub fn enter_digit(&mut self, val: u8) {
+        let Some(ind) = self.selected_editable() else {
+            return;
+        };
+        let [x, y] = ind;
+        // only act if the value actually changes
+        if self.gameboard.cells[y][x] == val {
+            return;
+        }
+        if self.assist.strict && !self.gameboard.is_valid_move(y, x, val) {
+            self.flash = Some((ind, Flash::Rejected, FLASH_TIME));
+            return;
+        }
+        let warn =
+            !self.assist.strict && self.assist.candidate_warning && !self.is_candidate(ind, val);
+        let prev = self.gameboard.cells[y][x];
+        self.push_change(x, y, prev, false);
+        self.set_cell(x, y, val);
+
+        if self.gameboard.is_valid_move(y, x, val) {
+            self.invalid_cells.remove(ind);
+            // 不阻止输入，只闪烁提醒；违反规则的数字由下面的冲突标记处理
+            self.flash = warn.then_some((ind, Flash::Warning, FLASH_TIME));
+        } else {
+            self.mistakes += 1;
+            self.invalid_cells.insert(ind);
+        }
+    }
+
+    /// `val` 是否仍在格子的候选数中：按当前盘面（不含该格）排除同行/列/宫的数字，
+    /// 再做只删候选、不落子的推理（区块、数对）
+    fn is_candidate(&self, [x, y]: [usize; 2], val: u8) -> bool {
+        let mut board = self.gameboard.clone();
+        board.set([x, y], 0);
+        let mut solver = LogicSolver::new(&board);
+        solver.eliminate();
+        solver.candidates((y, x)) & (1 << val) != 0
+    }
+
+    /// 清空选中格
+    pub fn clear_selected(&mut self) {
+        let Some(ind) = self.selected_editable() else {
+            return;
+        };
+        let [x, y] = ind;
+        // only act if there is something to delete
+        if self.gameboard.cells[y][x] == 0 {
+            return;
+        }
+        let prev = self.gameboard.cells[y][x];
+        self.push_change(x, y, prev, false);
+        self.set_cell(x, y, 0);
+        self.invalid_cells.remove(ind);
+    }
+
+    /// `cells` 中玩家填写了数字的格子（不含题面与空格）
+    pub fn entries_in(&self, cells: CellSet) -> CellSet {
+        let mut entries = CellSet::default();
+        for [x, y] in cells.iter() {
+            if self.initial_cells[y][x] == 0 && self.gameboard.cells[y][x] != 0 {
+                entries.insert([x, y]);
+            }
+        }
+        entries
+    }
+
+    /// 清空 `cells` 中玩家填写的数字，整体记为一次操作（放弃一条推理时用，可以撤销）
+    pub fn clear_cells(&mut self, cells: CellSet) {
+        if self.submitted {
+            return;
+        }
+        let mut joined = false;
+        for [x, y] in self.entries_in(cells).iter() {
+            self.push_change(x, y, self.gameboard.cells[y][x], joined);
+            self.set_cell(x, y, 0);
+            joined = true;
+        }
+        if joined {
+            self.recompute_invalid_cells();
+        }
+    }
+
+    /// 将当前提示写入棋盘（作为玩家输入）；成功返回 true
+    pub fn confirm_hint(&mut self) -> bool {
+        let Some((cell, val)) = self.hint else {
+            return false;
+        };
+        self.place(cell, val)
+    }
+
+    /// 在空的可编辑格写入数字（作为玩家输入，可撤销）；成功返回 true
+    pub fn place(&mut self, [x, y]: [usize; 2], val: u8) -> bool {
+        // 仅当该格可编辑且当前为空时写入
+        if self.initial_cells[y][x] != 0 || self.gameboard.cells[y][x] != 0 {
+            return false;
+        }
+        self.push_change(x, y, 0, false);
+        self.set_cell(x, y, val);
+        if self.hint.is_some_and(|(cell, _)| cell == [x, y]) {
+            self.hint = None;
         }
-        self.history.push(self.gameboard.cells);
+        self.invalid_cells.remove([x, y]);
+        // 提示来自解，一般不会非法；脚本提出的数字则可能违反规则
+        if !self.gameboard.is_valid_move(y, x, val) {
+            self.invalid_cells.insert([x, y]);
+        }
+        true
+    }
+
+    /// 修改一个格子并记录到事件流（所有盘面修改都经过这里）
+    fn set_cell(&mut self, x: usize, y: usize, value: u8) {
+        self.gameboard.set([x, y], value);
+        self.checked.remove([x, y]);
+        self.wrong.remove([x, y]);
+        self.unit_check = None;
+        self.events.push(GameEvent {
+            time: self.elapsed,
+            x,
+            y,
+            value,
+        });
+    }
+
+    /// 应用其他玩家的修改（合作模式）：进入事件流，但不进入撤销历史、不计错误次数
+    pub fn apply_remote(&mut self, [x, y]: [usize; 2], value: u8) {
+        if self.initial_cells[y][x] != 0 || self.submitted {
+            return;
+        }
+        self.set_cell(x, y, value);
+        if self.hint.is_some_and(|(cell, _)| cell == [x, y]) {
+            self.hint = None;
+        }
+        self.recompute_invalid_cells();
     }
 
-    /// 记录一次对单个格子的修改（变更为新值之前的旧值）
-    fn push_change(&mut self, x: usize, y: usize, prev: u8) {
-        if self.changes.len() >= 200 {
-            self.changes.remove(0);
+    /// 记录一次对单个格子的修改（变更为新值之前的旧值）；`joined` 表示与上一条属于同一次操作
+    fn push_change(&mut self, x: usize, y: usize, prev: u8, joined: bool) {
+        self.changes.push(Change { x, y, prev, joined });
+        if let Some(branch) = self.branches.last_mut() {
+            branch.cells.insert([x, y]);
         }
-        self.changes.push(Change { x, y, prev });
+        self.undone.clear();
+    }
+
+    /// 当前盘面上的全部冲突（含与题面数字的冲突），按行、列、宫的顺序
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        for unit in Unit::ALL {
+            for index in 0..9 {
+                // 区域中每个数字出现的格子
+                let mut found: [Vec<[usize; 2]>; 9] = Default::default();
+                for &(y, x) in unit.members(index) {
+                    let value = self.gameboard.cells[y][x];
+                    if value != 0 {
+                        found[value as usize - 1].push([x, y]);
+                    }
+                }
+                for (digit, cells) in (1..=9).zip(found) {
+                    if cells.len() > 1 {
+                        conflicts.push(Conflict {
+                            unit,
+                            index,
+                            digit,
+                            cells,
+                        });
+                    }
+                }
+            }
+        }
+        conflicts
     }
 
     /// 全量重新计算无效格集合（仅对玩家输入的格子做标记，初始题面不标红）
@@ -286,102 +1051,301 @@ impl GameboardController {
                 // 仅标记玩家输入（初始为 0 的格子）
                 if self.initial_cells[y][x] == 0 && v != 0 && !self.gameboard.is_valid_move(y, x, v)
                 {
-                    self.invalid_cells.push([x, y]);
+                    self.invalid_cells.insert([x, y]);
                 }
             }
         }
     }
 
-    /// 重新计算"显示全部答案"的解缓存
-    fn recompute_solution_cache(&mut self) {
-        if !self.show_all {
-            self.solved_cache = None;
-            return;
-        }
-        // 基于初始题面求解（忽略玩家输入，无论对错都能求解）
-        let mut clone = Gameboard::from_cells(self.initial_cells);
-        if clone.solve() {
-            self.solved_cache = Some(clone.cells);
-        } else {
-            self.solved_cache = None;
-        }
-    }
-
     /// 切换显示全部答案（只显示，不落子）
     pub fn toggle_show_all(&mut self) {
-        if self.show_all {
-            self.show_all = false;
-            self.solved_cache = None;
-        } else {
-            self.show_all = true;
-            self.recompute_solution_cache();
-        }
+        self.show_all = !self.show_all;
+    }
+
+    /// 是否有可以撤销的输入（分支中只算分支里的修改）
+    pub fn can_undo(&self) -> bool {
+        let start = self.branches.last().map_or(0, |branch| branch.changes);
+        self.changes.len() > start && !self.submitted
     }
 
     /// 撤销：
     /// 1) 未选择格子：撤销最近一次用户输入（全局最近）
-    /// 2) 已选择格子：只撤销该格子的最近一次输入
+    /// 2) 已选择格子：只撤销该格子的最近一次输入（多选时为其中任一格子的最近一次输入）
+    ///
+    /// 一次操作改了多个格子（如重置）时整体撤销。
+    /// 在假设分支中只撤销分支里的修改，分支之前的输入要先保留或放弃分支
     pub fn undo(&mut self) {
-        if self.changes.is_empty() || self.submitted {
+        if !self.can_undo() {
             return;
         }
+        let start = self.branches.last().map_or(0, |branch| branch.changes);
 
-        // 选择目标变更索引
-        let target_idx = if let Some([sx, sy]) = self.selected_cell {
-            // 从末尾向前寻找最近一次修改了该格子的记录；若该格无记录，则回退为全局最近
-            match self.changes.iter().rposition(|c| c.x == sx && c.y == sy) {
-                Some(idx) => Some(idx),
-                None => Some(self.changes.len() - 1),
+        // 选择目标变更索引：多选时看多选的格子，否则看选中格
+        let selection = if self.multi.is_empty() {
+            let mut selection = CellSet::default();
+            if let Some(cell) = self.selected_cell {
+                selection.insert(cell);
             }
+            selection
         } else {
+            self.multi
+        };
+        let target_idx = if selection.is_empty() {
             Some(self.changes.len() - 1)
+        } else {
+            // 从末尾向前寻找最近一次修改了这些格子的记录；若都无记录，则回退为全局最近
+            match self
+                .changes
+                .rposition(start, |c| selection.contains([c.x, c.y]))
+            {
+                Some(idx) => Some(idx),
+                None => Some(self.changes.len() - 1),
+            }
         };
 
         if let Some(idx) = target_idx {
-            let change = self.changes.remove(idx);
-            // 应用撤销：将该格子恢复为修改前的值
-            self.gameboard.set([change.x, change.y], change.prev);
+            // 目标记录所在的整次操作
+            let mut first = idx;
+            let joined = |i: usize| self.changes.get(i).is_some_and(|c| c.joined);
+            while first > start && joined(first) {
+                first -= 1;
+            }
+            let mut end = idx + 1;
+            while joined(end) {
+                end += 1;
+            }
+            let group = self.changes.drain(first..end);
+            // 应用撤销：从后往前将格子恢复为修改前的值，同时记下撤销前的值用于重做
+            let mut redo = Vec::with_capacity(group.len());
+            for change in group.iter().rev() {
+                redo.push(Change {
+                    prev: self.gameboard.cells[change.y][change.x],
+                    ..*change
+                });
+                self.set_cell(change.x, change.y, change.prev);
+            }
+            redo.reverse();
+            self.undone.push(redo);
             // 重新计算无效格（该变更可能影响同行同列同宫）
             self.recompute_invalid_cells();
         }
     }
 
-    /// 重置为初始题目（initial_cells）
+    /// 是否有可以重做的撤销
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty() && !self.submitted
+    }
+
+    /// 重做最近一次撤销（按撤销的倒序）
+    pub fn redo(&mut self) {
+        if !self.can_redo() {
+            return;
+        }
+        let Some(group) = self.undone.pop() else {
+            return;
+        };
+        // 重做的修改重新进入撤销记录，但不清空其余可重做的操作
+        let undone = std::mem::take(&mut self.undone);
+        for (i, change) in group.into_iter().enumerate() {
+            let prev = self.gameboard.cells[change.y][change.x];
+            self.push_change(change.x, change.y, prev, i > 0);
+            self.set_cell(change.x, change.y, change.prev);
+        }
+        self.undone = undone;
+        self.recompute_invalid_cells();
+    }
+
+    /// 重置为初始题目（initial_cells）；整体记为一次操作，可以撤销
     pub fn reset(&mut self) {
         // do nothing if there is no user input or already submitted
         if !self.has_user_input() || self.submitted {
             return;
         }
-        self.push_history();
-        self.gameboard.cells = self.initial_cells;
+        let mut joined = false;
+        for y in 0..9 {
+            for x in 0..9 {
+                let prev = self.gameboard.cells[y][x];
+                if prev != self.initial_cells[y][x] {
+                    self.push_change(x, y, prev, joined);
+                    self.set_cell(x, y, self.initial_cells[y][x]);
+                    joined = true;
+                }
+            }
+        }
+        self.branches.clear();
         self.invalid_cells.clear();
         self.hint = None;
         self.show_all = false;
-        self.solved_cache = None;
     }
 
-    /// 随机生成新题目（holes = 空格数量）
-    pub fn randomize(&mut self, holes: usize) {
-        self.push_history();
-        self.gameboard = Gameboard::generate_random(holes);
+    /// 开一个假设分支：之后的输入用另一种颜色显示，可以整体保留或放弃；分支可以嵌套
+    pub fn branch(&mut self) {
+        if self.submitted {
+            return;
+        }
+        self.branches.push(Branch {
+            board: self.gameboard.cells,
+            changes: self.changes.len(),
+            cells: CellSet::default(),
+        });
+        self.undone.clear();
+    }
+
+    /// 保留最内层分支：其中的输入并入上一层分支，没有上一层时成为普通输入
+    pub fn commit_branch(&mut self) {
+        let Some(branch) = self.branches.pop() else {
+            return;
+        };
+        if let Some(parent) = self.branches.last_mut() {
+            for cell in branch.cells.iter() {
+                parent.cells.insert(cell);
+            }
+        }
+    }
+
+    /// 放弃最内层分支：分支中改过的格子恢复为开分支时的值，分支里的撤销记录一并丢弃
+    pub fn discard_branch(&mut self) {
+        let Some(branch) = self.branches.pop() else {
+            return;
+        };
+        for [x, y] in branch.cells.iter() {
+            if self.gameboard.cells[y][x] != branch.board[y][x] {
+                self.set_cell(x, y, branch.board[y][x]);
+            }
+        }
+        self.changes.truncate(branch.changes);
+        self.undone.clear();
+        self.recompute_invalid_cells();
+    }
+
+    /// 格子是否在假设分支中改过（包括外层分支）
+    pub fn in_branch(&self, cell: [usize; 2]) -> bool {
+        self.branches
+            .iter()
+            .any(|branch| branch.cells.contains(cell))
+    }
+
+    /// 随机生成新题目（holes = 空格数量）；指定 `target` 时按难度评级生成，
+    /// 限时 `RATED_BUDGET` 内没有生成出来就用最接近的难度，并记在 `fallback` 中。
+    /// 当前是变体数独时生成同一种变体（按 `target` 的空格数，不评级），
+    /// 单词数独则换一个随机单词再生成一道单词数独。
+    /// 在后台线程生成，期间旧棋盘仍可操作；完成后由 `update` 替换。
+    pub fn randomize(&mut self, holes: usize, target: Option<Difficulty>) {
+        if let Some(kind) = self.variant.as_ref().map(Variant::kind) {
+            let holes = target.map_or(holes, Difficulty::holes);
+            self.spawn_load(None, move || {
+                let (board, variant) = kind.generate(holes);
+                (board, None, Some(variant))
+            });
+            return;
+        }
+        if self.gameboard.letters.is_some() {
+            let holes = target.map_or(holes, Difficulty::holes);
+            self.spawn_load(None, move || {
+                let word = wordoku::random_word(&mut thread_rng());
+                (Gameboard::generate_wordoku(word, holes), None, None)
+            });
+            return;
+        }
+        self.spawn_load(None, move || match target {
+            Some(target) => {
+                let (board, rated) = Gameboard::generate_rated(target, RATED_BUDGET);
+                (board, (rated != target).then_some(rated), None)
+            }
+            None => (Gameboard::generate_random(holes), None, None),
+        });
+    }
+
+    /// 在后台线程中准备新题目（生成或下载），完成后由 `update` 替换；
+    /// `daily` 为每日题的日期
+    pub fn load_async<F>(&mut self, daily: Option<String>, make: F)
+    where
+        F: FnOnce() -> Gameboard + Send + 'static,
+    {
+        self.spawn_load(daily, move || (make(), None, None));
+    }
+
+    /// `load_async` 的实现；`make` 同时给出未达到目标时的实际难度与变体数独的提示
+    fn spawn_load<F>(&mut self, daily: Option<String>, make: F)
+    where
+        F: FnOnce() -> (Gameboard, Option<Difficulty>, Option<Variant>) + Send + 'static,
+    {
+        if self.pending.is_some() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (board, fallback, variant) = make();
+            let solved = solve_puzzle(&board, variant.as_ref());
+            let _ = sender.send(Loaded {
+                board,
+                variant,
+                solved,
+                fallback,
+            });
+        });
+        self.pending = Some(PendingPuzzle {
+            receiver,
+            waited: 0.0,
+            daily,
+        });
+    }
+
+    /// 载入一道新题目，清空本局状态；题目的解在后台求出
+    pub fn load_new_puzzle(&mut self, board: Gameboard) {
+        self.load_solved_puzzle(board, None);
+        self.start_solving();
+    }
+
+    /// 载入一道已求出解的新题目（`solved` 为 None 时由调用者在后台求解）
+    fn load_solved_puzzle(&mut self, board: Gameboard, solved: Option<Solved>) {
+        self.gameboard = board;
         self.initial_cells = self.gameboard.cells;
+        self.solution = solved.as_ref().and_then(|solved| solved.solution);
+        self.variant = None;
+        self.diagnostics = solved.map(|solved| solved.diagnostics);
+        self.solving = None;
+        self.queued = None;
+        self.fallback = None;
+        self.checked.clear();
+        self.wrong.clear();
+        self.has_checked = false;
+        self.unit_check = None;
+        self.wheel = None;
+        self.press = None;
+        self.flash = None;
+        self.changes.clear();
+        self.undone.clear();
+        self.multi.clear();
+        self.branches.clear();
         self.invalid_cells.clear();
         self.hint = None;
         self.show_all = false;
-        self.solved_cache = None;
         self.submitted = false;
+        self.elapsed = 0.0;
+        self.mistakes = 0;
+        self.events.clear();
+        self.hints.clear();
+        self.pack = None;
+        self.daily = None;
     }
 
     /// 生成一个提示：选择"最容易想到"的空格（候选数最少的可编辑空格），
-    /// 基于求解结果给出正确值，蓝色显示，不直接写入棋盘。
+    /// 从题目的解中给出正确值，蓝色显示，不直接写入棋盘。
     pub fn show_hint(&mut self) {
         // 提交后禁用 Hint
         if self.submitted {
             return;
         }
         // 若已有提示，则本次点击视为取消提示
-        if self.hint.is_some() {
+        if self.hint.is_some() || self.queued == Some(Queued::Hint) {
             self.hint = None;
+            self.queued = None;
+            return;
+        }
+        // 解还没求出时先排队，求出后再给出提示
+        if self.solving.is_some() {
+            self.queued = Some(Queued::Hint);
             return;
         }
         // 1) 选择候选数最少的可编辑空格
@@ -420,38 +1384,145 @@ impl GameboardController {
             return;
         };
 
-        // 3) 基于求解结果得到该格正确值
-        let mut clone = self.gameboard.clone();
-        if !clone.solve() {
+        // 3) 从题目的解中取该格正确值
+        let Some(solution) = self.solution else {
             self.hint = None;
             return;
-        }
-        let val = clone.cells[ty][tx];
+        };
+        let val = solution[ty][tx];
         if (1..=9).contains(&val) {
             self.hint = Some(([tx, ty], val));
+            self.hints.push(GameEvent {
+                time: self.elapsed,
+                x: tx,
+                y: ty,
+                value: val,
+            });
         } else {
             self.hint = None;
         }
     }
 
+    /// 检查选中格：只标出这一格的对错，不透露棋盘其它部分的信息
+    pub fn check_selected(&mut self) {
+        if let Some(cell) = self.selected_editable() {
+            let mut cells = CellSet::default();
+            cells.insert(cell);
+            self.check(Some(cells));
+        }
+    }
+
+    /// 只检查选中格所在的行；在同一格上再按一次换成列，再按换成宫
+    pub fn check_unit(&mut self) {
+        let Some(cell) = self.selected_cell else {
+            return;
+        };
+        if self.submitted {
+            return;
+        }
+        let unit = match self.unit_check {
+            Some((last, unit)) if last == cell => unit.next(),
+            _ => Unit::Row,
+        };
+        self.unit_check = Some((cell, unit));
+        self.check(Some(unit.cells(cell)));
+    }
+
+    /// 检查全部已填的格子；与提交不同，棋盘不锁定，可以继续修改
+    pub fn check_all(&mut self) {
+        if !self.submitted {
+            self.check(None);
+        }
+    }
+
+    /// 与题目的解比对 `cells`（None 为全部格子）中玩家填写的数字
+    fn check(&mut self, cells: Option<CellSet>) {
+        if self.solving.is_some() {
+            self.queued = Some(Queued::Check(cells));
+            return;
+        }
+        let Some(solution) = self.solution else {
+            return;
+        };
+        self.has_checked = true;
+        for (y, row) in solution.iter().enumerate() {
+            for (x, &correct_val) in row.iter().enumerate() {
+                let value = self.gameboard.cells[y][x];
+                if cells.is_some_and(|set| !set.contains([x, y]))
+                    || self.initial_cells[y][x] != 0
+                    || value == 0
+                {
+                    continue;
+                }
+                if value == correct_val {
+                    self.checked.insert([x, y]);
+                } else {
+                    self.wrong.insert([x, y]);
+                }
+            }
+        }
+    }
+
+    /// 找出仍在棋盘上、最早填错的格子：按事件流找到每个填错的格子最后一次被填写的时间，
+    /// 取最早的一个选中并用红底标出，不透露正确值。没有填错时把已填的格子都标为正确
+    pub fn find_mistake(&mut self) {
+        if self.submitted {
+            return;
+        }
+        if self.solving.is_some() {
+            self.queued = Some(Queued::FindMistake);
+            return;
+        }
+        let Some(solution) = self.solution else {
+            return;
+        };
+        let wrong = |[x, y]: [usize; 2]| {
+            let value = self.gameboard.cells[y][x];
+            self.initial_cells[y][x] == 0 && value != 0 && value != solution[y][x]
+        };
+        // 读档恢复的格子没有事件，视为比所有事件都早（按行排列）
+        let written = |cell: [usize; 2]| {
+            self.events
+                .rposition(0, |e| [e.x, e.y] == cell)
+                .map_or(0, |i| i + 1)
+        };
+        let first = (0..9)
+            .flat_map(|y| (0..9).map(move |x| [x, y]))
+            .filter(|&cell| wrong(cell))
+            .min_by_key(|&cell| written(cell));
+        match first {
+            Some(cell) => {
+                self.wrong.insert(cell);
+                self.selected_cell = Some(cell);
+            }
+            None => self.check(None),
+        }
+    }
+
     /// 提交答案：锁定棋盘，将玩家输入与正确答案对比标记颜色
     pub fn submit(&mut self) {
         if self.submitted {
             return;
         }
-        // 计算正确答案（基于初始题面求解）
-        let mut solution = Gameboard::from_cells(self.initial_cells);
-        if !solution.solve() {
-            return; // 无解则不提交
+        if self.solving.is_some() {
+            self.queued = Some(Queued::Submit);
+            return;
         }
-        // 标记提交状态
+        // 正确答案（载入题目时已求出）
+        let Some(solution) = self.solution else {
+            return; // 无解则不提交
+        };
+        // 标记提交状态；提交后的颜色取代检查结果，未结束的假设分支按保留处理
         self.submitted = true;
+        self.branches.clear();
+        self.checked.clear();
+        self.wrong.clear();
         // 清除 Hint 和无效格标记（提交后用绿色/红分）
         self.hint = None;
         self.invalid_cells.clear();
         // 重新计算无效格：玩家输入与正确答案不符的标红
-        for y in 0..9 {
-            for x in 0..9 {
+        for (y, row) in solution.iter().enumerate() {
+            for (x, &correct_val) in row.iter().enumerate() {
                 if self.initial_cells[y][x] != 0 {
                     continue;
                 } // 只检查可编辑格
@@ -459,9 +1530,8 @@ impl GameboardController {
                 if player_val == 0 {
                     continue;
                 } // 空格不标记
-                let correct_val = solution.cells[y][x];
                 if player_val != correct_val {
-                    self.invalid_cells.push([x, y]); // 错误的加入 invalid
+                    self.invalid_cells.insert([x, y]); // 错误的加入 invalid
                 }
             }
         }
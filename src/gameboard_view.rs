@@ -1,9 +1,18 @@
 //! Gameboard view: render the Gameboard to the screen.
 
-use crate::gameboard_controller::GameboardController;
+use crate::action::{self, Action};
+use crate::gameboard_controller::{Flash, GameboardController, Unit, FLASH_TIME};
+use crate::text::{self, Align, DigitLayout};
 use graphics::character::CharacterCache;
 use graphics::types::Color;
 use graphics::{Context, Graphics};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::str::FromStr;
+use sudoku_core::logic::{Link, Pos};
+use sudoku_core::sandwich::Line;
+use sudoku_core::thermo::{self, Thermo};
+use sudoku_core::{Sandwich, Variant};
 
 /// Rendering settings for the board view.
 pub struct GameboardViewSettings {
@@ -11,6 +20,8 @@ pub struct GameboardViewSettings {
     pub position: [f64; 2],
     /// Board size in pixels (width == height)
     pub size: f64,
+    /// Window clear color (behind the board)
+    pub clear_color: Color,
     /// Background color
     pub background_color: Color,
     /// Outer board edge color
@@ -27,8 +38,28 @@ pub struct GameboardViewSettings {
     pub cell_edge_radius: f64,
     /// Selected cell background color
     pub selected_cell_background_color: Color,
+    /// Background of the cell under the mouse cursor (lighter than the selection)
+    pub hover_cell_background_color: Color,
+    /// Background tint for conflicting entries (assist)
+    pub conflict_cell_background_color: Color,
+    /// Outline of the row / column / box last checked with Shift + check
+    pub checked_unit_color: Color,
+    /// Background for editable cells still blank when the puzzle was submitted
+    pub unanswered_cell_color: Color,
+    /// Flash for a digit outside the cell's candidates (fades out over `FLASH_TIME`)
+    pub warning_cell_color: Color,
+    /// Flash for a digit rejected by strict mode
+    pub rejected_cell_color: Color,
     /// Text color for numbers
     pub text_color: Color,
+    /// Sandwich clue or thermometer that the filled digits can no longer satisfy
+    pub broken_clue_color: Color,
+    /// Thermometer bulb and stem (under the cell highlights and digits)
+    pub thermo_color: Color,
+    /// Wordoku: background of the diagonal spelling the hidden word once solved
+    pub hidden_word_color: Color,
+    /// Color mode: the color shown for each digit 1..=9
+    pub swatch_colors: [Color; 9],
     /// Padding inside the view (pixels) between board edge and cells
     pub padding: f64,
     /// Current window size — updated each frame by `main.rs` so view can layout overlays
@@ -42,6 +73,8 @@ pub struct GameboardViewSettings {
     pub btn_active_color: Color,
     pub btn_border_color: Color,
     pub btn_text_color: Color,
+    /// Label color of buttons whose action is currently unavailable.
+    pub btn_disabled_text_color: Color,
     /// HUD anchor position
     pub hud_anchor: HudAnchor,
     /// HUD font size
@@ -50,6 +83,33 @@ pub struct GameboardViewSettings {
     pub hud_bg_color: Color,
     /// HUD text color
     pub hud_text_color: Color,
+    /// Progress bar height (pixels)
+    pub progress_bar_height: f64,
+    /// Progress bar fill color (filled cells)
+    pub progress_fill_color: Color,
+    /// Progress bar color for cells verified correct after submit
+    pub progress_verified_color: Color,
+    /// Overlay dimming the board while a new puzzle is generated
+    pub spinner_overlay_color: Color,
+    /// Loading spinner arc color
+    pub spinner_color: Color,
+    /// Co-op player colors (cursor outline and entry tint), indexed by `net::color_index`
+    pub player_colors: [Color; 4],
+    /// Chain drawn over the board for the explained cell (links and candidate marks)
+    pub chain_color: Color,
+    /// Global UI scale (1.0 = 100%). Fonts, buttons, HUD and padding above are already
+    /// multiplied by it; drawing code scales its remaining fixed margins with `scaled`.
+    pub scale: f64,
+}
+
+/// Smallest and largest UI scale.
+pub const MIN_SCALE: f64 = 0.75;
+pub const MAX_SCALE: f64 = 2.0;
+
+impl Default for GameboardViewSettings {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameboardViewSettings {
@@ -58,6 +118,7 @@ impl GameboardViewSettings {
         Self {
             position: [10.0; 2],
             size: 400.0,
+            clear_color: [1.0; 4],
             background_color: [0.8, 0.8, 1.0, 1.0],
             board_edge_color: [0.0, 0.0, 0.2, 1.0],
             section_edge_color: [0.0, 0.0, 0.2, 1.0],
@@ -65,13 +126,45 @@ impl GameboardViewSettings {
             board_edge_radius: 3.0,
             section_edge_radius: 2.0,
             cell_edge_radius: 1.0,
-            selected_cell_background_color: [0.9, 0.9, 1.0, 1.0],
-            text_color: [0.0, 0.0, 0.1, 1.0],
+            selected_cell_background_color: [0.9, 0.95, 1.0, 0.6],
+            hover_cell_background_color: [1.0, 1.0, 1.0, 0.3],
+            conflict_cell_background_color: [1.0, 0.3, 0.3, 0.18],
+            checked_unit_color: [0.35, 0.45, 0.9, 1.0],
+            unanswered_cell_color: [0.5, 0.5, 0.5, 0.3],
+            warning_cell_color: [1.0, 0.7, 0.1, 0.45],
+            rejected_cell_color: [1.0, 0.2, 0.2, 0.55],
+            text_color: [0.0, 0.0, 0.0, 1.0],
+            broken_clue_color: [0.85, 0.15, 0.15, 1.0],
+            thermo_color: [0.5, 0.52, 0.6, 0.35],
+            hidden_word_color: [1.0, 0.8, 0.2, 0.45],
+            swatch_colors: [
+                [0.9, 0.2, 0.2, 1.0],
+                [0.98, 0.6, 0.1, 1.0],
+                [0.98, 0.88, 0.2, 1.0],
+                [0.3, 0.72, 0.3, 1.0],
+                [0.4, 0.8, 0.95, 1.0],
+                [0.15, 0.35, 0.85, 1.0],
+                [0.58, 0.35, 0.8, 1.0],
+                [0.98, 0.58, 0.8, 1.0],
+                [0.5, 0.32, 0.18, 1.0],
+            ],
             padding: 10.0,
             hud_anchor: HudAnchor::TopLeft,
             hud_font_size: 14,
             hud_bg_color: [1.0, 1.0, 1.0, 0.85],
             hud_text_color: [0.0, 0.0, 0.0, 0.85],
+            progress_bar_height: 4.0,
+            progress_fill_color: [0.35, 0.45, 0.9, 1.0],
+            progress_verified_color: [0.2, 0.8, 0.2, 1.0],
+            spinner_overlay_color: [1.0, 1.0, 1.0, 0.6],
+            spinner_color: [0.35, 0.45, 0.9, 1.0],
+            player_colors: [
+                [0.25, 0.45, 0.95, 1.0],
+                [0.95, 0.55, 0.1, 1.0],
+                [0.2, 0.7, 0.3, 1.0],
+                [0.6, 0.3, 0.85, 1.0],
+            ],
+            chain_color: [0.9, 0.45, 0.1, 0.9],
             window_size: [512.0, 512.0],
             btn_width: 96.0,
             btn_height: 14.0 + 10.0,
@@ -81,6 +174,133 @@ impl GameboardViewSettings {
             btn_active_color: [0.75, 0.85, 1.0, 1.0],
             btn_border_color: [0.2, 0.2, 0.25, 1.0],
             btn_text_color: [0.05, 0.05, 0.08, 1.0],
+            btn_disabled_text_color: [0.62, 0.62, 0.66, 1.0],
+            scale: 1.0,
+        }
+    }
+
+    /// Set the UI scale (clamped to `MIN_SCALE..=MAX_SCALE`), resizing fonts, buttons,
+    /// HUD and padding from their defaults. Colors are left untouched.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        let base = Self::new();
+        let scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+        self.scale = scale;
+        self.padding = base.padding * scale;
+        self.btn_width = base.btn_width * scale;
+        self.btn_height = base.btn_height * scale;
+        self.btn_spacing = base.btn_spacing * scale;
+        self.hud_font_size = (base.hud_font_size as f64 * scale).round() as u32;
+        self.progress_bar_height = base.progress_bar_height * scale;
+        self.board_edge_radius = base.board_edge_radius * scale;
+        self.section_edge_radius = base.section_edge_radius * scale;
+        self.cell_edge_radius = base.cell_edge_radius * scale;
+        self
+    }
+
+    /// A fixed layout size (pixels at 100%) at the current scale.
+    pub fn scaled(&self, px: f64) -> f64 {
+        px * self.scale
+    }
+
+    /// Vertical space kept free below the board for the button row (and mirrored above
+    /// it for the HUD), so the buttons stay inside the window at large scales.
+    pub fn button_area(&self) -> f64 {
+        self.btn_height + self.scaled(12.0)
+    }
+
+    /// Margin kept left of and above the board for Sandwich Sudoku clues.
+    pub fn clue_area(&self) -> f64 {
+        self.scaled(26.0)
+    }
+
+    /// Margin kept left of the board (and mirrored right of it) for the color mode palette.
+    pub fn palette_area(&self) -> f64 {
+        self.scaled(30.0 + 2.0 * 8.0)
+    }
+
+    /// Height of the HUD box (two text lines and the progress bar) plus its margin.
+    pub fn hud_area(&self) -> f64 {
+        let font = self.hud_font_size as f64;
+        self.scaled(8.0) + 2.0 * font + self.progress_bar_height + 4.0 * self.scaled(6.0)
+    }
+}
+
+impl GameboardViewSettings {
+    /// Apply a color theme on top of the current settings.
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        match theme {
+            Theme::Light => {
+                let light = Self::new();
+                self.clear_color = light.clear_color;
+                self.background_color = light.background_color;
+                self.board_edge_color = light.board_edge_color;
+                self.section_edge_color = light.section_edge_color;
+                self.cell_edge_color = light.cell_edge_color;
+                self.selected_cell_background_color = light.selected_cell_background_color;
+                self.hover_cell_background_color = light.hover_cell_background_color;
+                self.text_color = light.text_color;
+                self.thermo_color = light.thermo_color;
+                self.hud_bg_color = light.hud_bg_color;
+                self.hud_text_color = light.hud_text_color;
+                self.btn_bg_color = light.btn_bg_color;
+                self.btn_hover_color = light.btn_hover_color;
+                self.btn_active_color = light.btn_active_color;
+                self.btn_border_color = light.btn_border_color;
+                self.btn_text_color = light.btn_text_color;
+                self.btn_disabled_text_color = light.btn_disabled_text_color;
+                self.spinner_overlay_color = light.spinner_overlay_color;
+            }
+            Theme::Dark => {
+                self.clear_color = [0.1, 0.1, 0.12, 1.0];
+                self.background_color = [0.16, 0.17, 0.22, 1.0];
+                self.board_edge_color = [0.75, 0.78, 0.9, 1.0];
+                self.section_edge_color = [0.75, 0.78, 0.9, 1.0];
+                self.cell_edge_color = [0.45, 0.47, 0.58, 1.0];
+                self.selected_cell_background_color = [0.3, 0.35, 0.5, 0.6];
+                self.hover_cell_background_color = [1.0, 1.0, 1.0, 0.08];
+                self.text_color = [0.92, 0.92, 0.95, 1.0];
+                self.thermo_color = [0.6, 0.63, 0.75, 0.3];
+                self.hud_bg_color = [0.15, 0.15, 0.18, 0.85];
+                self.hud_text_color = [0.9, 0.9, 0.92, 0.9];
+                self.btn_bg_color = [0.22, 0.23, 0.28, 1.0];
+                self.btn_hover_color = [0.3, 0.32, 0.42, 1.0];
+                self.btn_active_color = [0.35, 0.42, 0.6, 1.0];
+                self.btn_border_color = [0.6, 0.62, 0.7, 1.0];
+                self.btn_text_color = [0.92, 0.92, 0.95, 1.0];
+                self.btn_disabled_text_color = [0.45, 0.46, 0.52, 1.0];
+                self.spinner_overlay_color = [0.1, 0.1, 0.12, 0.6];
+            }
+        }
+        self
+    }
+}
+
+/// Color themes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// The other theme (for the toggle shortcut)
+    pub fn toggled(self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Light,
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "light" => Ok(Theme::Light),
+            "dark" => Ok(Theme::Dark),
+            _ => Err(format!("unknown theme {:?} (light, dark)", s)),
         }
     }
 }
@@ -98,15 +318,23 @@ pub enum HudAnchor {
 pub struct GameboardView {
     /// View settings
     pub settings: GameboardViewSettings,
+    /// Digit positions for the current cell size, re-measured after a resize or rescale
+    digits: Cell<Option<DigitLayout>>,
+    /// Where the sidebar was drawn this frame ([x, y, w, h] and line height), for clicks
+    sidebar: Cell<Option<([f64; 4], f64)>>,
 }
 
 impl GameboardView {
     /// Create a new view with given settings.
     pub fn new(settings: GameboardViewSettings) -> Self {
-        GameboardView { settings }
+        GameboardView {
+            settings,
+            digits: Cell::new(None),
+            sidebar: Cell::new(None),
+        }
     }
 
-    /// Draw the board using the provided graphics context and glyph cache.
+    /// Draw the full playing screen: board, buttons, HUD and loading spinner.
     pub fn draw<G: Graphics, C>(
         &self,
         controller: &GameboardController,
@@ -116,39 +344,667 @@ impl GameboardView {
     ) where
         C: CharacterCache<Texture = G::Texture>,
     {
-        use graphics::{Image, Line, Rectangle, Transformed};
+        self.sidebar.set(None);
+        self.draw_board(controller, glyphs, c, g);
+        if let Some(Variant::Sandwich(sandwich)) = &controller.variant {
+            let broken = sandwich.broken(&controller.gameboard);
+            self.draw_sandwich(sandwich, &broken, glyphs, c, g);
+        }
+        self.draw_buttons(controller, glyphs, c, g);
+        if controller.assist.colors {
+            self.draw_palette(controller, c, g);
+        }
+        self.draw_hud(controller, glyphs, c, g);
+
+        if let Some(pending) = &controller.pending {
+            self.draw_spinner(self.board_rect(), pending.waited, c, g);
+        }
+    }
 
-        let ref settings = self.settings;
-        let board_rect = [
+    /// Outer board rect [x, y, w, h].
+    pub fn board_rect(&self) -> [f64; 4] {
+        let settings = &self.settings;
+        [
             settings.position[0],
             settings.position[1],
             settings.size,
             settings.size,
+        ]
+    }
+
+    /// Screen rect [x, y, w, h] of the cell at `[x, y]`.
+    pub fn cell_rect(&self, [x, y]: [usize; 2]) -> [f64; 4] {
+        let settings = &self.settings;
+        let inner_size = (settings.size - 2.0 * settings.padding).max(16.0);
+        let cell_size = inner_size / 9.0;
+        [
+            settings.position[0] + settings.padding + x as f64 * cell_size,
+            settings.position[1] + settings.padding + y as f64 * cell_size,
+            cell_size,
+            cell_size,
+        ]
+    }
+
+    /// Sandwich clues in the margins: each row's left of the board, each column's
+    /// above it. Clues in `broken` are drawn in `broken_clue_color`.
+    fn draw_sandwich<G: Graphics, C>(
+        &self,
+        sandwich: &Sandwich,
+        broken: &[Line],
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let settings = &self.settings;
+        let [bx, by, _, _] = self.board_rect();
+        let area = settings.clue_area();
+        for i in 0..9 {
+            let [x, y, w, h] = self.cell_rect([i, i]);
+            for (line, rect) in [
+                (Line::Row(i), [bx - area, y, area, h]),
+                (Line::Column(i), [x, by - area, w, area]),
+            ] {
+                let color = if broken.contains(&line) {
+                    settings.broken_clue_color
+                } else {
+                    settings.text_color
+                };
+                text::draw_centered(
+                    &sandwich.clue(line).to_string(),
+                    settings.hud_font_size,
+                    color,
+                    rect,
+                    glyphs,
+                    c,
+                    g,
+                );
+            }
+        }
+    }
+
+    /// Thermometers: a round bulb on the first cell and a thick stem through the
+    /// centers of the rest. Thermometers in `broken` are tinted `broken_clue_color`.
+    fn draw_thermos<G: Graphics>(
+        &self,
+        thermos: &[Thermo],
+        broken: &[usize],
+        c: &Context,
+        g: &mut G,
+    ) {
+        use graphics::{Ellipse, Line};
+
+        let center = |(r, col): (usize, usize)| {
+            let [x, y, w, h] = self.cell_rect([col, r]);
+            [x + w / 2.0, y + h / 2.0]
+        };
+        let size = self.cell_rect([0, 0])[2];
+        for (i, thermo) in thermos.iter().enumerate() {
+            let mut color = self.settings.thermo_color;
+            if broken.contains(&i) {
+                color = self.settings.broken_clue_color;
+                color[3] = self.settings.thermo_color[3];
+            }
+            let stem = Line::new_round(color, size * 0.12);
+            for pair in thermo.cells.windows(2) {
+                let (a, b) = (center(pair[0]), center(pair[1]));
+                stem.draw([a[0], a[1], b[0], b[1]], &c.draw_state, c.transform, g);
+            }
+            let [x, y] = center(thermo.cells[0]);
+            let radius = size * 0.32;
+            Ellipse::new(color).draw(
+                [x - radius, y - radius, 2.0 * radius, 2.0 * radius],
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+    }
+
+    /// Co-op overlay: tint cells by the player who last entered them and outline
+    /// the other players' selected cells, each in that player's color.
+    /// `owners` holds color indices (`None` for givens and untouched cells).
+    pub fn draw_peers<G: Graphics>(
+        &self,
+        owners: &[[Option<usize>; 9]; 9],
+        cursors: &[(usize, [usize; 2])],
+        c: &Context,
+        g: &mut G,
+    ) {
+        use graphics::Rectangle;
+
+        let colors = &self.settings.player_colors;
+        for (y, row) in owners.iter().enumerate() {
+            for (x, owner) in row.iter().enumerate() {
+                if let Some(i) = owner {
+                    let mut tint = colors[i % colors.len()];
+                    tint[3] = 0.18;
+                    Rectangle::new(tint).draw(
+                        self.cell_rect([x, y]),
+                        &c.draw_state,
+                        c.transform,
+                        g,
+                    );
+                }
+            }
+        }
+        for &(i, cell) in cursors {
+            Rectangle::new_border(colors[i % colors.len()], self.settings.scaled(1.5)).draw(
+                self.cell_rect(cell),
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+    }
+
+    /// Draw a chain over the board: each candidate is marked at its pencil-mark
+    /// spot (digit `n` in cell `n` of a 3x3 grid inside the cell), and each link is
+    /// an arrow between two marks, solid for strong links and dashed for weak ones.
+    pub fn draw_links<G: Graphics, C>(&self, links: &[Link], glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::{Ellipse, Line};
+
+        let color = self.settings.chain_color;
+        let width = self.settings.scaled(1.5);
+        let spot = |((r, col), digit): (Pos, u8)| {
+            let [x, y, w, _] = self.cell_rect([col, r]);
+            let i = (digit - 1) as f64;
+            [
+                x + w * ((i % 3.0).floor() + 0.5) / 3.0,
+                y + w * ((i / 3.0).floor() + 0.5) / 3.0,
+            ]
+        };
+        let radius = self.cell_rect([0, 0])[2] / 7.0;
+
+        for link in links {
+            let (a, b) = (spot(link.from), spot(link.to));
+            let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+            let len = dx.hypot(dy);
+            if len <= 2.0 * radius {
+                continue;
+            }
+            let (ux, uy) = (dx / len, dy / len);
+            // from the edge of one mark to the edge of the other
+            let start = [a[0] + ux * radius, a[1] + uy * radius];
+            let end = [b[0] - ux * radius, b[1] - uy * radius];
+            let line = Line::new_round(color, width / 2.0);
+            if link.strong {
+                line.draw(
+                    [start[0], start[1], end[0], end[1]],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            } else {
+                let dash = self.settings.scaled(5.0);
+                let mut t = 0.0;
+                while t < len - 2.0 * radius {
+                    let t2 = (t + dash).min(len - 2.0 * radius);
+                    let p = [start[0] + ux * t, start[1] + uy * t];
+                    let q = [start[0] + ux * t2, start[1] + uy * t2];
+                    line.draw([p[0], p[1], q[0], q[1]], &c.draw_state, c.transform, g);
+                    t += 2.0 * dash;
+                }
+            }
+            // arrowhead: two short strokes back from the end, 30 degrees either side
+            let head = radius * 0.9;
+            for side in [-1.0, 1.0] {
+                let (sin, cos) = (side * 0.5, 0.866);
+                let hx = -(ux * cos - uy * sin) * head;
+                let hy = -(uy * cos + ux * sin) * head;
+                line.draw(
+                    [end[0], end[1], end[0] + hx, end[1] + hy],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+        }
+
+        let mut marks: Vec<(Pos, u8)> = links.iter().flat_map(|l| [l.from, l.to]).collect();
+        marks.dedup();
+        let font_size = ((radius * 1.6) as u32).max(8);
+        for &mark in &marks {
+            let [x, y] = spot(mark);
+            let mut fill = color;
+            fill[3] = 0.15;
+            let rect = [x - radius, y - radius, 2.0 * radius, 2.0 * radius];
+            Ellipse::new(fill)
+                .border(graphics::ellipse::Border {
+                    color,
+                    radius: width / 2.0,
+                })
+                .draw(rect, &c.draw_state, c.transform, g);
+            text::draw_centered(&mark.1.to_string(), font_size, color, rect, glyphs, c, g);
+        }
+    }
+
+    /// Number wheel layout around `cell`: (center, radius of the ring the digits sit
+    /// on, radius of each digit's disc). The center is kept far enough from the
+    /// window edges that the whole wheel stays visible.
+    fn wheel_layout(&self, cell: [usize; 2]) -> ([f64; 2], f64, f64) {
+        let [x, y, size, _] = self.cell_rect(cell);
+        let (ring, item) = (size * 1.25, size * 0.42);
+        let reach = ring + item;
+        let window = self.settings.window_size;
+        let clamp = |v: f64, max: f64| v.min(max - reach).max(reach);
+        let center = [
+            clamp(x + size / 2.0, window[0]),
+            clamp(y + size / 2.0, window[1]),
         ];
+        (center, ring, item)
+    }
+
+    /// Center of digit `digit`'s disc on the wheel: 1 at the top, then clockwise.
+    fn wheel_spot(center: [f64; 2], ring: f64, digit: u8) -> [f64; 2] {
+        let angle = (digit - 1) as f64 * std::f64::consts::TAU / 9.0;
+        [
+            center[0] + ring * angle.sin(),
+            center[1] - ring * angle.cos(),
+        ]
+    }
+
+    /// Digit of the number wheel around `cell` under `pos`: the slice of the ring
+    /// it points into, or `None` in the middle of the wheel and outside it.
+    pub fn wheel_digit(&self, cell: [usize; 2], pos: [f64; 2]) -> Option<u8> {
+        let (center, ring, item) = self.wheel_layout(cell);
+        let (dx, dy) = (pos[0] - center[0], pos[1] - center[1]);
+        let distance = dx.hypot(dy);
+        if distance < ring - item || distance > ring + item {
+            return None;
+        }
+        let slice = std::f64::consts::TAU / 9.0;
+        let angle = dx.atan2(-dy).rem_euclid(std::f64::consts::TAU);
+        Some(((angle / slice).round() as u8) % 9 + 1)
+    }
+
+    /// Draw the number wheel around `cell`, highlighting the digit under `pos`.
+    /// Digits show as the board shows them: digits, letters or color swatches.
+    pub fn draw_wheel<G: Graphics, C>(
+        &self,
+        controller: &GameboardController,
+        cell: [usize; 2],
+        pos: [f64; 2],
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Ellipse;
+
+        let settings = &self.settings;
+        let (center, ring, item) = self.wheel_layout(cell);
+        let hovered = self.wheel_digit(cell, pos);
+        let border = graphics::ellipse::Border {
+            color: settings.btn_border_color,
+            radius: settings.scaled(1.0),
+        };
+        let mut backdrop = settings.btn_bg_color;
+        backdrop[3] = 0.6;
+        let reach = ring + item;
+        Ellipse::new(backdrop).draw(
+            [
+                center[0] - reach,
+                center[1] - reach,
+                2.0 * reach,
+                2.0 * reach,
+            ],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        let font_size = ((item * 1.1) as u32).max(10);
+        let symbols = controller.gameboard.symbols();
+        for digit in 1..=9u8 {
+            let [x, y] = Self::wheel_spot(center, ring, digit);
+            let rect = [x - item, y - item, 2.0 * item, 2.0 * item];
+            if controller.assist.colors {
+                // 色块模式：悬停的颜色加粗边框
+                let radius = if hovered == Some(digit) { 3.0 } else { 1.0 };
+                Ellipse::new(settings.swatch_colors[digit as usize - 1])
+                    .border(graphics::ellipse::Border {
+                        radius: settings.scaled(radius),
+                        ..border
+                    })
+                    .draw(rect, &c.draw_state, c.transform, g);
+                continue;
+            }
+            let fill = if hovered == Some(digit) {
+                settings.btn_active_color
+            } else {
+                settings.btn_bg_color
+            };
+            Ellipse::new(fill)
+                .border(border)
+                .draw(rect, &c.draw_state, c.transform, g);
+            text::draw_centered(
+                &symbols[digit as usize - 1].to_string(),
+                font_size,
+                settings.btn_text_color,
+                rect,
+                glyphs,
+                c,
+                g,
+            );
+        }
+    }
+
+    /// Draw `digit` faintly in the empty cell at `cell` (the gamepad's digit wheel).
+    pub fn draw_preview<G: Graphics, C>(
+        &self,
+        controller: &GameboardController,
+        cell: [usize; 2],
+        digit: u8,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let rect = self.cell_rect(cell);
+        if controller.assist.colors {
+            self.draw_swatch(digit, rect, false, None, 0.3, c, g);
+            return;
+        }
+        let [x, y, cell_size, _] = rect;
+        let font_size = ((cell_size * 0.65) as u32).max(12);
+        let mut color = self.settings.text_color;
+        color[3] = 0.3;
+        self.digit_layout(glyphs, font_size, cell_size, controller.gameboard.symbols())
+            .draw(digit, color, [x, y], glyphs, c, g);
+    }
+
+    /// Color mode: draw `val`'s color in the cell `rect`, as a square for givens and
+    /// a disc for entries, with `alpha` opacity and an optional `outline` ring that
+    /// stands in for the digit colors (wrong, checked, in a branch).
+    #[allow(clippy::too_many_arguments)]
+    fn draw_swatch<G: Graphics>(
+        &self,
+        val: u8,
+        [x, y, w, h]: [f64; 4],
+        given: bool,
+        outline: Option<Color>,
+        alpha: f32,
+        c: &Context,
+        g: &mut G,
+    ) {
+        use graphics::{Ellipse, Rectangle};
+
+        if !(1..=9).contains(&val) {
+            return;
+        }
+        let mut color = self.settings.swatch_colors[val as usize - 1];
+        color[3] *= alpha;
+        let inset = if given { 0.12 } else { 0.18 } * w;
+        let rect = [x + inset, y + inset, w - 2.0 * inset, h - 2.0 * inset];
+        let ring = w * 0.07;
+        if given {
+            let mut shape = Rectangle::new(color);
+            if let Some(outline) = outline {
+                shape = shape.border(graphics::rectangle::Border {
+                    color: outline,
+                    radius: ring / 2.0,
+                });
+            }
+            shape.draw(rect, &c.draw_state, c.transform, g);
+        } else {
+            let mut shape = Ellipse::new(color);
+            if let Some(outline) = outline {
+                shape = shape.border(graphics::ellipse::Border {
+                    color: outline,
+                    radius: ring / 2.0,
+                });
+            }
+            shape.draw(rect, &c.draw_state, c.transform, g);
+        }
+    }
+
+    /// Color mode palette: nine color swatches and an eraser in a column left of
+    /// the board, inside `palette_area`.
+    fn palette_rects(&self) -> Vec<[f64; 4]> {
+        let settings = &self.settings;
+        let (side, gap, margin) = (
+            settings.scaled(30.0),
+            settings.scaled(6.0),
+            settings.scaled(8.0),
+        );
+        let total = 10.0 * side + 9.0 * gap;
+        let top = settings.position[1] + (settings.size - total) / 2.0;
+        (0..10)
+            .map(|i| [margin, top + i as f64 * (side + gap), side, side])
+            .collect()
+    }
+
+    /// Digit of the palette swatch under `pos` (0 for the eraser), if any.
+    pub fn palette_at(&self, pos: [f64; 2]) -> Option<u8> {
+        let index = self.palette_rects().iter().position(|r| {
+            pos[0] >= r[0] && pos[0] < r[0] + r[2] && pos[1] >= r[1] && pos[1] < r[1] + r[3]
+        })?;
+        Some((index as u8 + 1) % 10)
+    }
+
+    /// Draw the color mode palette; the swatch under the cursor and the color of
+    /// the selected cell get a thicker border.
+    fn draw_palette<G: Graphics>(&self, controller: &GameboardController, c: &Context, g: &mut G) {
+        use graphics::{Line, Rectangle};
+
+        let settings = &self.settings;
+        let hovered = self.palette_at(controller.cursor_pos);
+        let current = controller
+            .selected_cell
+            .map(|[x, y]| controller.gameboard.cells[y][x]);
+        for (digit, rect) in (1..=10u8).map(|i| i % 10).zip(self.palette_rects()) {
+            let fill = match digit {
+                0 => settings.btn_bg_color,
+                d => settings.swatch_colors[d as usize - 1],
+            };
+            let radius = if hovered == Some(digit) || current == Some(digit) && digit != 0 {
+                3.0
+            } else {
+                1.0
+            };
+            Rectangle::new(fill)
+                .border(graphics::rectangle::Border {
+                    color: settings.btn_border_color,
+                    radius: settings.scaled(radius) / 2.0,
+                })
+                .draw(rect, &c.draw_state, c.transform, g);
+            if digit == 0 {
+                // 橡皮擦：一个叉
+                let [x, y, w, h] = rect;
+                let pad = w * 0.28;
+                let cross = Line::new_round(settings.btn_text_color, settings.scaled(1.5));
+                cross.draw(
+                    [x + pad, y + pad, x + w - pad, y + h - pad],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+                cross.draw(
+                    [x + w - pad, y + pad, x + pad, y + h - pad],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+        }
+    }
+
+    /// Cached digit layout for `size` / `cell_size` / `symbols`, measured again when
+    /// any of them changes.
+    fn digit_layout<C: CharacterCache>(
+        &self,
+        glyphs: &mut C,
+        size: u32,
+        cell_size: f64,
+        symbols: [char; 9],
+    ) -> DigitLayout {
+        match self.digits.get() {
+            Some(layout) if layout.matches(size, cell_size, symbols) => layout,
+            _ => {
+                let layout = DigitLayout::new(glyphs, size, cell_size, symbols);
+                self.digits.set(Some(layout));
+                layout
+            }
+        }
+    }
+
+    /// Draw only the board (cells, digits, grid lines) without buttons or HUD.
+    pub fn draw_board<G: Graphics, C>(
+        &self,
+        controller: &GameboardController,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::{Line, Rectangle};
+
+        let settings = &self.settings;
+        let board_rect = self.board_rect();
 
         // Draw board background.
         Rectangle::new(settings.background_color).draw(board_rect, &c.draw_state, c.transform, g);
 
+        // Thermometers sit under the cell highlights and digits.
+        if let Some(Variant::Thermo(thermos)) = &controller.variant {
+            let broken = thermo::broken(thermos, &controller.gameboard);
+            self.draw_thermos(thermos, &broken, c, g);
+        }
+
         // Compute inner board area (respect padding) so board doesn't touch window edges
         let inner_left = settings.position[0] + settings.padding;
         let inner_top = settings.position[1] + settings.padding;
         let inner_size = (settings.size - 2.0 * settings.padding).max(16.0);
         let cell_size = inner_size / 9.0;
 
-        // Draw selected cell background (selected_cell stored as [x, y]).
-        if let Some(ind) = controller.selected_cell {
+        // 鼠标下的格子（点击前的预选）用比选中格更浅的底色标出
+        let hovered = controller.cell_at(settings.position, settings.size);
+        if let Some(cell) = hovered.filter(|&cell| controller.selected_cell != Some(cell)) {
+            Rectangle::new(settings.hover_cell_background_color).draw(
+                self.cell_rect(cell),
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+
+        // 多选的格子与选中格同样的底色
+        for cell in controller.multi.iter() {
+            Rectangle::new(settings.selected_cell_background_color).draw(
+                self.cell_rect(cell),
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+
+        // Draw selected cell background (selected_cell stored as [x, y]); while an
+        // arrow-key move is animating it sits between the old and new cell.
+        if let Some(ind) = controller.selection_pos() {
             let pos = [
-                inner_left + ind[0] as f64 * cell_size,
-                inner_top + ind[1] as f64 * cell_size,
+                inner_left + ind[0] * cell_size,
+                inner_top + ind[1] * cell_size,
             ];
             let cell_rect = [pos[0], pos[1], cell_size, cell_size];
             // subtle semi-transparent highlight (no thick border)
-            Rectangle::new([0.9, 0.95, 1.0, 0.6]).draw(cell_rect, &c.draw_state, c.transform, g);
+            Rectangle::new(settings.selected_cell_background_color).draw(
+                cell_rect,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+
+        // 提交前的冲突提示（辅助功能，可在配置中关闭），以及玩家主动检查出的错误
+        if !controller.submitted && !controller.show_all {
+            let mut marked = controller.wrong;
+            if controller.assist.highlight_conflicts {
+                controller
+                    .invalid_cells
+                    .iter()
+                    .for_each(|cell| marked.insert(cell));
+            }
+            for [col, row] in marked.iter() {
+                let cell_rect = [
+                    inner_left + col as f64 * cell_size,
+                    inner_top + row as f64 * cell_size,
+                    cell_size,
+                    cell_size,
+                ];
+                Rectangle::new(settings.conflict_cell_background_color).draw(
+                    cell_rect,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+        }
+
+        // 提交后留空的可编辑格整格标灰
+        if controller.submitted && !controller.show_all {
+            for row in 0..9 {
+                for col in 0..9 {
+                    if controller.initial_cells[row][col] == 0
+                        && controller.gameboard.cells[row][col] == 0
+                    {
+                        let cell_rect = [
+                            inner_left + col as f64 * cell_size,
+                            inner_top + row as f64 * cell_size,
+                            cell_size,
+                            cell_size,
+                        ];
+                        Rectangle::new(settings.unanswered_cell_color).draw(
+                            cell_rect,
+                            &c.draw_state,
+                            c.transform,
+                            g,
+                        );
+                    }
+                }
+            }
+        }
+
+        // 单词数独解出后标出对角线上的隐藏单词
+        if controller.hidden_word().is_some() {
+            for i in 0..9 {
+                Rectangle::new(settings.hidden_word_color).draw(
+                    self.cell_rect([i, i]),
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+        }
+
+        // Brief flash: amber for a digit the candidates already rule out,
+        // red for one strict mode refused to write.
+        if let Some(([col, row], flash, left)) = controller.flash {
+            let mut color = match flash {
+                Flash::Warning => settings.warning_cell_color,
+                Flash::Rejected => settings.rejected_cell_color,
+            };
+            color[3] *= (left / FLASH_TIME).clamp(0.0, 1.0) as f32;
+            let cell_rect = [
+                inner_left + col as f64 * cell_size,
+                inner_top + row as f64 * cell_size,
+                cell_size,
+                cell_size,
+            ];
+            Rectangle::new(color).draw(cell_rect, &c.draw_state, c.transform, g);
         }
 
         // Draw characters with styling: initial cells black; player input red
         // Choose font size relative to cell size for responsiveness
         let font_size = ((cell_size * 0.65) as u32).max(12);
+        let digits =
+            self.digit_layout(glyphs, font_size, cell_size, controller.gameboard.symbols());
 
         for row in 0..9 {
             for col in 0..9 {
@@ -160,6 +1016,7 @@ impl GameboardView {
                 // 颜色策略：
                 // - 提交后：玩家输入正确显示绿色，错误显示红色
                 // - 提交前：玩家输入全程标红；初始题面为黑色
+                // - 假设分支中改过的格子为紫色，保留或放弃分支后恢复
                 // - Show All 开启时跳过玩家输入的绘制（只显示初始题面）
                 let text_color = if controller.initial_cells[row][col] == 0 {
                     if controller.show_all {
@@ -167,82 +1024,94 @@ impl GameboardView {
                     }
                     if controller.submitted {
                         // 提交后：错误红色，正确绿色
-                        if controller.invalid_cells.contains(&[col, row]) {
+                        if controller.invalid_cells.contains([col, row]) {
                             [1.0, 0.2, 0.2, 1.0] // 错误：红色
                         } else {
                             [0.2, 0.8, 0.2, 1.0] // 正确：绿色
                         }
+                    } else if controller.in_branch([col, row]) {
+                        [0.6, 0.3, 0.9, 1.0] // 假设分支中的输入：紫色
+                    } else if controller.checked.contains([col, row]) {
+                        [0.2, 0.8, 0.2, 1.0] // 检查过且正确：绿色
                     } else {
                         [1.0, 0.2, 0.2, 1.0] // 提交前：红色
                     }
                 } else {
-                    [0.0, 0.0, 0.0, 1.0] // 初始题面：黑色
+                    settings.text_color // 初始题面：黑色（随主题）
                 };
 
-                if let Some(ch) = std::char::from_digit(val as u32, 10) {
-                    let cell_left = inner_left + col as f64 * cell_size;
-                    let cell_top = inner_top + row as f64 * cell_size;
-                    if let Ok(character) = glyphs.character(font_size, ch) {
-                        // center the glyph using atlas_size and character metrics
-                        let glyph_w = character.atlas_size[0] as f64;
-                        let glyph_h = character.atlas_size[1] as f64;
-                        let ch_x = cell_left + (cell_size - glyph_w) / 2.0 + character.left();
-                        let ch_y = cell_top + (cell_size + glyph_h) / 2.0 - character.top();
-
-                        let img = Image::new_color(text_color);
-                        img.src_rect([
-                            character.atlas_offset[0],
-                            character.atlas_offset[1],
-                            character.atlas_size[0],
-                            character.atlas_size[1],
-                        ])
-                        .draw(
-                            character.texture,
-                            &c.draw_state,
-                            c.transform.trans(ch_x, ch_y),
-                            g,
-                        );
+                let origin = [
+                    inner_left + col as f64 * cell_size,
+                    inner_top + row as f64 * cell_size,
+                ];
+                if controller.assist.colors {
+                    // 色块模式：题面为方块、输入为圆点，输入的对错 / 检查 / 分支改用外圈颜色
+                    let given = controller.initial_cells[row][col] != 0;
+                    let marked = controller.submitted
+                        || controller.in_branch([col, row])
+                        || controller.checked.contains([col, row]);
+                    let outline = (!given && marked).then_some(text_color);
+                    let rect = [origin[0], origin[1], cell_size, cell_size];
+                    self.draw_swatch(val, rect, given, outline, 1.0, c, g);
+                    continue;
+                }
+                digits.draw(val, text_color, origin, glyphs, c, g);
+            }
+        }
+
+        // 提交后在填错的格子右上角用小号绿字标出正确的数字
+        if controller.submitted && !controller.show_all {
+            if let Some(solved) = controller.solution {
+                let size = ((cell_size * 0.28) as u32).max(8);
+                let margin = cell_size * 0.08;
+                for [col, row] in controller.invalid_cells.iter() {
+                    let corner = [
+                        inner_left + (col + 1) as f64 * cell_size - margin,
+                        inner_top + row as f64 * cell_size + margin + size as f64,
+                    ];
+                    if controller.assist.colors {
+                        let side = size as f64;
+                        let rect = [corner[0] - side, corner[1] - side, side, side];
+                        self.draw_swatch(solved[row][col], rect, false, None, 1.0, c, g);
+                        continue;
                     }
+                    let symbol = controller.gameboard.symbol(solved[row][col]);
+                    text::draw_anchored(
+                        &symbol.map(String::from).unwrap_or_default(),
+                        size,
+                        [0.2, 0.6, 0.2, 1.0],
+                        corner,
+                        Align::Right,
+                        glyphs,
+                        c,
+                        g,
+                    );
                 }
             }
         }
 
         // 显示全部答案（浅蓝色），覆盖所有可编辑格（含已输入），先绘制，单个提示会覆盖
         if controller.show_all {
-            if let Some(solved) = controller.solved_cache {
-                for row in 0..9 {
-                    for col in 0..9 {
+            if let Some(solved) = controller.solution {
+                for (row, solved_row) in solved.iter().enumerate() {
+                    for (col, &val) in solved_row.iter().enumerate() {
                         if controller.initial_cells[row][col] != 0 {
                             continue;
                         }
-                        let val = solved[row][col];
                         if val == 0 {
                             continue;
                         }
-                        if let Some(ch) = std::char::from_digit(val as u32, 10) {
-                            let cell_left = inner_left + col as f64 * cell_size;
-                            let cell_top = inner_top + row as f64 * cell_size;
-                            if let Ok(character) = glyphs.character(font_size, ch) {
-                                let glyph_w = character.atlas_size[0] as f64;
-                                let glyph_h = character.atlas_size[1] as f64;
-                                let ch_x =
-                                    cell_left + (cell_size - glyph_w) / 2.0 + character.left();
-                                let ch_y = cell_top + (cell_size + glyph_h) / 2.0 - character.top();
-                                let img = Image::new_color([0.2, 0.6, 1.0, 0.9]);
-                                img.src_rect([
-                                    character.atlas_offset[0],
-                                    character.atlas_offset[1],
-                                    character.atlas_size[0],
-                                    character.atlas_size[1],
-                                ])
-                                .draw(
-                                    character.texture,
-                                    &c.draw_state,
-                                    c.transform.trans(ch_x, ch_y),
-                                    g,
-                                );
-                            }
+                        let origin = [
+                            inner_left + col as f64 * cell_size,
+                            inner_top + row as f64 * cell_size,
+                        ];
+                        if controller.assist.colors {
+                            let rect = [origin[0], origin[1], cell_size, cell_size];
+                            let outline = Some([0.2, 0.6, 1.0, 0.9]);
+                            self.draw_swatch(val, rect, false, outline, 0.6, c, g);
+                            continue;
                         }
+                        digits.draw(val, [0.2, 0.6, 1.0, 0.9], origin, glyphs, c, g);
                     }
                 }
             }
@@ -253,28 +1122,16 @@ impl GameboardView {
             let col = pos[0];
             let row = pos[1];
             if controller.gameboard.cells[row][col] == 0 {
-                if let Some(ch) = std::char::from_digit(val as u32, 10) {
-                    let cell_left = inner_left + col as f64 * cell_size;
-                    let cell_top = inner_top + row as f64 * cell_size;
-                    if let Ok(character) = glyphs.character(font_size, ch) {
-                        let glyph_w = character.atlas_size[0] as f64;
-                        let glyph_h = character.atlas_size[1] as f64;
-                        let ch_x = cell_left + (cell_size - glyph_w) / 2.0 + character.left();
-                        let ch_y = cell_top + (cell_size + glyph_h) / 2.0 - character.top();
-                        let img = Image::new_color([0.2, 0.4, 1.0, 1.0]);
-                        img.src_rect([
-                            character.atlas_offset[0],
-                            character.atlas_offset[1],
-                            character.atlas_size[0],
-                            character.atlas_size[1],
-                        ])
-                        .draw(
-                            character.texture,
-                            &c.draw_state,
-                            c.transform.trans(ch_x, ch_y),
-                            g,
-                        );
-                    }
+                let origin = [
+                    inner_left + col as f64 * cell_size,
+                    inner_top + row as f64 * cell_size,
+                ];
+                if controller.assist.colors {
+                    let rect = [origin[0], origin[1], cell_size, cell_size];
+                    let outline = Some([0.2, 0.4, 1.0, 1.0]);
+                    self.draw_swatch(val, rect, false, outline, 0.8, c, g);
+                } else {
+                    digits.draw(val, [0.2, 0.4, 1.0, 1.0], origin, glyphs, c, g);
                 }
             }
         }
@@ -301,6 +1158,26 @@ impl GameboardView {
             }
         }
 
+        // 按区域检查的行 / 列 / 宫（选中格不变时）用边框标出
+        if let Some((cell, unit)) = controller.unit_check {
+            if controller.selected_cell == Some(cell) {
+                let [x, y] = cell;
+                let (col, row, w, h) = match unit {
+                    Unit::Row => (0, y, 9, 1),
+                    Unit::Column => (x, 0, 1, 9),
+                    Unit::Box => (x / 3 * 3, y / 3 * 3, 3, 3),
+                };
+                let rect = [
+                    inner_left + col as f64 * cell_size,
+                    inner_top + row as f64 * cell_size,
+                    w as f64 * cell_size,
+                    h as f64 * cell_size,
+                ];
+                Rectangle::new_border(settings.checked_unit_color, settings.section_edge_radius)
+                    .draw(rect, &c.draw_state, c.transform, g);
+            }
+        }
+
         // Draw board edge around outer rect
         Rectangle::new_border(settings.board_edge_color, settings.board_edge_radius).draw(
             board_rect,
@@ -322,36 +1199,64 @@ impl GameboardView {
             c.transform,
             g,
         );
+    }
 
-        // Draw bottom-centered buttons (Undo / Reset / Random / Hint / Show All / Submit)
-        let btn_labels = ["Undo", "Reset", "Random", "Hint", "Show All", "Submit"];
-        let btn_font = settings.hud_font_size;
-        let btn_w = settings.btn_width;
-        let btn_h = settings.btn_height;
-        let btn_spacing = settings.btn_spacing;
-        let total_w =
-            btn_labels.len() as f64 * btn_w + (btn_labels.len() as f64 - 1.0) * btn_spacing;
+    /// Bottom button rects [x, y, w, h], centered under the board. Buttons shrink to
+    /// fit narrow windows instead of running off the edge.
+    pub fn button_rects(&self) -> Vec<[f64; 4]> {
+        let settings = &self.settings;
+        let count = action::buttons().count() as f64;
+        let margin = settings.scaled(8.0);
+        let spacing = settings.btn_spacing;
+        let fit_w = (settings.window_size[0] - 2.0 * margin - (count - 1.0) * spacing) / count;
+        let btn_w = settings.btn_width.min(fit_w).max(1.0);
+        let total_w = count * btn_w + (count - 1.0) * spacing;
         let preferred_start_x = settings.position[0] + (settings.size - total_w) / 2.0;
-        // 始终放在棋盘下方，固定间距，不再向窗口底部钳制（可能超出窗口但不遮挡棋盘）
-        let margin = 8.0;
         let start_x = preferred_start_x
             .max(margin)
             .min(settings.window_size[0] - margin - total_w);
-        let start_y = settings.position[1] + settings.size + 12.0; // 固定在棋盘正下方
+        // 固定在棋盘正下方
+        let start_y = settings.position[1] + settings.size + settings.scaled(12.0);
+        (0..action::buttons().count())
+            .map(|i| {
+                let x = start_x + i as f64 * (btn_w + spacing);
+                [x, start_y, btn_w, settings.btn_height]
+            })
+            .collect()
+    }
 
-        for (i, &label) in btn_labels.iter().enumerate() {
-            let bx = start_x + i as f64 * (btn_w + btn_spacing);
-            let by = start_y;
-            let rect = [bx, by, btn_w, btn_h];
+    /// Action of the bottom button under `pos` (see `action::buttons`), if any.
+    pub fn button_at(&self, pos: [f64; 2]) -> Option<Action> {
+        let index = self.button_rects().iter().position(|r| {
+            pos[0] >= r[0] && pos[0] < r[0] + r[2] && pos[1] >= r[1] && pos[1] < r[1] + r[3]
+        })?;
+        action::buttons().nth(index)
+    }
 
+    /// Draw the bottom-centered buttons (Undo / Redo / Reset / Random / Hint / Show All / Submit).
+    /// Buttons whose action is unavailable are greyed out and do not react to hovering.
+    fn draw_buttons<G: Graphics, C>(
+        &self,
+        controller: &GameboardController,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let settings = &self.settings;
+        let hovered = self.button_at(controller.cursor_pos);
+
+        for (button, &rect) in action::buttons().zip(&self.button_rects()) {
             // hover/active detection using controller.cursor_pos and controller.mouse_pressed
-            let mx = controller.cursor_pos[0];
-            let my = controller.cursor_pos[1];
-            let is_hover = mx >= bx && mx < bx + btn_w && my >= by && my < by + btn_h;
+            let enabled = (button.spec().enabled)(controller);
+            let is_hover = enabled && hovered == Some(button);
             let is_active = is_hover && controller.mouse_pressed;
 
-            // choose background color based on state (Submit 按钮用绿色)
-            let bg = if i == 5 {
+            // choose background color based on state (Submit 按钮用绿色，禁用时与其它按钮一样)
+            let bg = if button == Action::Submit && enabled {
                 // Submit 按钮特殊样式
                 if is_active {
                     [0.3, 0.7, 0.3, 1.0] // 按下：深绿
@@ -379,34 +1284,274 @@ impl GameboardView {
             );
 
             // draw label centered
-            let mut text_w = 0.0;
-            for ch in label.chars() {
-                if let Ok(g) = glyphs.character(btn_font, ch) {
-                    text_w += g.advance_width();
-                }
-            }
-            let mut tx = bx + (btn_w - text_w) / 2.0;
-            let ty = by + (btn_h + settings.hud_font_size as f64) / 2.0 - 2.0;
-            for ch in label.chars() {
-                if let Ok(glyph) = glyphs.character(btn_font, ch) {
-                    let gx = tx + glyph.left();
-                    let gy = ty - glyph.top();
-                    let img = Image::new_color(settings.btn_text_color);
-                    img.src_rect([
-                        glyph.atlas_offset[0],
-                        glyph.atlas_offset[1],
-                        glyph.atlas_size[0],
-                        glyph.atlas_size[1],
-                    ])
-                    .draw(
-                        glyph.texture,
-                        &c.draw_state,
-                        c.transform.trans(gx, gy),
-                        g,
-                    );
-                    tx += glyph.advance_width();
-                }
-            }
+            text::draw_centered(
+                button.spec().button.unwrap_or(button.name()),
+                settings.hud_font_size,
+                if enabled {
+                    settings.btn_text_color
+                } else {
+                    settings.btn_disabled_text_color
+                },
+                rect,
+                glyphs,
+                c,
+                g,
+            );
         }
     }
-}
\ No newline at end of file
+
+    /// Dim the board and draw a rotating arc while a new puzzle is generated.
+    fn draw_spinner<G: Graphics>(&self, board_rect: [f64; 4], t: f64, c: &Context, g: &mut G) {
+        use graphics::{CircleArc, Rectangle};
+        use std::f64::consts::PI;
+
+        let settings = &self.settings;
+        Rectangle::new(settings.spinner_overlay_color).draw(
+            board_rect,
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        let radius = board_rect[2] / 12.0;
+        let center = [
+            board_rect[0] + board_rect[2] / 2.0,
+            board_rect[1] + board_rect[3] / 2.0,
+        ];
+        let start = t * 2.0 * PI;
+        CircleArc::new(
+            settings.spinner_color,
+            radius / 6.0,
+            start,
+            start + PI * 1.5,
+        )
+        .draw(
+            graphics::ellipse::circle(center[0], center[1], radius),
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+    }
+
+    /// Tab strip rects [x, y, w, h], laid out left to right and ending at the top-right corner.
+    fn tab_rects(&self, count: usize) -> Vec<[f64; 4]> {
+        let settings = &self.settings;
+        let (w, h) = (settings.scaled(72.0), settings.btn_height);
+        let (spacing, margin) = (settings.scaled(4.0), settings.scaled(8.0));
+        let total_w = count as f64 * (w + spacing) - spacing;
+        let start_x = settings.window_size[0] - margin - total_w;
+        (0..count)
+            .map(|i| [start_x + i as f64 * (w + spacing), margin, w, h])
+            .collect()
+    }
+
+    /// Index of the tab under `pos`, if any.
+    pub fn tab_at(&self, count: usize, pos: [f64; 2]) -> Option<usize> {
+        if count < 2 {
+            return None;
+        }
+        self.tab_rects(count).iter().position(|r| {
+            pos[0] >= r[0] && pos[0] < r[0] + r[2] && pos[1] >= r[1] && pos[1] < r[1] + r[3]
+        })
+    }
+
+    /// Draw the tab strip for open puzzles, highlighting `active`.
+    pub fn draw_tabs<G: Graphics, C>(
+        &self,
+        labels: &[String],
+        active: usize,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let settings = &self.settings;
+        for (i, (rect, label)) in self.tab_rects(labels.len()).iter().zip(labels).enumerate() {
+            let bg = if i == active {
+                settings.btn_active_color
+            } else {
+                settings.btn_bg_color
+            };
+            Rectangle::new(bg).draw(*rect, &c.draw_state, c.transform, g);
+            Rectangle::new_border(settings.btn_border_color, 1.0).draw(
+                *rect,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+            text::draw_centered(
+                label,
+                settings.hud_font_size,
+                settings.btn_text_color,
+                *rect,
+                glyphs,
+                c,
+                g,
+            );
+        }
+    }
+
+    /// Index of the sidebar line under `pos`, if the sidebar was drawn this frame.
+    pub fn sidebar_line_at(&self, pos: [f64; 2]) -> Option<usize> {
+        let ([x, y, w, h], line_h) = self.sidebar.get()?;
+        let pad = self.settings.scaled(6.0);
+        let inside = pos[0] >= x && pos[0] < x + w && pos[1] >= y + pad && pos[1] < y + h - pad;
+        inside.then(|| ((pos[1] - y - pad) / line_h) as usize)
+    }
+
+    /// Draw a side panel (e.g. race standings) below the tab strip. It is anchored to
+    /// the right edge, so on wide windows it sits beside the board and on narrow ones
+    /// it overlays the board with the translucent HUD background.
+    pub fn draw_sidebar<G: Graphics, C>(
+        &self,
+        lines: &[String],
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let settings = &self.settings;
+        let font = settings.hud_font_size;
+        let (pad, margin) = (settings.scaled(6.0), settings.scaled(8.0));
+        let line_h = font as f64 + pad;
+        let text_w = lines
+            .iter()
+            .map(|line| text::measure(glyphs, font, line).width)
+            .fold(0.0, f64::max);
+        let w = (text_w + 2.0 * pad).max(settings.scaled(96.0));
+        let h = lines.len() as f64 * line_h + 2.0 * pad;
+        let x = settings.window_size[0] - margin - w;
+        let y = 2.0 * margin + settings.btn_height;
+
+        Rectangle::new(settings.hud_bg_color).draw([x, y, w, h], &c.draw_state, c.transform, g);
+        self.sidebar.set(Some(([x, y, w, h], line_h)));
+        for (i, line) in lines.iter().enumerate() {
+            text::draw_anchored(
+                line,
+                font,
+                settings.hud_text_color,
+                [x + pad, y + (i + 1) as f64 * line_h],
+                Align::Left,
+                glyphs,
+                c,
+                g,
+            );
+        }
+    }
+
+    /// Draw the HUD box (timer, progress counter + thin progress bar, then how many of
+    /// each digit are still to be placed) at `hud_anchor`.
+    fn draw_hud<G: Graphics, C>(
+        &self,
+        controller: &GameboardController,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let settings = &self.settings;
+        let total = 81usize;
+        let filled = controller.filled_count();
+        let verified = controller.verified_count();
+        let time = controller.elapsed_text();
+        let mut label = match verified {
+            Some(ok) => format!("{}  {}/{}  Correct {}", time, filled, total, ok),
+            None => format!("{}  {}/{}", time, filled, total),
+        };
+        if let Some(fallback) = controller.fallback_text() {
+            label.push_str("  ");
+            label.push_str(&fallback);
+        }
+        if let Some(branch) = controller.branch_text() {
+            label.push_str("  ");
+            label.push_str(&branch);
+        }
+        if controller.idle && !controller.submitted {
+            label.push_str("  Idle");
+        }
+
+        let digits = controller.digit_counts_text();
+
+        let font = settings.hud_font_size;
+        let text_w = text::measure(glyphs, font, &label)
+            .width
+            .max(text::measure(glyphs, font, &digits).width);
+
+        let pad = settings.scaled(6.0);
+        let bar_h = settings.progress_bar_height;
+        let box_w = (text_w + 2.0 * pad).max(settings.scaled(96.0));
+        let margin = settings.scaled(8.0);
+        let box_h = settings.hud_area() - margin;
+        let [win_w, win_h] = settings.window_size;
+        let (bx, by) = match settings.hud_anchor {
+            HudAnchor::TopLeft => (margin, margin),
+            HudAnchor::TopRight => (win_w - margin - box_w, margin),
+            HudAnchor::BottomLeft => (margin, win_h - margin - box_h),
+            HudAnchor::BottomRight => (win_w - margin - box_w, win_h - margin - box_h),
+        };
+
+        Rectangle::new(settings.hud_bg_color).draw(
+            [bx, by, box_w, box_h],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+
+        // counter text (right-anchored HUDs align their text to the right edge)
+        let ty = by + pad + font as f64;
+        let (tx, align) = match settings.hud_anchor {
+            HudAnchor::TopLeft | HudAnchor::BottomLeft => (bx + pad, Align::Left),
+            HudAnchor::TopRight | HudAnchor::BottomRight => (bx + box_w - pad, Align::Right),
+        };
+        for (line, y) in [
+            (&label, ty),
+            (&digits, ty + bar_h + 2.0 * pad + font as f64),
+        ] {
+            text::draw_anchored(
+                line,
+                font,
+                settings.hud_text_color,
+                [tx, y],
+                align,
+                glyphs,
+                c,
+                g,
+            );
+        }
+
+        // thin progress bar: filled portion, verified portion on top
+        let bar_x = bx + pad;
+        let bar_y = ty + pad;
+        let bar_w = box_w - 2.0 * pad;
+        Rectangle::new([0.0, 0.0, 0.0, 0.1]).draw(
+            [bar_x, bar_y, bar_w, bar_h],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        let filled_w = bar_w * filled as f64 / total as f64;
+        Rectangle::new(settings.progress_fill_color).draw(
+            [bar_x, bar_y, filled_w, bar_h],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        if let Some(ok) = verified {
+            let ok_w = bar_w * ok as f64 / total as f64;
+            Rectangle::new(settings.progress_verified_color).draw(
+                [bar_x, bar_y, ok_w, bar_h],
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! 手柄输入：把 piston 的手柄事件（按键、十字键、摇杆）翻译成 `Pad` 操作，方便在沙发上玩。
+//!
+//! 游戏中十字键与左摇杆移动选中格；数字盘是一个 1 – 9 的当前数字，X / Y 转到上一个 /
+//! 下一个数字（淡色预览在选中的空格里），A 把它填入选中格，B 清空选中格，LB 撤销，
+//! RB 提示，Start 暂停。在菜单等其它界面中方向对应方向键，A 与 Start 为 Enter，B 为 Esc
+//! （主菜单中不响应 B，避免误触退出）。
+//!
+//! 只有 sdl2 窗口后端（`--features sdl2`）会产生手柄事件，glutin 后端下手柄没有反应。
+//! 按键编号因手柄而异，默认值为 Xbox 布局在 SDL 中的编号，可在配置文件 `[gamepad]` 中修改
+//! （见 `config.rs`）。
+
+use piston::input::{Button, GenericEvent, HatState, Key};
+use serde::{Deserialize, Serialize};
+
+/// 摇杆推过这个比例才算一次移动
+const STICK_PRESS: f64 = 0.6;
+
+/// 摇杆回到这个比例以内后才能再次移动
+const STICK_RELEASE: f64 = 0.3;
+
+/// 手柄按键编号（配置文件 `[gamepad]`）
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    /// 把数字盘上的数字填入选中格
+    pub place: u8,
+    /// 清空选中格
+    pub clear: u8,
+    /// 数字盘转到上一个 / 下一个数字
+    pub prev_digit: u8,
+    pub next_digit: u8,
+    pub undo: u8,
+    pub hint: u8,
+    pub pause: u8,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            place: 0,
+            clear: 1,
+            prev_digit: 2,
+            next_digit: 3,
+            undo: 4,
+            hint: 5,
+            pause: 7,
+        }
+    }
+}
+
+/// 一次手柄操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pad {
+    /// 移动选中格 (dx, dy)
+    Move(isize, isize),
+    Place,
+    Clear,
+    PrevDigit,
+    NextDigit,
+    Undo,
+    Hint,
+    Pause,
+}
+
+impl Pad {
+    /// 游戏以外的界面中对应的按键
+    pub fn key(self) -> Option<Key> {
+        match self {
+            Pad::Move(0, -1) => Some(Key::Up),
+            Pad::Move(0, 1) => Some(Key::Down),
+            Pad::Move(-1, 0) => Some(Key::Left),
+            Pad::Move(1, 0) => Some(Key::Right),
+            Pad::Place | Pad::Pause => Some(Key::Return),
+            Pad::Clear => Some(Key::Escape),
+            _ => None,
+        }
+    }
+}
+
+/// 手柄状态
+pub struct Gamepad {
+    /// 数字盘当前的数字（1 – 9）
+    pub digit: u8,
+    /// 是否用过手柄（用过之后才在选中格预览数字盘上的数字）
+    pub active: bool,
+    /// 左摇杆的两个轴是否已推出（回中后才能再次移动）
+    stick: [bool; 2],
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        Gamepad {
+            digit: 1,
+            active: false,
+            stick: [false; 2],
+        }
+    }
+}
+
+impl Gamepad {
+    /// 把手柄事件翻译成操作；不是手柄事件或没有对应操作时返回 None
+    pub fn event<E: GenericEvent>(&mut self, e: &E, config: &GamepadConfig) -> Option<Pad> {
+        let pad = match (e.press_args(), e.button_args()) {
+            (Some(Button::Controller(button)), _) => [
+                (config.place, Pad::Place),
+                (config.clear, Pad::Clear),
+                (config.prev_digit, Pad::PrevDigit),
+                (config.next_digit, Pad::NextDigit),
+                (config.undo, Pad::Undo),
+                (config.hint, Pad::Hint),
+                (config.pause, Pad::Pause),
+            ]
+            .iter()
+            .find(|&&(index, _)| index == button.button)
+            .map(|&(_, pad)| pad),
+            // SDL 把十字键的变化作为松开事件发出，所以不看按下 / 松开
+            (_, Some(args)) => match args.button {
+                Button::Hat(hat) => match hat.state {
+                    HatState::Up => Some(Pad::Move(0, -1)),
+                    HatState::Down => Some(Pad::Move(0, 1)),
+                    HatState::Left => Some(Pad::Move(-1, 0)),
+                    HatState::Right => Some(Pad::Move(1, 0)),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => e
+                .controller_axis_args()
+                .and_then(|args| self.stick(args.axis, args.position)),
+        };
+        if pad.is_some() {
+            self.active = true;
+        }
+        pad
+    }
+
+    /// 左摇杆（轴 0 为横向，轴 1 为纵向）推过阈值时移动一格
+    fn stick(&mut self, axis: u8, position: f64) -> Option<Pad> {
+        let pushed = self.stick.get_mut(axis as usize)?;
+        if position.abs() < STICK_RELEASE {
+            *pushed = false;
+        }
+        if *pushed || position.abs() < STICK_PRESS {
+            return None;
+        }
+        *pushed = true;
+        let step = if position > 0.0 { 1 } else { -1 };
+        Some(if axis == 0 {
+            Pad::Move(step, 0)
+        } else {
+            Pad::Move(0, step)
+        })
+    }
+
+    /// 数字盘转 `delta` 格（9 之后回到 1）
+    pub fn turn(&mut self, delta: i32) {
+        self.digit = ((self.digit as i32 - 1 + delta).rem_euclid(9) + 1) as u8;
+    }
+}
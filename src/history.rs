@@ -0,0 +1,278 @@
+//! 对局历史：每局提交后写入本地 SQLite 数据库，供统计界面显示历史、趋势与最佳成绩。
+//!
+//! 数据库位于平台数据目录（Linux 为 `~/.local/share/sudoku/history.sqlite3`）。
+//! 表结构版本记录在 `PRAGMA user_version` 中，打开时按顺序执行尚未应用的迁移。
+
+use crate::paths;
+use rusqlite::{params, Connection, Row};
+use std::fs;
+use std::path::{Path, PathBuf};
+use sudoku_core::Difficulty;
+
+/// 数据库文件名
+const HISTORY_FILE: &str = "history.sqlite3";
+
+/// 表结构迁移，第 i 项把版本 i 升级到 i + 1；只能在末尾追加
+const MIGRATIONS: &[&str] = &[
+    // v1：对局与题库进度（早期未记录版本的数据库已有这两张表，IF NOT EXISTS 保证可重复执行）
+    "CREATE TABLE IF NOT EXISTS games (
+        id         INTEGER PRIMARY KEY,
+        puzzle     TEXT NOT NULL,
+        difficulty TEXT NOT NULL,
+        seconds    REAL NOT NULL,
+        mistakes   INTEGER NOT NULL,
+        solved     INTEGER NOT NULL,
+        played_at  TEXT NOT NULL DEFAULT (datetime('now', 'localtime'))
+    );
+    CREATE TABLE IF NOT EXISTS pack_progress (
+        pack       TEXT NOT NULL,
+        idx        INTEGER NOT NULL,
+        seconds    REAL NOT NULL,
+        PRIMARY KEY (pack, idx)
+    );",
+    // v2：玩家名字（本机最佳成绩表中显示，可为空）
+    "ALTER TABLE games ADD COLUMN name TEXT NOT NULL DEFAULT '';",
+    // v3：每日 / 每周题的日期（连续完成天数日历）
+    "ALTER TABLE games ADD COLUMN daily TEXT;",
+];
+
+/// 一局的记录
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    /// 初始题面（81 字符）
+    pub puzzle: String,
+    pub difficulty: Difficulty,
+    /// 用时（秒）
+    pub seconds: f64,
+    /// 填入冲突数字的次数
+    pub mistakes: u32,
+    /// 提交时是否全部正确
+    pub solved: bool,
+    /// 完成时间（本地时间 `YYYY-MM-DD HH:MM:SS`，由数据库填写）
+    pub played_at: String,
+    /// 玩家名字（配置文件 `[online]` 中的 `name`，未设置时为空）
+    pub name: String,
+    /// 每日 / 每周题的当期第一天（`YYYY-MM-DD`），其它题目为 None
+    pub daily: Option<String>,
+}
+
+/// 某个难度的汇总
+#[derive(Clone, Debug)]
+pub struct DifficultySummary {
+    pub difficulty: Difficulty,
+    pub played: u32,
+    pub solved: u32,
+    /// 解出的对局中的最短 / 平均用时
+    pub best: Option<f64>,
+    pub average: Option<f64>,
+}
+
+/// 历史数据库
+pub struct History {
+    conn: Connection,
+}
+
+impl History {
+    /// 默认数据库路径；无法确定用户目录时为 None
+    pub fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join(HISTORY_FILE))
+    }
+
+    /// 打开默认位置的数据库（不存在时创建）
+    pub fn open_default() -> Result<Self, String> {
+        let path = Self::path().ok_or("无法确定数据目录")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+        }
+        Self::open(&path).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// 打开数据库并迁移到当前版本；数据库版本比程序新时返回错误
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut conn = Connection::open(path).map_err(|err| err.to_string())?;
+        let version: usize = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|err| err.to_string())?;
+        if version > MIGRATIONS.len() {
+            return Err(format!(
+                "database version {} is newer than supported ({})",
+                version,
+                MIGRATIONS.len()
+            ));
+        }
+        Self::migrate(&mut conn, version).map_err(|err| err.to_string())?;
+        Ok(History { conn })
+    }
+
+    /// 从 `version` 开始执行尚未应用的迁移，每一步与版本号更新在同一个事务中
+    fn migrate(conn: &mut Connection, version: usize) -> rusqlite::Result<()> {
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(sql)?;
+            tx.pragma_update(None, "user_version", i + 1)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// 记录一局（`played_at` 忽略，使用当前时间）
+    pub fn record(&self, game: &GameRecord) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO games (puzzle, difficulty, seconds, mistakes, solved, name, daily)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                game.puzzle,
+                game.difficulty.name(),
+                game.seconds,
+                game.mistakes,
+                game.solved,
+                game.name,
+                game.daily
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 最近的对局，新的在前
+    pub fn recent(&self, limit: usize) -> rusqlite::Result<Vec<GameRecord>> {
+        self.query(None, None, limit)
+    }
+
+    /// 按难度 / 结果筛选对局（None 表示不限），新的在前
+    pub fn query(
+        &self,
+        difficulty: Option<Difficulty>,
+        solved: Option<bool>,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<GameRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT puzzle, difficulty, seconds, mistakes, solved, played_at, name, daily
+             FROM games
+             WHERE (?1 IS NULL OR difficulty = ?1) AND (?2 IS NULL OR solved = ?2)
+             ORDER BY id DESC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![difficulty.map(Difficulty::name), solved, limit as i64],
+            game_record,
+        )?;
+        rows.collect()
+    }
+
+    /// 记录题库中第 `index` 题已解出（保留最短用时）
+    pub fn mark_pack_solved(&self, pack: &str, index: usize, seconds: f64) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO pack_progress (pack, idx, seconds) VALUES (?1, ?2, ?3)
+             ON CONFLICT (pack, idx) DO UPDATE SET seconds = MIN(seconds, excluded.seconds)",
+            params![pack, index as i64, seconds],
+        )?;
+        Ok(())
+    }
+
+    /// 题库中已解出的题目序号
+    pub fn pack_solved(&self, pack: &str) -> rusqlite::Result<Vec<usize>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT idx FROM pack_progress WHERE pack = ?1 ORDER BY idx")?;
+        let rows = stmt.query_map([pack], |row| row.get::<_, i64>(0).map(|i| i as usize))?;
+        rows.collect()
+    }
+
+    /// 按难度汇总（只列出玩过的难度）
+    pub fn summary(&self) -> rusqlite::Result<Vec<DifficultySummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COUNT(*), COALESCE(SUM(solved), 0),
+                    MIN(CASE WHEN solved THEN seconds END),
+                    AVG(CASE WHEN solved THEN seconds END)
+             FROM games WHERE difficulty = ?1",
+        )?;
+        let mut summary = Vec::new();
+        for difficulty in Difficulty::ALL {
+            let row = stmt.query_row([difficulty.name()], |row| {
+                Ok(DifficultySummary {
+                    difficulty,
+                    played: row.get(0)?,
+                    solved: row.get(1)?,
+                    best: row.get(2)?,
+                    average: row.get(3)?,
+                })
+            })?;
+            if row.played > 0 {
+                summary.push(row);
+            }
+        }
+        Ok(summary)
+    }
+
+    /// 某道题解出的最短用时，快的在前
+    pub fn best_times(&self, puzzle: &str, limit: usize) -> rusqlite::Result<Vec<GameRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT puzzle, difficulty, seconds, mistakes, solved, played_at, name, daily
+             FROM games WHERE puzzle = ?1 AND solved
+             ORDER BY seconds LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![puzzle, limit as i64], game_record)?;
+        rows.collect()
+    }
+
+    /// 某个难度解出的最短用时（本机最佳成绩表），快的在前；用时相同时先完成的在前
+    pub fn top_times(
+        &self,
+        difficulty: Difficulty,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<GameRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT puzzle, difficulty, seconds, mistakes, solved, played_at, name, daily
+             FROM games WHERE difficulty = ?1 AND solved
+             ORDER BY seconds, id LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![difficulty.name(), limit as i64], game_record)?;
+        rows.collect()
+    }
+
+    /// 用时 `seconds` 在某个难度的最佳成绩中排第几（从 1 开始，与之前的成绩并列时取较前的名次）
+    pub fn rank(&self, difficulty: Difficulty, seconds: f64) -> rusqlite::Result<usize> {
+        let faster: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM games WHERE difficulty = ?1 AND solved AND seconds < ?2",
+            params![difficulty.name(), seconds],
+            |row| row.get(0),
+        )?;
+        Ok(faster as usize + 1)
+    }
+
+    /// 解出过的每日 / 每周题日期（`YYYY-MM-DD`），早的在前
+    pub fn daily_solved(&self) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT daily FROM games WHERE daily IS NOT NULL AND solved ORDER BY daily",
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// 趋势：最近 `n` 局解出用时的平均值，以及再往前 `n` 局的平均值
+    pub fn trend(&self, n: usize) -> rusqlite::Result<(Option<f64>, Option<f64>)> {
+        let mut stmt = self.conn.prepare(
+            "SELECT AVG(seconds) FROM (
+                SELECT seconds FROM games WHERE solved
+                ORDER BY id DESC LIMIT ?1 OFFSET ?2
+             )",
+        )?;
+        let recent = stmt.query_row([n as i64, 0], |row| row.get(0))?;
+        let before = stmt.query_row([n as i64, n as i64], |row| row.get(0))?;
+        Ok((recent, before))
+    }
+}
+
+/// 把 `SELECT puzzle, difficulty, seconds, mistakes, solved, played_at, name, daily` 的一行读成记录
+fn game_record(row: &Row) -> rusqlite::Result<GameRecord> {
+    let difficulty: String = row.get(1)?;
+    Ok(GameRecord {
+        puzzle: row.get(0)?,
+        difficulty: difficulty.parse().unwrap_or_default(),
+        seconds: row.get(2)?,
+        mistakes: row.get(3)?,
+        solved: row.get(4)?,
+        played_at: row.get(5)?,
+        name: row.get(6)?,
+        daily: row.get(7)?,
+    })
+}
@@ -0,0 +1,386 @@
+//! 撤销历史与事件流的紧凑存储。
+//!
+//! 两者在一局中只增不减（撤销不限步数，事件流用于回放与赛后分析），很长的对局里会占用越来越多的内存。
+//! `Journal` 只让最近的记录保持原样，更早的记录每 `CHUNK` 条编码成一块字节串：
+//! 事件按时间戳做游程编码（重置、撤销一组修改等同一时刻的多条事件共用一个时间），
+//! 撤销记录每条压成两个字节。平均每条事件约 3 – 5 字节、每条撤销记录 2 字节，原样存放时为 32 与 24 字节。
+//!
+//! 编码在 `GameboardController::update` 中进行，每帧最多一块，不占用输入处理的时间。
+//! 读写较早的记录（撤销很久以前的输入、放弃很早开的分支）时，从该块起解码回原样，之后再慢慢重新编码。
+
+use crate::gameboard_controller::{Change, GameEvent};
+use std::ops::Range;
+
+/// 每块编码的记录数；原样保留的记录在 `CHUNK` 到 `2 * CHUNK` 条之间时才开始编码
+const CHUNK: usize = 256;
+
+/// 可以编码成块的记录
+pub trait Packed: Copy {
+    /// 把 `records` 追加编码到 `out`
+    fn encode(records: &[Self], out: &mut Vec<u8>);
+    /// 解码 `encode` 写出的一块，追加到 `out`
+    fn decode(bytes: &[u8], out: &mut Vec<Self>);
+}
+
+/// 只在末尾增长的记录序列：较早的部分编码成块，最近的部分原样存放
+#[derive(Clone, Debug)]
+pub struct Journal<T> {
+    /// 编码过的块，每块恰好 `CHUNK` 条记录
+    chunks: Vec<Box<[u8]>>,
+    /// 编码块之后的记录
+    recent: Vec<T>,
+}
+
+impl<T> Default for Journal<T> {
+    fn default() -> Self {
+        Journal {
+            chunks: Vec::new(),
+            recent: Vec::new(),
+        }
+    }
+}
+
+impl<T: Packed> Journal<T> {
+    pub fn len(&self) -> usize {
+        self.frozen() + self.recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, record: T) {
+        self.recent.push(record);
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.recent.clear();
+    }
+
+    /// 第 `index` 条记录（在编码块中时只解码那一块）
+    pub fn get(&self, index: usize) -> Option<T> {
+        match index.checked_sub(self.frozen()) {
+            Some(i) => self.recent.get(i).copied(),
+            None => Some(self.chunk(index / CHUNK)[index % CHUNK]),
+        }
+    }
+
+    /// 按顺序列出全部记录
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.iter_from(0)
+    }
+
+    /// 从第 `start` 条起按顺序列出记录；`start` 在原样存放的部分时不解码
+    pub fn iter_from(&self, start: usize) -> impl Iterator<Item = T> + '_ {
+        let first = (start / CHUNK).min(self.chunks.len());
+        let skip = start - first * CHUNK;
+        (first..self.chunks.len())
+            .flat_map(move |i| self.chunk(i))
+            .chain(self.recent.iter().copied())
+            .skip(skip)
+    }
+
+    /// 从第 `start` 条到末尾中最后一条满足 `pred` 的记录的位置；从后往前逐块解码，找到即停
+    pub fn rposition(&self, start: usize, pred: impl Fn(&T) -> bool) -> Option<usize> {
+        if let Some(i) = self.recent.iter().rposition(&pred) {
+            let index = self.frozen() + i;
+            return (index >= start).then_some(index);
+        }
+        for chunk in (start / CHUNK..self.chunks.len()).rev() {
+            if let Some(i) = self.chunk(chunk).iter().rposition(&pred) {
+                let index = chunk * CHUNK + i;
+                return (index >= start).then_some(index);
+            }
+        }
+        None
+    }
+
+    /// 取出 `range` 中的记录，之后的记录前移
+    pub fn drain(&mut self, range: Range<usize>) -> Vec<T> {
+        self.thaw(range.start);
+        let offset = self.frozen();
+        self.recent
+            .drain(range.start - offset..range.end - offset)
+            .collect()
+    }
+
+    /// 只保留前 `len` 条记录
+    pub fn truncate(&mut self, len: usize) {
+        self.thaw(len);
+        let offset = self.frozen();
+        self.recent.truncate(len.saturating_sub(offset));
+    }
+
+    /// 原样存放的记录足够多时把最早的 `CHUNK` 条编码成一块；编码了返回 true
+    pub fn compact(&mut self) -> bool {
+        if self.recent.len() < 2 * CHUNK {
+            return false;
+        }
+        let mut bytes = Vec::new();
+        T::encode(&self.recent[..CHUNK], &mut bytes);
+        self.chunks.push(bytes.into_boxed_slice());
+        self.recent.drain(..CHUNK);
+        true
+    }
+
+    /// 编码块中的记录数
+    fn frozen(&self) -> usize {
+        self.chunks.len() * CHUNK
+    }
+
+    fn chunk(&self, index: usize) -> Vec<T> {
+        let mut records = Vec::with_capacity(CHUNK);
+        T::decode(&self.chunks[index], &mut records);
+        records
+    }
+
+    /// 把第 `index` 条所在的块及之后的块解码回原样，以便修改
+    fn thaw(&mut self, index: usize) {
+        let keep = index / CHUNK;
+        if keep >= self.chunks.len() {
+            return;
+        }
+        let mut records = Vec::with_capacity(self.len() - keep * CHUNK);
+        for chunk in self.chunks.drain(keep..) {
+            T::decode(&chunk, &mut records);
+        }
+        records.append(&mut self.recent);
+        self.recent = records;
+    }
+}
+
+/// 事件：连续的同一时刻（按百分之一秒，与回放文件相同的精度）的事件为一组，
+/// 每组写与上一组的时间差（百分之一秒）、组内条数，再逐条写 `格子序号 * 10 + 新值`
+impl Packed for GameEvent {
+    fn encode(records: &[Self], out: &mut Vec<u8>) {
+        let mut last = 0;
+        let mut rest = records;
+        while let Some(first) = rest.first() {
+            let time = centiseconds(first.time);
+            let run = rest
+                .iter()
+                .take_while(|e| centiseconds(e.time) == time)
+                .count();
+            write_varint(out, zigzag(time - last));
+            write_varint(out, run as u64);
+            for e in &rest[..run] {
+                write_varint(out, ((e.y * 9 + e.x) * 10 + e.value as usize) as u64);
+            }
+            last = time;
+            rest = &rest[run..];
+        }
+    }
+
+    fn decode(mut bytes: &[u8], out: &mut Vec<Self>) {
+        let mut time = 0;
+        while !bytes.is_empty() {
+            time += unzigzag(read_varint(&mut bytes));
+            for _ in 0..read_varint(&mut bytes) {
+                let packed = read_varint(&mut bytes) as usize;
+                let cell = packed / 10;
+                out.push(GameEvent {
+                    time: time as f64 / 100.0,
+                    x: cell % 9,
+                    y: cell / 9,
+                    value: (packed % 10) as u8,
+                });
+            }
+        }
+    }
+}
+
+/// 撤销记录：每条两个字节（小端），低 7 位为格子序号，其上 4 位为旧值，最高位为 `joined`
+impl Packed for Change {
+    fn encode(records: &[Self], out: &mut Vec<u8>) {
+        for change in records {
+            let packed = (change.y * 9 + change.x) as u16
+                | (change.prev as u16) << 7
+                | (change.joined as u16) << 15;
+            out.extend_from_slice(&packed.to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8], out: &mut Vec<Self>) {
+        for pair in bytes.chunks_exact(2) {
+            let packed = u16::from_le_bytes([pair[0], pair[1]]);
+            let cell = (packed & 0x7f) as usize;
+            out.push(Change {
+                x: cell % 9,
+                y: cell / 9,
+                prev: (packed >> 7 & 0xf) as u8,
+                joined: packed >> 15 != 0,
+            });
+        }
+    }
+}
+
+fn centiseconds(time: f64) -> i64 {
+    (time * 100.0).round() as i64
+}
+
+/// 有符号数映射为无符号数，绝对值小的编码后也短
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+/// LEB128：每字节 7 位，最高位表示后面还有
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> u64 {
+    let mut n = 0;
+    let mut shift = 0;
+    while let Some((&byte, rest)) = bytes.split_first() {
+        *bytes = rest;
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Journal, Packed, CHUNK};
+    use crate::gameboard_controller::{Change, GameEvent};
+
+    /// 覆盖同一时刻的多条事件、时间倒退（零之字）、多字节的时间差，以及每个格子和每个值
+    fn events(n: usize) -> Vec<GameEvent> {
+        let mut centis = 0i64;
+        (0..n)
+            .map(|i| {
+                centis += match i % 7 {
+                    0 | 1 => 0,
+                    2 => -3,
+                    5 => 100_000,
+                    _ => i as i64,
+                };
+                GameEvent {
+                    time: centis as f64 / 100.0,
+                    x: i % 9,
+                    y: i / 9 % 9,
+                    value: (i % 10) as u8,
+                }
+            })
+            .collect()
+    }
+
+    fn changes(n: usize) -> Vec<Change> {
+        (0..n)
+            .map(|i| Change {
+                x: i % 9,
+                y: i / 9 % 9,
+                prev: (i % 10) as u8,
+                joined: i % 3 == 0,
+            })
+            .collect()
+    }
+
+    fn key(change: &Change) -> (usize, usize, u8, bool) {
+        (change.x, change.y, change.prev, change.joined)
+    }
+
+    fn keys(changes: impl IntoIterator<Item = Change>) -> Vec<(usize, usize, u8, bool)> {
+        changes.into_iter().map(|c| key(&c)).collect()
+    }
+
+    fn round_trip<T: Packed>(records: &[T]) -> Vec<T> {
+        let mut bytes = Vec::new();
+        T::encode(records, &mut bytes);
+        let mut decoded = Vec::new();
+        T::decode(&bytes, &mut decoded);
+        decoded
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let records = events(3 * CHUNK);
+        assert_eq!(round_trip(&records), records);
+    }
+
+    #[test]
+    fn changes_round_trip() {
+        let records = changes(3 * CHUNK);
+        assert_eq!(keys(round_trip(&records)), keys(records));
+    }
+
+    fn filled<T: Packed>(records: &[T]) -> Journal<T> {
+        let mut journal = Journal::default();
+        for &record in records {
+            journal.push(record);
+        }
+        while journal.compact() {}
+        journal
+    }
+
+    /// 编码块与原样记录之间的读取、修改与再次编码
+    #[test]
+    fn events_survive_compaction() {
+        let records = events(4 * CHUNK + 10);
+        let mut journal = filled(&records);
+        assert_eq!(journal.chunks.len(), 3);
+        assert_eq!(journal.len(), records.len());
+        assert_eq!(journal.iter().collect::<Vec<_>>(), records);
+        for i in [0, CHUNK - 1, CHUNK, CHUNK + 1, 3 * CHUNK, records.len() - 1] {
+            assert_eq!(journal.get(i), Some(records[i]));
+        }
+        assert_eq!(journal.get(records.len()), None);
+        assert_eq!(
+            journal.iter_from(CHUNK - 3).collect::<Vec<_>>(),
+            records[CHUNK - 3..]
+        );
+
+        // 从第一块末尾取到第二块开头：之后的块都解码回原样
+        let mut expected = records.clone();
+        let drained: Vec<_> = expected.drain(CHUNK - 2..CHUNK + 2).collect();
+        assert_eq!(journal.drain(CHUNK - 2..CHUNK + 2), drained);
+        assert_eq!(journal.chunks.len(), 0);
+        assert_eq!(journal.iter().collect::<Vec<_>>(), expected);
+
+        while journal.compact() {}
+        assert_eq!(journal.chunks.len(), 3);
+        assert_eq!(journal.iter().collect::<Vec<_>>(), expected);
+
+        journal.truncate(2 * CHUNK + 5);
+        expected.truncate(2 * CHUNK + 5);
+        assert_eq!(journal.chunks.len(), 2);
+        assert_eq!(journal.iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn changes_survive_compaction() {
+        let records = changes(3 * CHUNK + 1);
+        let mut journal = filled(&records);
+        assert_eq!(journal.chunks.len(), 2);
+        assert_eq!(keys(journal.iter()), keys(records.iter().copied()));
+        assert_eq!(
+            journal.rposition(0, |c| c.joined && c.x == 0),
+            records.iter().rposition(|c| c.joined && c.x == 0)
+        );
+        // 只在编码块中出现的记录
+        let index = CHUNK + 9;
+        let target = key(&records[index]);
+        assert_eq!(journal.rposition(0, |c| key(c) == target), Some(index));
+        assert_eq!(journal.rposition(index + 1, |c| key(c) == target), None);
+
+        journal.truncate(CHUNK + 1);
+        assert_eq!(journal.chunks.len(), 1);
+        assert_eq!(
+            keys(journal.iter()),
+            keys(records[..CHUNK + 1].iter().copied())
+        );
+        assert!(!journal.compact());
+    }
+}
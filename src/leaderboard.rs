@@ -0,0 +1,175 @@
+//! 每日题排行榜：解出每日 / 每周题后把用时提交到配置的排行榜服务器，并显示最快的成绩。
+//!
+//! 提交需要在配置文件 `[online]` 中设置 `submit_times = true`（默认不上传任何数据）。
+//! `leaderboard_url` 中的 `{date}` 替换为当期第一天，协议为纯文本，每行 `秒数 名字`：
+//! ```text
+//! GET  https://example.com/leaderboard/2026-10-16   → 返回成绩列表（可带 # 注释行）
+//! POST https://example.com/leaderboard/2026-10-16   ← 请求体为一行 `312.5 alice`
+//! ```
+//! 未配置网址、无法联网或未开启 `online` feature 时，显示本机历史中同一道题的最佳用时。
+
+use crate::daily::OnlineConfig;
+use crate::history::History;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use sudoku_core::Gameboard;
+
+/// 显示的成绩条数
+pub const TOP: usize = 10;
+
+/// 下载超时（秒）
+#[cfg(feature = "online")]
+const TIMEOUT_SECS: u64 = 5;
+
+/// 一条成绩
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub seconds: f64,
+    /// 玩家名字；本机成绩为完成时间
+    pub name: String,
+}
+
+/// 某一期的排行榜
+pub struct Leaderboard {
+    /// 当期第一天（`YYYY-MM-DD`）
+    pub key: String,
+    pub entries: Vec<Entry>,
+    /// 是否为服务器上的排行榜（false 为本机成绩）
+    pub online: bool,
+    /// 后台请求（提交并下载）尚未完成
+    receiver: Option<Receiver<Result<Vec<Entry>, String>>>,
+}
+
+impl Leaderboard {
+    /// 先用本机历史中的成绩，配置了服务器时在后台提交 `time`（允许提交时）并下载排行榜
+    pub fn load(
+        config: &OnlineConfig,
+        key: String,
+        puzzle: &Gameboard,
+        time: Option<f64>,
+        history: Option<&History>,
+    ) -> Self {
+        let entries = history
+            .and_then(|h| h.best_times(&puzzle.to_line(), TOP).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|game| Entry {
+                seconds: game.seconds,
+                name: game.played_at,
+            })
+            .collect();
+        let receiver = (!config.leaderboard_url.is_empty()).then(|| {
+            let url = config.leaderboard_url.replace("{date}", &key);
+            let submission = time
+                .filter(|_| config.submit_times)
+                .map(|t| format!("{:.1} {}\n", t, player_name(config)));
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                let result = submission
+                    .map_or(Ok(()), |body| post(&url, &body))
+                    .and_then(|()| get(&url))
+                    .and_then(|text| parse(&text));
+                let _ = sender.send(result);
+            });
+            receiver
+        });
+        Leaderboard {
+            key,
+            entries,
+            online: false,
+            receiver,
+        }
+    }
+
+    /// 是否还在等待服务器
+    pub fn is_loading(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// 检查后台请求是否完成；失败时打印原因并保留本机成绩
+    pub fn poll(&mut self) {
+        let Some(receiver) = &self.receiver else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(Ok(entries)) => {
+                self.entries = entries;
+                self.online = true;
+            }
+            Ok(Err(err)) => eprintln!("无法获取排行榜，显示本机成绩: {}", err),
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {}
+        }
+        self.receiver = None;
+    }
+}
+
+/// 排行榜上显示的名字（未设置时为 player）；去掉换行以免破坏协议
+fn player_name(config: &OnlineConfig) -> String {
+    let name = config.name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        "player".to_string()
+    } else {
+        name
+    }
+}
+
+/// 解析服务器返回的成绩列表，按用时排序并只保留前 `TOP` 条
+fn parse(text: &str) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    for (i, line) in text.lines().map(str::trim).enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (seconds, name) = line.split_once(' ').unwrap_or((line, ""));
+        let seconds: f64 = seconds
+            .parse()
+            .ok()
+            .filter(|s: &f64| s.is_finite() && *s >= 0.0)
+            .ok_or_else(|| format!("line {}: bad time", i + 1))?;
+        entries.push(Entry {
+            seconds,
+            name: name.trim().to_string(),
+        });
+    }
+    entries.sort_by(|a, b| a.seconds.total_cmp(&b.seconds));
+    entries.truncate(TOP);
+    Ok(entries)
+}
+
+#[cfg(feature = "online")]
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(TIMEOUT_SECS))
+        .build()
+}
+
+#[cfg(feature = "online")]
+fn get(url: &str) -> Result<String, String> {
+    agent()
+        .get(url)
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "online")]
+fn post(url: &str, body: &str) -> Result<(), String> {
+    agent()
+        .post(url)
+        .set("Content-Type", "text/plain; charset=utf-8")
+        .send_string(body)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "online"))]
+fn get(_url: &str) -> Result<String, String> {
+    Err("built without the `online` feature".to_string())
+}
+
+#[cfg(not(feature = "online"))]
+fn post(_url: &str, _body: &str) -> Result<(), String> {
+    Err("built without the `online` feature".to_string())
+}
@@ -2,91 +2,331 @@
 
 //! Sudoku Game Main
 
-extern crate glutin_window;
 extern crate graphics;
 extern crate opengl_graphics;
 extern crate piston;
 extern crate rand;
+extern crate sudoku_core;
 
-pub use crate::gameboard::Gameboard;
 pub use crate::gameboard_controller::GameboardController;
 pub use crate::gameboard_view::{GameboardView, GameboardViewSettings};
+use sudoku_core::wordoku;
+pub use sudoku_core::Gameboard;
+use sudoku_core::{Variant, RATED_BUDGET};
 
-use glutin_window::GlutinWindow;
-use opengl_graphics::{Filter, GlGraphics, GlyphCache, OpenGL, TextureSettings};
+use clap::Parser;
+use opengl_graphics::{Filter, GlGraphics, OpenGL, TextureSettings};
 use piston::event_loop::{EventLoop, EventSettings, Events};
-use piston::input::RenderEvent;
-use piston::window::Window;
+use piston::input::{RenderEvent, UpdateEvent};
 use piston::window::WindowSettings;
+use piston::window::{AdvancedWindow, Window};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fmt::Display;
+use window::{AppWindow, WindowBackend};
 
-mod gameboard;
+mod about;
+mod accessibility;
+mod action;
+mod analysis;
+mod assets;
+mod audio;
+mod booklet;
+mod card;
+mod cli;
+mod commands;
+mod config;
+mod crash;
+mod daily;
+mod drills;
+mod explain;
 mod gameboard_controller;
 mod gameboard_view;
+mod gamepad;
+mod history;
+mod journal;
+mod leaderboard;
+mod net;
+#[cfg(feature = "ocr")]
+mod ocr;
+mod pack;
+mod paths;
+mod persist;
+mod qr;
+mod replay;
+mod savefile;
+mod scene;
+mod screen_reader;
+#[cfg(feature = "scripting")]
+mod scripting;
+mod server;
+mod share;
+mod telemetry;
+mod text;
+mod tui;
+mod tutorial;
+mod update;
+mod window;
+
+/// 逻辑更新频率（计时器）
+const UPS: u64 = 10;
+/// 仅计时器在走时的渲染帧率（每秒刷新几次足够显示秒数）
+const IDLE_FPS: u64 = 4;
+/// 有动画（加载动画等）时的渲染帧率
+const ANIM_FPS: u64 = 60;
+
+/// 打印可读的错误信息并以退出码 1 结束（不打印 panic 回溯）
+fn fail(message: impl Display) -> ! {
+    eprintln!("错误: {}", message);
+    std::process::exit(1);
+}
 
 fn main() {
+    let mut args = cli::Args::parse();
+    if let Some(command) = args.command.take() {
+        std::process::exit(commands::run(command));
+    }
+
+    // 题目来源：--load 存档 > --puzzle 指定题面或分享链接 > 随机生成（--variant 为变体数独，--seed 可复现）
+    let mut config = config::Config::load();
+    let holes = args.holes(config.difficulty);
+    let target = args.target(config.difficulty);
+    let mut gameboard_controller = if let Some(path) = &args.load {
+        match savefile::load(path) {
+            Ok(controller) => controller,
+            Err(err) => fail(format_args!("无法读取存档 {}: {}", path.display(), err)),
+        }
+    } else if let Some(puzzle) = args.puzzle().cloned() {
+        GameboardController::new(puzzle)
+    } else if args.daily {
+        let daily = daily::fetch(&config.online, daily::Date::today());
+        let mut controller = GameboardController::new(daily.puzzle);
+        controller.daily = Some(daily.key);
+        controller
+    } else if let Some(kind) = args.variant {
+        let (puzzle, variant) = match args.seed {
+            Some(seed) => kind.generate_seeded(holes, seed),
+            None => kind.generate(holes),
+        };
+        let mut controller = GameboardController::new(puzzle);
+        controller.set_variant(variant);
+        controller
+    } else if let Some(word) = args.wordoku {
+        let word = word.unwrap_or_else(|| wordoku::random_word(&mut rand::thread_rng()));
+        GameboardController::new(match args.seed {
+            Some(seed) => Gameboard::generate_wordoku_seeded(word, holes, seed),
+            None => Gameboard::generate_wordoku(word, holes),
+        })
+    } else if let Some(target) = target {
+        let (puzzle, rated) = match args.seed {
+            Some(seed) => Gameboard::generate_rated_with_rng(
+                target,
+                RATED_BUDGET,
+                &mut StdRng::seed_from_u64(seed),
+            ),
+            None => Gameboard::generate_rated(target, RATED_BUDGET),
+        };
+        let mut controller = GameboardController::new(puzzle);
+        controller.fallback = (rated != target).then_some(rated);
+        controller
+    } else if let Some(seed) = args.seed {
+        GameboardController::new(Gameboard::generate_seeded(holes, seed))
+    } else {
+        GameboardController::new(Gameboard::generate_random(holes))
+    };
+
+    // 局域网联机：--host 以自己的题目开房，--join 改用房主发来的题目
+    let room = if let Some(port) = args.host {
+        let puzzle = Gameboard::from_cells(gameboard_controller.initial_cells);
+        let port = net::host(port, puzzle, args.coop)
+            .unwrap_or_else(|err| fail(format_args!("无法在端口 {} 开房: {}", port, err)));
+        println!(
+            "已在端口 {} 开房，其他玩家使用 --join <本机地址>:{} 加入",
+            port, port
+        );
+        let client = net::RoomClient::connect(("127.0.0.1", port), &args.name)
+            .unwrap_or_else(|err| fail(format_args!("无法连接到自己的房间: {}", err)));
+        Some(client)
+    } else if let Some(addr) = &args.join {
+        let addr = if addr.contains(':') {
+            addr.clone()
+        } else {
+            format!("{}:{}", addr, net::DEFAULT_PORT)
+        };
+        let client = net::RoomClient::connect(addr.as_str(), &args.name)
+            .unwrap_or_else(|err| fail(format_args!("无法加入 {}: {}", addr, err)));
+        gameboard_controller = GameboardController::new(client.puzzle.clone());
+        Some(client)
+    } else {
+        None
+    };
+    gameboard_controller.holes = holes;
+    gameboard_controller.target = target;
+    if args.colors {
+        config.assist.colors = true;
+    }
+    gameboard_controller.assist = config.assist;
+    let replay = args.replay.as_ref().map(|path| {
+        replay::Replay::load(path)
+            .unwrap_or_else(|err| fail(format_args!("无法读取回放 {}: {}", path.display(), err)))
+    });
+
+    if args.tui {
+        if let Err(err) = tui::run(&mut gameboard_controller) {
+            fail(format_args!("终端模式出错: {}", err));
+        }
+        return;
+    }
+
+    // 之后的 panic（绘制、字体等）先保存对局与崩溃报告再退出
+    crash::install();
+
     let opengl = OpenGL::V3_2;
-    // 初始窗口设置为纵向更高，确保棋盘下方的按钮可见
-    let setting = WindowSettings::new("Sudoku", [640, 750])
+    // 初始窗口设置为纵向更高，确保棋盘下方的按钮可见；Esc 由各场景自行处理
+    let setting = WindowSettings::new("Sudoku", [config.window.width, config.window.height])
         .graphics_api(opengl)
-        .exit_on_esc(true);
-    let mut window: GlutinWindow = setting.build().expect("Could not create window");
-    let mut events = Events::new(EventSettings::new().lazy(true));
+        .exit_on_esc(false);
+    let mut window = AppWindow::create(setting).unwrap_or_else(|err| {
+        fail(format_args!(
+            "无法创建 {} 窗口: {}\n没有图形环境时可以使用 --tui 在终端中游玩",
+            AppWindow::NAME,
+            err
+        ))
+    });
+    if let (Some(x), Some(y)) = (config.window.x, config.window.y) {
+        window.set_position([x, y]);
+    }
+    // 计时器运行时持续产生低频 update/render 事件；空闲时切回 lazy 模式以降低 CPU 占用。
+    // 画面没有变化时跳过绘制，因此由我们在绘制后自己交换缓冲区
+    let mut events = Events::new(
+        EventSettings::new()
+            .ups(UPS)
+            .max_fps(IDLE_FPS)
+            .swap_buffers(false),
+    );
+    let mut frame_mode = (true, IDLE_FPS);
     let mut gl = GlGraphics::new(opengl);
 
-    // 随机生成题目，指定空格数量（传入空格数量）
-    let gameboard = Gameboard::generate_random(gameboard::DEFAULT_HOLES);
-    let mut gameboard_controller = GameboardController::new(gameboard);
-
-    let gameboard_view_settings = GameboardViewSettings::new();
-    let mut gameboard_view = GameboardView::new(gameboard_view_settings);
+    if let Some(theme) = args.theme {
+        config.theme = theme;
+    }
+    let gameboard_view_settings = GameboardViewSettings::new()
+        .with_theme(config.theme)
+        .with_scale(config.scale);
+    let gameboard_view = GameboardView::new(gameboard_view_settings);
 
     let texture_settings = TextureSettings::new().filter(Filter::Nearest);
-    let ref mut glyphs = GlyphCache::new("assets/FiraSans-Regular.ttf", (), texture_settings)
-        .expect("Could not load font");
+    let glyphs = &mut assets::load_font(texture_settings).unwrap_or_else(|err| fail(err));
 
-    use piston::input::Button;
-    use piston::input::Key;
-    use piston::input::PressEvent;
+    // 指定了题目来源时直接开始游戏，否则先进入主菜单
+    let playing = args.load.is_some()
+        || args.puzzle().is_some()
+        || args.seed.is_some()
+        || args.variant.is_some()
+        || args.wordoku.is_some()
+        || args.daily
+        || room.is_some();
+    let history = history::History::open_default()
+        .map_err(|err| eprintln!("无法打开历史记录: {}", err))
+        .ok();
+    let packs = pack::discover(&args.pack);
+    let mut app = scene::App::new(
+        gameboard_controller,
+        gameboard_view,
+        config,
+        history,
+        packs,
+        playing,
+    );
+    app.room = room;
+    app.screen_reader = screen_reader::ScreenReader::attach(&mut window);
+    // --replay 直接进入回放
+    if let Some(replay) = replay {
+        app.open_replay(replay);
+    }
+    // --photo 识别照片后进入题目编辑器
+    #[cfg(feature = "ocr")]
+    if let Some(path) = &args.photo {
+        app.import_photo(path);
+    }
 
     while let Some(e) = events.next(&mut window) {
-        // 处理输入事件（controller 处理移动与数字输入）
-        gameboard_controller.event(
-            gameboard_view.settings.position,
-            gameboard_view.settings.size,
-            gameboard_view.settings.window_size,
-            &e,
+        if let Some(args) = e.update_args() {
+            app.update(args.dt);
+        }
+
+        // 根据是否需要持续刷新调整事件循环：动画时高帧率，只有计时器时低帧率，否则 lazy
+        let animating = app.is_animating();
+        // 读屏软件连接时也保持低频刷新，它请求的操作在下一次 update 时执行
+        let mode = (
+            animating || app.timer_running() || app.screen_reader.is_active(),
+            if animating { ANIM_FPS } else { IDLE_FPS },
         );
+        if mode != frame_mode {
+            events.set_lazy(!mode.0);
+            events.set_max_fps(mode.1);
+            frame_mode = mode;
+        }
 
-        // 全局快捷键：U=undo, R=reset, G=randomize
-        if let Some(Button::Keyboard(k)) = e.press_args() {
-            match k {
-                Key::U => gameboard_controller.undo(),
-                Key::R => gameboard_controller.reset(),
-                Key::G => gameboard_controller.randomize(gameboard::DEFAULT_HOLES),
-                _ => {}
-            }
+        // 输入事件（包括窗口焦点变化，用于自动暂停）交给当前场景处理
+        if !app.event(&e) {
+            window.set_should_close(true);
         }
+        crash::remember(&app.tabs[app.active]);
 
         // 渲染
-        if let Some(args) = e.render_args() {
+        if let Some(args) = e.render_args().filter(|_| app.take_redraw()) {
             gl.draw(args.viewport(), |c, g| {
                 use graphics::clear;
                 // try to get actual window size from the window object; fallback to viewport
                 let (win_w, win_h) = {
                     // GlutinWindow usually provides a `size()` method returning [u32; 2]
                     let s = window.size();
-                    (s.width as f64, s.height as f64)
+                    (s.width, s.height)
+                };
+                let settings = &mut app.view.settings;
+                // 棋盘上下各留出按钮一行（或 HUD）的高度，放大界面时按钮与 HUD 不压住棋盘
+                let reserved = settings.button_area().max(settings.hud_area());
+                // 三明治数独在棋盘左侧与上方（对称地也在右侧与下方）留出外侧提示的位置
+                let clues = if matches!(app.tabs[app.active].variant, Some(Variant::Sandwich(_))) {
+                    settings.clue_area()
+                } else {
+                    0.0
                 };
-                let size = win_w.min(win_h);
+                // 色块模式在棋盘左侧（对称地也在右侧）留出调色板的位置
+                let palette = if app.tabs[app.active].assist.colors {
+                    settings.palette_area()
+                } else {
+                    0.0
+                };
+                let size = (win_w - 2.0 * (clues + palette))
+                    .min(win_h - 2.0 * reserved - 2.0 * clues)
+                    .max(16.0);
                 let pos = [(win_w - size) / 2.0, (win_h - size) / 2.0];
-                gameboard_view.settings.position = pos;
-                gameboard_view.settings.size = size;
+                settings.position = pos;
+                settings.size = size;
                 // inform view about current window size so overlays (buttons) can stay visible
-                gameboard_view.settings.window_size = [win_w, win_h];
+                settings.window_size = [win_w, win_h];
 
-                clear([1.0; 4], g);
-                gameboard_view.draw(&gameboard_controller, glyphs, &c, g);
+                clear(settings.clear_color, g);
+                app.draw(glyphs, &c, g);
             });
+            window.swap_buffers();
         }
     }
+
+    println!("本次游戏:\n{}", app.stats.summary().join("\n"));
+
+    // 记录窗口大小与位置，下次启动时恢复
+    let mut config = app.config;
+    let size = window.size();
+    config.window.width = size.width.round() as u32;
+    config.window.height = size.height.round() as u32;
+    if let Some(pos) = window.get_position() {
+        config.window.x = Some(pos.x);
+        config.window.y = Some(pos.y);
+    }
+    if let Err(err) = config.save() {
+        eprintln!("无法保存配置: {}", err);
+    }
 }
@@ -0,0 +1,494 @@
+//! 局域网联机：一人用 `--host` 开房，其他人用 `--join 地址` 加入，所有人拿到同一道题。
+//!
+//! 两种玩法：
+//! - 竞速（默认）：侧栏显示每位玩家的进度，第一个正确提交的玩家获胜；
+//! - 合作（`--host --coop`）：所有人编辑同一个盘面，每人的光标与填写用各自的颜色显示。
+//!
+//! 协议是 TCP 上逐行的文本：
+//! ```text
+//! 客户端 -> 主机   hello <名字>
+//!                  progress <百分比>
+//!                  solved <秒数> <81 字符盘面>
+//!                  set <x> <y> <值>
+//!                  cursor <x> <y>
+//! 主机 -> 客户端   welcome <id>
+//!                  mode coop
+//!                  puzzle <81 字符题面>
+//!                  player <id> <百分比> <名字>
+//!                  left <id>
+//!                  winner <秒数> <名字>
+//!                  set <id> <x> <y> <值>
+//!                  cursor <id> <x> <y>
+//! ```
+//! 开房的一方同样作为客户端连接到自己的房间，因此所有玩家走同一套逻辑。
+//! 提交的盘面由主机校验（填满、无冲突、与题面一致），客户端无法谎报获胜。
+//! 合作模式下 `set` 就是 controller 的 `GameEvent` 流：本地事件发给主机，
+//! 主机转发给其他玩家并保存当前盘面，后加入的玩家会先收到已有的全部修改。
+
+use crate::gameboard_controller::format_time;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use sudoku_core::Gameboard;
+
+/// 默认端口
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// 加入房间时等待题目的最长时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 合作模式中玩家颜色的名称，与 `GameboardViewSettings::player_colors` 一一对应
+pub const PLAYER_COLOR_NAMES: [&str; 4] = ["blue", "orange", "green", "purple"];
+
+/// 房间中的一位玩家
+#[derive(Clone, Debug)]
+pub struct Player {
+    pub id: u32,
+    pub name: String,
+    /// 已填空格的百分比
+    pub progress: u8,
+}
+
+/// 玩家 id 对应的颜色序号
+pub fn color_index(id: u32) -> usize {
+    (id as usize + PLAYER_COLOR_NAMES.len() - 1) % PLAYER_COLOR_NAMES.len()
+}
+
+/// 主机端的房间状态（所有连接共享）
+struct Room {
+    puzzle: Gameboard,
+    coop: bool,
+    /// 合作模式的当前盘面与每格最后的修改者（0 表示无人修改）
+    board: Gameboard,
+    owners: [[u32; 9]; 9],
+    peers: Vec<(Player, TcpStream)>,
+    winner: Option<(f64, String)>,
+    next_id: u32,
+}
+
+impl Room {
+    /// 向所有玩家发送一行；写失败的连接由各自的读取线程在断开时清理
+    fn broadcast(&mut self, line: &str) {
+        self.broadcast_except(0, line);
+    }
+
+    /// 向除 `id` 以外的玩家发送一行
+    fn broadcast_except(&mut self, id: u32, line: &str) {
+        for (player, stream) in &mut self.peers {
+            if player.id != id {
+                let _ = writeln!(stream, "{}", line);
+            }
+        }
+    }
+
+    fn player_line(player: &Player) -> String {
+        format!("player {} {} {}", player.id, player.progress, player.name)
+    }
+
+    /// 提交的盘面是否是本题的正确解
+    fn is_solution(&self, board: &Gameboard) -> bool {
+        let givens_kept = (0..9).all(|y| {
+            (0..9).all(|x| {
+                let given = self.puzzle.cells[y][x];
+                given == 0 || given == board.cells[y][x]
+            })
+        });
+        givens_kept && board.is_complete() && board.conflicting_cells().is_empty()
+    }
+}
+
+/// 在 `port` 上开房（后台线程接受连接），返回实际监听的端口
+pub fn host(port: u16, puzzle: Gameboard, coop: bool) -> io::Result<u16> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    let port = listener.local_addr()?.port();
+    let room = Arc::new(Mutex::new(Room {
+        board: puzzle.clone(),
+        puzzle,
+        coop,
+        owners: [[0; 9]; 9],
+        peers: Vec::new(),
+        winner: None,
+        next_id: 1,
+    }));
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let room = Arc::clone(&room);
+            thread::spawn(move || {
+                if let Err(err) = serve(stream, &room) {
+                    eprintln!("联机连接出错: {}", err);
+                }
+            });
+        }
+    });
+    Ok(port)
+}
+
+/// 处理一个玩家的连接，直到断开
+fn serve(stream: TcpStream, room: &Mutex<Room>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut lines = BufReader::new(stream).lines();
+    let name = match lines.next() {
+        Some(Ok(line)) => line
+            .strip_prefix("hello ")
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or("player")
+            .to_string(),
+        _ => return Ok(()),
+    };
+
+    let id = {
+        let mut room = room.lock().unwrap();
+        let id = room.next_id;
+        room.next_id += 1;
+        writeln!(writer, "welcome {}", id)?;
+        if room.coop {
+            writeln!(writer, "mode coop")?;
+        }
+        writeln!(writer, "puzzle {}", room.puzzle.to_line())?;
+        for (player, _) in &room.peers {
+            writeln!(writer, "{}", Room::player_line(player))?;
+        }
+        if let Some((seconds, winner)) = &room.winner {
+            writeln!(writer, "winner {} {}", seconds, winner)?;
+        }
+        // 合作模式：补发已有的修改
+        for y in 0..9 {
+            for x in 0..9 {
+                let owner = room.owners[y][x];
+                if owner != 0 {
+                    writeln!(
+                        writer,
+                        "set {} {} {} {}",
+                        owner, x, y, room.board.cells[y][x]
+                    )?;
+                }
+            }
+        }
+        let player = Player {
+            id,
+            name,
+            progress: 0,
+        };
+        let line = Room::player_line(&player);
+        room.peers.push((player, writer));
+        room.broadcast(&line);
+        id
+    };
+
+    for line in lines {
+        let line = line?;
+        let (key, value) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        let mut room = room.lock().unwrap();
+        match key {
+            "progress" => {
+                let Ok(progress) = value.trim().parse::<u8>() else {
+                    continue;
+                };
+                let Some((player, _)) = room.peers.iter_mut().find(|(p, _)| p.id == id) else {
+                    continue;
+                };
+                player.progress = progress.min(100);
+                let line = Room::player_line(player);
+                room.broadcast(&line);
+            }
+            "solved" => {
+                let Some((seconds, board)) = value.split_once(' ') else {
+                    continue;
+                };
+                let (Ok(seconds), Ok(board)) =
+                    (seconds.parse::<f64>(), Gameboard::from_line(board.trim()))
+                else {
+                    continue;
+                };
+                if !room.coop && room.winner.is_none() && room.is_solution(&board) {
+                    let name = room
+                        .peers
+                        .iter()
+                        .find(|(p, _)| p.id == id)
+                        .map(|(p, _)| p.name.clone())
+                        .unwrap_or_default();
+                    room.broadcast(&format!("winner {} {}", seconds, name));
+                    room.winner = Some((seconds, name));
+                }
+            }
+            "set" if room.coop => {
+                let Some([x, y, value]) = parse_numbers(value) else {
+                    continue;
+                };
+                if x >= 9 || y >= 9 || value > 9 {
+                    continue;
+                }
+                let (x, y, value) = (x as usize, y as usize, value as u8);
+                if room.puzzle.cells[y][x] != 0 {
+                    continue;
+                }
+                room.board.cells[y][x] = value;
+                room.owners[y][x] = if value == 0 { 0 } else { id };
+                room.broadcast_except(id, &format!("set {} {} {} {}", id, x, y, value));
+            }
+            "cursor" if room.coop => {
+                let Some([x, y]) = parse_numbers(value) else {
+                    continue;
+                };
+                room.broadcast_except(id, &format!("cursor {} {} {}", id, x, y));
+            }
+            _ => {}
+        }
+    }
+
+    let mut room = room.lock().unwrap();
+    room.peers.retain(|(p, _)| p.id != id);
+    room.broadcast(&format!("left {}", id));
+    Ok(())
+}
+
+/// 客户端收到的消息
+enum Message {
+    Player(Player),
+    Left(u32),
+    Winner(f64, String),
+    Set(u32, [usize; 2], u8),
+    Cursor(u32, [usize; 2]),
+    Disconnected,
+}
+
+/// 已加入的联机房间
+pub struct RoomClient {
+    stream: TcpStream,
+    receiver: Receiver<Message>,
+    /// 本局题目
+    pub puzzle: Gameboard,
+    /// 是否是合作模式
+    pub coop: bool,
+    /// 自己在房间中的 id
+    pub id: u32,
+    pub players: Vec<Player>,
+    /// 获胜者的 (用时, 名字)
+    pub winner: Option<(f64, String)>,
+    /// 与主机的连接是否已断开
+    pub disconnected: bool,
+    /// 合作模式：每格最后的修改者（0 表示题面或无人修改）
+    pub owners: [[u32; 9]; 9],
+    /// 合作模式：其他玩家选中的格子
+    pub cursors: Vec<(u32, [usize; 2])>,
+    /// 合作模式：已发送的本地事件数（controller `events` 的下标）
+    pub sent_events: usize,
+    /// 收到但尚未应用到盘面的其他玩家的修改
+    remote: Vec<([usize; 2], u8)>,
+    /// 上次发送的进度 / 光标（只在变化时发送）
+    sent_progress: Option<u8>,
+    sent_cursor: Option<[usize; 2]>,
+    /// 是否已提交过
+    submitted: bool,
+}
+
+impl RoomClient {
+    /// 连接到 `addr` 并等待主机发来题目
+    pub fn connect(addr: impl ToSocketAddrs, name: &str) -> io::Result<Self> {
+        let mut stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        writeln!(stream, "hello {}", name.replace(['\r', '\n'], " "))?;
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut id = 0;
+        let mut coop = false;
+        let puzzle = loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "host closed the connection",
+                ));
+            }
+            match line.trim().split_once(' ') {
+                Some(("welcome", value)) => id = value.parse().unwrap_or(0),
+                Some(("mode", value)) => coop = value == "coop",
+                Some(("puzzle", value)) => {
+                    break Gameboard::from_line(value)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+                }
+                _ => {}
+            }
+        };
+        stream.set_read_timeout(None)?;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(message) = parse_message(&line) {
+                    if sender.send(message).is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = sender.send(Message::Disconnected);
+        });
+
+        Ok(RoomClient {
+            stream,
+            receiver,
+            puzzle,
+            coop,
+            id,
+            players: Vec::new(),
+            winner: None,
+            disconnected: false,
+            owners: [[0; 9]; 9],
+            cursors: Vec::new(),
+            sent_events: 0,
+            remote: Vec::new(),
+            sent_progress: None,
+            sent_cursor: None,
+            submitted: false,
+        })
+    }
+
+    /// 处理已收到的消息（每次 update 调用）
+    pub fn poll(&mut self) {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Message::Player(player)) => {
+                    match self.players.iter_mut().find(|p| p.id == player.id) {
+                        Some(p) => *p = player,
+                        None => self.players.push(player),
+                    }
+                }
+                Ok(Message::Left(id)) => {
+                    self.players.retain(|p| p.id != id);
+                    self.cursors.retain(|&(c, _)| c != id);
+                }
+                Ok(Message::Winner(seconds, name)) => self.winner = Some((seconds, name)),
+                Ok(Message::Set(id, [x, y], value)) => {
+                    self.owners[y][x] = if value == 0 { 0 } else { id };
+                    self.remote.push(([x, y], value));
+                }
+                Ok(Message::Cursor(id, cell)) => {
+                    match self.cursors.iter_mut().find(|(c, _)| *c == id) {
+                        Some((_, c)) => *c = cell,
+                        None => self.cursors.push((id, cell)),
+                    }
+                }
+                Ok(Message::Disconnected) | Err(TryRecvError::Disconnected) => {
+                    self.disconnected = true;
+                    return;
+                }
+                Err(TryRecvError::Empty) => return,
+            }
+        }
+    }
+
+    /// 取出其他玩家的修改，按收到的顺序应用到本地盘面
+    pub fn take_remote(&mut self) -> Vec<([usize; 2], u8)> {
+        std::mem::take(&mut self.remote)
+    }
+
+    /// 报告自己的进度（与上次相同时不发送）
+    pub fn send_progress(&mut self, progress: u8) {
+        if self.sent_progress != Some(progress) && !self.disconnected {
+            self.sent_progress = Some(progress);
+            let _ = writeln!(self.stream, "progress {}", progress);
+        }
+    }
+
+    /// 提交解出的盘面，由主机判定是否获胜（只提交一次）
+    pub fn send_solved(&mut self, seconds: f64, board: &Gameboard) {
+        if !self.submitted && !self.disconnected {
+            self.submitted = true;
+            let _ = writeln!(self.stream, "solved {} {}", seconds, board.to_line());
+        }
+    }
+
+    /// 合作模式：发送本地的一次修改
+    pub fn send_set(&mut self, [x, y]: [usize; 2], value: u8) {
+        self.owners[y][x] = if value == 0 { 0 } else { self.id };
+        if !self.disconnected {
+            let _ = writeln!(self.stream, "set {} {} {}", x, y, value);
+        }
+    }
+
+    /// 合作模式：发送自己选中的格子（变化时才发送）
+    pub fn send_cursor(&mut self, cell: Option<[usize; 2]>) {
+        let Some([x, y]) = cell else {
+            return;
+        };
+        if self.sent_cursor != cell && !self.disconnected {
+            self.sent_cursor = cell;
+            let _ = writeln!(self.stream, "cursor {} {}", x, y);
+        }
+    }
+
+    /// 侧栏显示的文字：竞速时为进度与获胜者，合作时为玩家与各自的颜色
+    pub fn sidebar_lines(&self) -> Vec<String> {
+        let mut lines = vec![if self.coop { "Co-op" } else { "Race" }.to_string()];
+        for player in &self.players {
+            let you = if player.id == self.id { " (you)" } else { "" };
+            lines.push(if self.coop {
+                let color = PLAYER_COLOR_NAMES[color_index(player.id)];
+                format!("{}{}  {}", player.name, you, color)
+            } else {
+                format!("{}{}  {}%", player.name, you, player.progress)
+            });
+        }
+        if let Some((seconds, name)) = &self.winner {
+            lines.push(format!("Winner: {} {}", name, format_time(*seconds)));
+        }
+        if self.disconnected {
+            lines.push("Disconnected".to_string());
+        }
+        lines
+    }
+}
+
+impl Drop for RoomClient {
+    /// 关闭连接（读取线程持有同一个 socket，只 drop 写端不会断开）
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+/// 解析以空格分隔的 N 个非负整数
+fn parse_numbers<const N: usize>(value: &str) -> Option<[u32; N]> {
+    let mut numbers = [0; N];
+    let mut parts = value.split_whitespace();
+    for n in &mut numbers {
+        *n = parts.next()?.parse().ok()?;
+    }
+    Some(numbers)
+}
+
+fn parse_message(line: &str) -> Option<Message> {
+    let (key, value) = line.trim().split_once(' ')?;
+    match key {
+        "player" => {
+            let mut parts = value.splitn(3, ' ');
+            Some(Message::Player(Player {
+                id: parts.next()?.parse().ok()?,
+                progress: parts.next()?.parse().ok()?,
+                name: parts.next().unwrap_or_default().to_string(),
+            }))
+        }
+        "left" => value.parse().ok().map(Message::Left),
+        "winner" => {
+            let (seconds, name) = value.split_once(' ').unwrap_or((value, ""));
+            Some(Message::Winner(seconds.parse().ok()?, name.to_string()))
+        }
+        "set" => {
+            let [id, x, y, v] = parse_numbers(value)?;
+            let cell = [x as usize, y as usize];
+            (x < 9 && y < 9 && v <= 9).then_some(Message::Set(id, cell, v as u8))
+        }
+        "cursor" => {
+            let [id, x, y] = parse_numbers(value)?;
+            (x < 9 && y < 9).then_some(Message::Cursor(id, [x as usize, y as usize]))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,409 @@
+//! 照片导入（`ocr` feature）：从印刷数独的照片中找出棋盘并识别数字，结果放进题目编辑器供玩家修正。
+//!
+//! 做法很简单：灰度化后按局部均值二值化，取最大的深色连通区域作为棋盘外框，
+//! 用它的四个角把棋盘拉正成 9×9 的格子；每格去掉边线后若有足够大的深色连通块，
+//! 就把它缩放成小位图，与用界面字体渲染的 1～9 模板比较倒角距离
+//! （封闭空洞的个数不同时另加罚分），取最相近的数字。
+//! 照片需要大致正对棋盘；手写数字和特殊字体可能认错，所以结果总要在编辑器中核对。
+
+use crate::assets::{self, FONT_FILE};
+use rusttype::{point, Font, Scale};
+use std::collections::VecDeque;
+use std::path::Path;
+use sudoku_core::{Gameboard, SIZE};
+
+/// 处理前把照片缩小到的最长边（像素）
+const MAX_SIDE: u32 = 1000;
+
+/// 拉正后每格的边长（像素）
+const CELL: usize = 48;
+
+/// 数字位图的宽高（模板与格子中的数字都缩放到这个大小再比较）
+const GLYPH_W: usize = 16;
+const GLYPH_H: usize = 24;
+
+/// 比局部均值暗这么多（百分比）才算深色
+const THRESHOLD: u32 = 15;
+
+/// 识别照片中的题目
+pub fn recognize(path: &Path) -> Result<Gameboard, String> {
+    let photo = image::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut gray = photo.to_luma8();
+    let (w, h) = gray.dimensions();
+    if w.max(h) > MAX_SIDE {
+        let scale = f64::from(MAX_SIDE) / f64::from(w.max(h));
+        let (w, h) = ((f64::from(w) * scale) as u32, (f64::from(h) * scale) as u32);
+        gray = image::imageops::resize(&gray, w, h, image::imageops::FilterType::Triangle);
+    }
+    let (w, h) = gray.dimensions();
+    let mask = Mask::threshold(w as usize, h as usize, gray.as_raw());
+    let corners = mask.grid_corners().ok_or("no grid found in the photo")?;
+    let board = mask.warp(corners);
+    let templates = templates()?;
+
+    let mut cells = [[0u8; SIZE]; SIZE];
+    for (r, row) in cells.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            if let Some(glyph) = board.digit_in_cell(r, c) {
+                *cell = classify(&glyph, &templates);
+            }
+        }
+    }
+    Ok(Gameboard::from_cells(cells))
+}
+
+/// 二值图：true 为深色
+struct Mask {
+    w: usize,
+    h: usize,
+    dark: Vec<bool>,
+}
+
+impl Mask {
+    /// 与周围 (2r+1)² 窗口的均值比较（积分图求均值），不受光照不均影响
+    fn threshold(w: usize, h: usize, gray: &[u8]) -> Self {
+        let mut integral = vec![0u64; (w + 1) * (h + 1)];
+        for y in 0..h {
+            let mut row = 0u64;
+            for x in 0..w {
+                row += u64::from(gray[y * w + x]);
+                integral[(y + 1) * (w + 1) + x + 1] = integral[y * (w + 1) + x + 1] + row;
+            }
+        }
+        let r = (w.max(h) / 40).max(3);
+        let mut dark = vec![false; w * h];
+        for y in 0..h {
+            let (y0, y1) = (y.saturating_sub(r), (y + r + 1).min(h));
+            for x in 0..w {
+                let (x0, x1) = (x.saturating_sub(r), (x + r + 1).min(w));
+                let sum = integral[y1 * (w + 1) + x1] + integral[y0 * (w + 1) + x0]
+                    - integral[y0 * (w + 1) + x1]
+                    - integral[y1 * (w + 1) + x0];
+                let count = ((x1 - x0) * (y1 - y0)) as u64;
+                let value = u64::from(gray[y * w + x]);
+                dark[y * w + x] = value * 100 * count < sum * u64::from(100 - THRESHOLD);
+            }
+        }
+        Mask { w, h, dark }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.dark[y * self.w + x]
+    }
+
+    /// 从 `(x, y)` 出发的四连通深色区域（只在 `area` = [x0, y0, x1, y1) 内）
+    fn component(
+        &self,
+        seen: &mut [bool],
+        x: usize,
+        y: usize,
+        area: [usize; 4],
+    ) -> Vec<[usize; 2]> {
+        let [x0, y0, x1, y1] = area;
+        let mut pixels = Vec::new();
+        let mut queue = VecDeque::from([[x, y]]);
+        seen[y * self.w + x] = true;
+        while let Some([x, y]) = queue.pop_front() {
+            pixels.push([x, y]);
+            let neighbours = [
+                [x.wrapping_sub(1), y],
+                [x + 1, y],
+                [x, y.wrapping_sub(1)],
+                [x, y + 1],
+            ];
+            for [nx, ny] in neighbours {
+                if (x0..x1).contains(&nx) && (y0..y1).contains(&ny) {
+                    let i = ny * self.w + nx;
+                    if self.dark[i] && !seen[i] {
+                        seen[i] = true;
+                        queue.push_back([nx, ny]);
+                    }
+                }
+            }
+        }
+        pixels
+    }
+
+    /// `area` 内全部深色连通区域
+    fn components(&self, area: [usize; 4]) -> Vec<Vec<[usize; 2]>> {
+        let mut seen = vec![false; self.w * self.h];
+        let mut found = Vec::new();
+        for y in area[1]..area[3] {
+            for x in area[0]..area[2] {
+                if self.get(x, y) && !seen[y * self.w + x] {
+                    found.push(self.component(&mut seen, x, y, area));
+                }
+            }
+        }
+        found
+    }
+
+    /// 最大的深色区域（棋盘线连成一片）的四个角：左上、右上、左下、右下
+    fn grid_corners(&self) -> Option<[[f64; 2]; 4]> {
+        let grid = self
+            .components([0, 0, self.w, self.h])
+            .into_iter()
+            .max_by_key(Vec::len)?;
+        // 太小的区域不会是棋盘
+        if grid.len() < self.w.min(self.h) * 4 {
+            return None;
+        }
+        let corner = |key: fn(f64, f64) -> f64| {
+            let &[x, y] = grid
+                .iter()
+                .max_by(|a, b| {
+                    let ka = key(a[0] as f64, a[1] as f64);
+                    let kb = key(b[0] as f64, b[1] as f64);
+                    ka.total_cmp(&kb)
+                })
+                .unwrap();
+            [x as f64, y as f64]
+        };
+        Some([
+            corner(|x, y| -x - y),
+            corner(|x, y| x - y),
+            corner(|x, y| y - x),
+            corner(|x, y| x + y),
+        ])
+    }
+
+    /// 把四个角围成的四边形双线性映射成 9×9 格的正方形
+    fn warp(&self, [tl, tr, bl, br]: [[f64; 2]; 4]) -> Mask {
+        let side = CELL * SIZE;
+        let mut dark = vec![false; side * side];
+        for ty in 0..side {
+            let v = (ty as f64 + 0.5) / side as f64;
+            for tx in 0..side {
+                let u = (tx as f64 + 0.5) / side as f64;
+                let lerp = |a: [f64; 2], b: [f64; 2], t: f64| {
+                    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+                };
+                let [x, y] = lerp(lerp(tl, tr, u), lerp(bl, br, u), v);
+                let (x, y) = (x.round() as usize, y.round() as usize);
+                dark[ty * side + tx] = x < self.w && y < self.h && self.get(x, y);
+            }
+        }
+        Mask {
+            w: side,
+            h: side,
+            dark,
+        }
+    }
+
+    /// 拉正后第 `r` 行第 `c` 列格子中的数字位图；空格返回 None。
+    /// 去掉格子边缘后取最大的连通块，碰到边缘的（残留的格线）和太矮的（噪点）不算
+    fn digit_in_cell(&self, r: usize, c: usize) -> Option<Vec<bool>> {
+        let margin = CELL / 8;
+        let area = [
+            c * CELL + margin,
+            r * CELL + margin,
+            (c + 1) * CELL - margin,
+            (r + 1) * CELL - margin,
+        ];
+        let digit = self
+            .components(area)
+            .into_iter()
+            .filter(|pixels| {
+                let [x0, y0, x1, y1] = bounds(pixels);
+                let touches = x0 == area[0] || y0 == area[1] || x1 == area[2] || y1 == area[3];
+                !touches && (y1 - y0) * 10 >= CELL * 3
+            })
+            .max_by_key(Vec::len)?;
+        Some(normalize(&digit))
+    }
+}
+
+/// 像素集合的外接矩形 [x0, y0, x1, y1)
+fn bounds(pixels: &[[usize; 2]]) -> [usize; 4] {
+    let xs = pixels.iter().map(|p| p[0]);
+    let ys = pixels.iter().map(|p| p[1]);
+    [
+        xs.clone().min().unwrap_or(0),
+        ys.clone().min().unwrap_or(0),
+        xs.max().map_or(0, |x| x + 1),
+        ys.max().map_or(0, |y| y + 1),
+    ]
+}
+
+/// 按高度缩放到 `GLYPH_H`，保持宽高比并水平居中（"1" 不会被拉宽）
+fn normalize(pixels: &[[usize; 2]]) -> Vec<bool> {
+    let [x0, y0, x1, y1] = bounds(pixels);
+    let (w, h) = (x1 - x0, y1 - y0);
+    let mut source = vec![false; w * h];
+    for &[x, y] in pixels {
+        source[(y - y0) * w + x - x0] = true;
+    }
+    let scale = h as f64 / GLYPH_H as f64;
+    let width = ((w as f64 / scale).round() as usize).clamp(1, GLYPH_W);
+    let left = (GLYPH_W - width) / 2;
+    let mut glyph = vec![false; GLYPH_W * GLYPH_H];
+    for gy in 0..GLYPH_H {
+        let sy = (((gy as f64 + 0.5) * scale) as usize).min(h - 1);
+        for gx in 0..width {
+            let sx = (((gx as f64 + 0.5) * w as f64 / width as f64) as usize).min(w - 1);
+            glyph[gy * GLYPH_W + left + gx] = source[sy * w + sx];
+        }
+    }
+    glyph
+}
+
+/// 一个数字模板：位图、距离图与其中封闭的空洞数
+#[derive(Clone)]
+struct Template {
+    digit: u8,
+    glyph: Vec<bool>,
+    distances: Vec<u32>,
+    holes: usize,
+}
+
+impl Template {
+    fn new(digit: u8, pixels: &[[usize; 2]]) -> Self {
+        let glyph = normalize(pixels);
+        Template {
+            digit,
+            distances: distances(&glyph),
+            holes: holes(&glyph),
+            glyph,
+        }
+    }
+}
+
+/// 用界面字体渲染 1～9 的模板。印刷体的 1 常带底座、4 常是封口的，各另加一个变体
+fn templates() -> Result<Vec<Template>, String> {
+    let bytes = assets::read(FONT_FILE).ok_or_else(|| format!("font {} not found", FONT_FILE))?;
+    let font = Font::try_from_vec(bytes).ok_or_else(|| format!("invalid font {}", FONT_FILE))?;
+    let mut templates = Vec::new();
+    for digit in 1..=9u8 {
+        let glyph = font
+            .glyph(char::from(b'0' + digit))
+            .scaled(Scale::uniform(64.0))
+            .positioned(point(0.0, 0.0));
+        let mut pixels = Vec::new();
+        glyph.draw(|x, y, coverage| {
+            if coverage > 0.5 {
+                pixels.push([x as usize, y as usize]);
+            }
+        });
+        let template = Template::new(digit, &pixels);
+        if digit == 4 {
+            // 4 有开口与封口两种写法，空洞数两样都认
+            templates.push(Template {
+                holes: 1 - template.holes.min(1),
+                ..template.clone()
+            });
+        }
+        templates.push(template);
+        if digit == 1 {
+            // 底座居中在竖笔下方，与竖笔同粗，左右各伸出约四分之一字高
+            let [_, y0, _, y1] = bounds(&pixels);
+            let bottom: Vec<usize> = pixels
+                .iter()
+                .filter(|p| p[1] == y1 - 1)
+                .map(|p| p[0])
+                .collect();
+            let stem = (
+                bottom.iter().sum::<usize>() / bottom.len().max(1),
+                bottom.len(),
+            );
+            let reach = (y1 - y0) / 4;
+            let mut based: Vec<[usize; 2]> = pixels.iter().map(|&[x, y]| [x + reach, y]).collect();
+            for y in y1 - stem.1.clamp(1, (y1 - y0) / 8)..y1 {
+                based.extend((stem.0..stem.0 + 2 * reach + 1).map(|x| [x, y]));
+            }
+            templates.push(Template::new(digit, &based));
+        }
+    }
+    Ok(templates)
+}
+
+/// 位图中被深色像素围住的浅色区域个数（8 有两个，6、9 有一个）
+fn holes(glyph: &[bool]) -> usize {
+    // 四周补一圈浅色，与外面连通的浅色区域就不算空洞
+    let (w, h) = (GLYPH_W + 2, GLYPH_H + 2);
+    let mut light = vec![true; w * h];
+    for y in 0..GLYPH_H {
+        for x in 0..GLYPH_W {
+            light[(y + 1) * w + x + 1] = !glyph[y * GLYPH_W + x];
+        }
+    }
+    let mask = Mask { w, h, dark: light };
+    // 笔画间偶尔漏出的一两个浅色像素不算
+    let holes = mask
+        .components([0, 0, w, h])
+        .into_iter()
+        .filter(|c| c.len() >= 4);
+    holes.count().saturating_sub(1)
+}
+
+/// 每个位置到最近深色像素的距离（3-4 倒角距离，两遍扫描）
+fn distances(glyph: &[bool]) -> Vec<u32> {
+    const FAR: u32 = u32::MAX / 2;
+    let mut d: Vec<u32> = glyph
+        .iter()
+        .map(|&dark| if dark { 0 } else { FAR })
+        .collect();
+    let at = |x: usize, y: usize| y * GLYPH_W + x;
+    for y in 0..GLYPH_H {
+        for x in 0..GLYPH_W {
+            let mut best = d[at(x, y)];
+            if x > 0 {
+                best = best.min(d[at(x - 1, y)] + 3);
+            }
+            if y > 0 {
+                best = best.min(d[at(x, y - 1)] + 3);
+                if x > 0 {
+                    best = best.min(d[at(x - 1, y - 1)] + 4);
+                }
+                if x + 1 < GLYPH_W {
+                    best = best.min(d[at(x + 1, y - 1)] + 4);
+                }
+            }
+            d[at(x, y)] = best;
+        }
+    }
+    for y in (0..GLYPH_H).rev() {
+        for x in (0..GLYPH_W).rev() {
+            let mut best = d[at(x, y)];
+            if x + 1 < GLYPH_W {
+                best = best.min(d[at(x + 1, y)] + 3);
+            }
+            if y + 1 < GLYPH_H {
+                best = best.min(d[at(x, y + 1)] + 3);
+                if x + 1 < GLYPH_W {
+                    best = best.min(d[at(x + 1, y + 1)] + 4);
+                }
+                if x > 0 {
+                    best = best.min(d[at(x - 1, y + 1)] + 4);
+                }
+            }
+            d[at(x, y)] = best;
+        }
+    }
+    d
+}
+
+/// 空洞数每差一个加的距离，让 6、8、9 这类笔画相近的数字分得开
+const HOLE_PENALTY: f64 = 5.0;
+
+/// 与模板的倒角距离（双向平均）最小的数字：笔画粗细不同时也能对上
+fn classify(glyph: &[bool], templates: &[Template]) -> u8 {
+    // 一个位图的深色像素到另一个位图最近深色像素的平均距离
+    let mean = |from: &[bool], to: &[u32]| {
+        let (sum, count) = from
+            .iter()
+            .zip(to)
+            .filter(|(dark, _)| **dark)
+            .fold((0u32, 0u32), |(sum, count), (_, &d)| (sum + d, count + 1));
+        f64::from(sum) / f64::from(count.max(1))
+    };
+    let (own, own_holes) = (distances(glyph), holes(glyph));
+    let score = |t: &Template| {
+        mean(glyph, &t.distances)
+            + mean(&t.glyph, &own)
+            + t.holes.abs_diff(own_holes) as f64 * HOLE_PENALTY
+    };
+    templates
+        .iter()
+        .min_by(|a, b| score(a).total_cmp(&score(b)))
+        .map_or(0, |t| t.digit)
+}
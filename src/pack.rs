@@ -0,0 +1,184 @@
+//! 题库（puzzle pack）：一组精选题目，保存为每行一题的文本文件。
+//!
+//! ```text
+//! # name: Starter
+//! 530070000600195000098000060800060003400803001700020006060000280000419005000080079
+//! ...
+//! ```
+//!
+//! `# name:` 行可选，缺省时用文件名作为题库名；其它 `#` 开头的行是注释。
+//! 也可以直接读取 OpenSudoku 的 `.opensudoku` XML 题库（见 `PuzzlePack::parse_opensudoku`）。
+//! 启动时从 `./packs` 与数据目录下的 `packs/`（Linux 为 `~/.local/share/sudoku/packs`）
+//! 读取全部题库，也可以用 `--pack` 额外指定文件或目录。
+
+use crate::paths;
+use std::fs;
+use std::path::{Path, PathBuf};
+use sudoku_core::Gameboard;
+
+/// 一个题库
+#[derive(Clone, Debug)]
+pub struct PuzzlePack {
+    pub name: String,
+    pub puzzles: Vec<Gameboard>,
+}
+
+/// 解析每行一题的文本（空行与 `#` 注释行跳过），错误信息带行号
+pub fn parse_lines(text: &str) -> Result<Vec<Gameboard>, String> {
+    text.lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| Gameboard::from_line(line).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+impl PuzzlePack {
+    /// 从文本解析题库；没有 `# name:` 行时使用 `default_name`
+    pub fn parse(default_name: &str, text: &str) -> Result<Self, String> {
+        let name = text
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("# name:"))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|| default_name.to_string());
+        let puzzles = parse_lines(text)?;
+        if puzzles.is_empty() {
+            return Err("no puzzles".to_string());
+        }
+        Ok(PuzzlePack { name, puzzles })
+    }
+
+    /// 解析 OpenSudoku 的 XML 题库：题库名取 `<name>`（新版备份格式为 `<folder name="…">`），
+    /// 每个 `<game data="…"/>` 是一题，`data` 为 81 个字符的题面。
+    /// 只做够用的解析：不处理 CDATA，同一文件中的多个 folder 合并为一个题库
+    pub fn parse_opensudoku(default_name: &str, text: &str) -> Result<Self, String> {
+        let mut name = None;
+        let mut puzzles = Vec::new();
+        for chunk in text.split('<').skip(1) {
+            let Some((tag, content)) = chunk.split_once('>') else {
+                continue;
+            };
+            let element = tag
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default();
+            match element {
+                "name" if name.is_none() && !content.trim().is_empty() => {
+                    name = Some(unescape(content.trim()));
+                }
+                "folder" if name.is_none() => name = attribute(tag, "name"),
+                "game" => {
+                    let data = attribute(tag, "data")
+                        .ok_or_else(|| format!("game {}: missing data", puzzles.len() + 1))?;
+                    let puzzle = Gameboard::from_line(&data)
+                        .map_err(|e| format!("game {}: {}", puzzles.len() + 1, e))?;
+                    puzzles.push(puzzle);
+                }
+                _ => {}
+            }
+        }
+        if puzzles.is_empty() {
+            return Err("no puzzles".to_string());
+        }
+        Ok(PuzzlePack {
+            name: name.unwrap_or_else(|| default_name.to_string()),
+            puzzles,
+        })
+    }
+
+    /// 读取一个题库文件（`.opensudoku` 按 XML 解析，其它按每行一题）
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let pack = if is_opensudoku(path) {
+            Self::parse_opensudoku(&stem, &text)
+        } else {
+            Self::parse(&stem, &text)
+        };
+        pack.map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// 读取文件或目录（目录下每个 `.txt` 或 `.opensudoku` 文件是一个题库，按文件名排序）；
+    /// 无法读取的文件打印警告后跳过
+    pub fn load_path(path: &Path) -> Vec<Self> {
+        let files = if path.is_dir() {
+            let mut files: Vec<PathBuf> = fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok().map(|e| e.path()))
+                        .filter(|p| {
+                            is_opensudoku(p) || p.extension().is_some_and(|ext| ext == "txt")
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            files.sort();
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+        files
+            .iter()
+            .filter_map(|file| {
+                Self::load(file)
+                    .map_err(|err| eprintln!("忽略题库 {}", err))
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+fn is_opensudoku(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "opensudoku")
+}
+
+/// 取 XML 标签中的属性值（`key="value"` 或 `key='value'`）
+fn attribute(tag: &str, key: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(i) = rest.find('=') {
+        let name = rest[..i].split_whitespace().next_back().unwrap_or_default();
+        let value = rest[i + 1..].trim_start();
+        let quote = value.chars().next().filter(|&q| q == '"' || q == '\'')?;
+        let (value, tail) = value[1..].split_once(quote)?;
+        if name == key {
+            return Some(unescape(value));
+        }
+        rest = tail;
+    }
+    None
+}
+
+/// 还原 XML 的预定义实体（`&amp;` 最后处理，避免二次还原）
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 默认的题库目录（存在的才会读取）
+pub fn default_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("packs")];
+    if let Some(dir) = paths::data_dir() {
+        dirs.push(dir.join("packs"));
+    }
+    dirs
+}
+
+/// 读取默认目录与 `extra` 中的全部题库；同名题库只保留第一个
+pub fn discover(extra: &[PathBuf]) -> Vec<PuzzlePack> {
+    let mut packs: Vec<PuzzlePack> = Vec::new();
+    let dirs = default_dirs().into_iter().filter(|dir| dir.is_dir());
+    for path in dirs.chain(extra.iter().cloned()) {
+        for pack in PuzzlePack::load_path(&path) {
+            if !packs.iter().any(|p| p.name == pack.name) {
+                packs.push(pack);
+            }
+        }
+    }
+    packs
+}
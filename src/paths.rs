@@ -0,0 +1,28 @@
+//! 文件位置：配置放在平台配置目录，存档、回放、历史、统计、二维码图片与崩溃报告放在平台数据目录
+//! （Linux 为 `~/.config/sudoku` 与 `~/.local/share/sudoku`，Windows 在 `AppData` 下，
+//! macOS 在 `~/Library/Application Support` 下），不依赖启动时的运行目录。
+//!
+//! 目录在第一次写入时自动创建（见 `persist::write_atomic`）。无法确定用户目录（例如没有 `HOME`）时，
+//! 配置与历史不读写，存档等玩家主动导出的文件退回运行目录。
+
+use directories::ProjectDirs;
+use std::path::PathBuf;
+
+fn project() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "sudoku")
+}
+
+/// 配置目录（Linux: ~/.config/sudoku）
+pub fn config_dir() -> Option<PathBuf> {
+    project().map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// 数据目录（Linux: ~/.local/share/sudoku）
+pub fn data_dir() -> Option<PathBuf> {
+    project().map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// 数据目录中的文件；无法确定数据目录时为运行目录中的同名文件
+pub fn data_file(name: &str) -> PathBuf {
+    data_dir().map_or_else(|| PathBuf::from(name), |dir| dir.join(name))
+}
@@ -0,0 +1,65 @@
+//! 持久化：配置、存档、回放与设置导出都经这里写入磁盘。
+//!
+//! 先写到同目录下的临时文件并落盘，再重命名覆盖目标文件（同一文件系统内的 rename 是原子的），
+//! 写到一半时崩溃或断电最多留下一个临时文件，原文件保持完整。
+//! 每种文件都带格式版本号：旧版本读取时迁移到当前版本，比程序更新的版本拒绝读取，
+//! 以免旧程序覆盖新格式的数据。对局历史保存在 SQLite 中，由数据库自身的事务保证一致性，
+//! 版本记录在 `PRAGMA user_version`（见 `history.rs`）。
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// 原子地写入文件（自动创建所在目录）
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let tmp = dir.join(format!(".{}.tmp", name.to_string_lossy()));
+    let written = File::create(&tmp).and_then(|mut file| {
+        file.write_all(contents.as_ref())?;
+        file.sync_all()
+    });
+    if let Err(err) = written.and_then(|()| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(err);
+    }
+    // 目录项也落盘，保证重命名本身在断电后仍然有效（部分平台不支持打开目录，忽略错误）
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// 读取首行 `# <kind> vN` 中的版本号；首行不是该格式时为 None
+pub fn header_version(text: &str, kind: &str) -> Option<u32> {
+    text.lines()
+        .next()?
+        .trim()
+        .strip_prefix('#')?
+        .trim()
+        .strip_prefix(kind)?
+        .trim()
+        .strip_prefix('v')?
+        .parse()
+        .ok()
+}
+
+/// 文件版本比程序支持的更新时返回错误
+pub fn check_version(what: &str, found: u32, supported: u32) -> io::Result<()> {
+    if found > supported {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} version {} is newer than supported ({})",
+                what, found, supported
+            ),
+        ));
+    }
+    Ok(())
+}
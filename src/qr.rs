@@ -0,0 +1,472 @@
+//! 二维码：把题目的分享链接编码成 QR 码，在界面上显示或导出为 PNG，手机扫一下就能打开同一道题。
+//!
+//! 只实现这里用得到的部分：字节模式、纠错等级 M、版本 1～10（最多 213 字节，分享链接绰绰有余）。
+//! 算法按 ISO/IEC 18004 的步骤：加纠错码并交织、画定位图形、按蛇形填入数据，
+//! 再从 8 种掩码中选罚分最低的一种。
+
+use crate::paths;
+use crate::persist;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 支持的最高版本（边长 57 个模块）
+const MAX_VERSION: usize = 10;
+
+/// 纠错等级 M 下各版本每块的纠错码字数与块数（下标为版本号）
+const ECC_PER_BLOCK: [usize; MAX_VERSION + 1] = [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+const BLOCKS: [usize; MAX_VERSION + 1] = [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+
+/// 默认的 PNG 导出文件
+const DEFAULT_PNG_FILE: &str = "sudoku-qr.png";
+
+/// 默认的 PNG 导出位置（数据目录中）
+pub fn default_png_path() -> PathBuf {
+    paths::data_file(DEFAULT_PNG_FILE)
+}
+
+/// 导出 PNG 时每个模块的像素数与四周留白的模块数
+const PNG_SCALE: usize = 8;
+const QUIET_ZONE: usize = 4;
+
+/// 编码好的二维码
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrCode {
+    size: usize,
+    /// 按行存放，true 为深色
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// 编码一段文字；超出版本 10 的容量时返回 None
+    pub fn encode(text: &str) -> Option<Self> {
+        let data = text.as_bytes();
+        let version = (1..=MAX_VERSION).find(|&v| {
+            let count_bits = if v < 10 { 8 } else { 16 };
+            4 + count_bits + data.len() * 8 <= data_codewords(v) * 8
+        })?;
+
+        let mut bits = Bits::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, if version < 10 { 8 } else { 16 });
+        for &byte in data {
+            bits.push(u32::from(byte), 8);
+        }
+        let capacity = data_codewords(version) * 8;
+        bits.push(0, (capacity - bits.len).min(4));
+        bits.push(0, (8 - bits.len % 8) % 8);
+        for pad in [0xEC, 0x11].iter().cycle() {
+            if bits.len >= capacity {
+                break;
+            }
+            bits.push(*pad, 8);
+        }
+
+        let mut qr = Matrix::new(version);
+        qr.draw_function_patterns();
+        qr.draw_codewords(&interleave(version, &bits.bytes));
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                let mut trial = qr.clone();
+                trial.apply_mask(mask);
+                trial.draw_format_bits(mask);
+                trial.penalty()
+            })
+            .unwrap_or(0);
+        qr.apply_mask(mask);
+        qr.draw_format_bits(mask);
+        Some(QrCode {
+            size: qr.size,
+            modules: qr.modules,
+        })
+    }
+
+    /// 边长（模块数）
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 第 `y` 行第 `x` 列是否为深色
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// 导出为灰度 PNG（带留白）
+    pub fn save_png(&self, path: &Path) -> io::Result<()> {
+        let side = (self.size + 2 * QUIET_ZONE) * PNG_SCALE;
+        let mut pixels = Vec::with_capacity(side * side);
+        for py in 0..side {
+            for px in 0..side {
+                let module = |p: usize| (p / PNG_SCALE).checked_sub(QUIET_ZONE);
+                let dark = match (module(px), module(py)) {
+                    (Some(x), Some(y)) if x < self.size && y < self.size => self.is_dark(x, y),
+                    _ => false,
+                };
+                pixels.push(if dark { 0 } else { 255 });
+            }
+        }
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, side as u32, side as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(io::Error::other)?;
+        persist::write_atomic(path, png)
+    }
+}
+
+/// 按位追加的缓冲区
+#[derive(Default)]
+struct Bits {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Bits {
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 != 0 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+/// 除定位等功能图形外可放数据的模块数
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let align = version / 7 + 2;
+        result -= (25 * align - 10) * align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+/// 数据码字数（不含纠错码）
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8 - ECC_PER_BLOCK[version] * BLOCKS[version]
+}
+
+/// GF(256) 乘法（本原多项式 0x11D）
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x1D);
+        z ^= ((y >> i) & 1) * x;
+    }
+    z
+}
+
+/// Reed–Solomon 生成多项式（最高次项系数省略）
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(d, factor);
+        }
+    }
+    result
+}
+
+/// 把数据分块、为每块加纠错码，再按列交织成最终的码字序列
+fn interleave(version: usize, data: &[u8]) -> Vec<u8> {
+    let blocks = BLOCKS[version];
+    let ecc_len = ECC_PER_BLOCK[version];
+    let raw = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+    let divisor = rs_divisor(ecc_len);
+
+    let mut split = Vec::with_capacity(blocks);
+    let mut start = 0;
+    for i in 0..blocks {
+        let len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let mut block = data[start..start + len].to_vec();
+        start += len;
+        let ecc = rs_remainder(&block, &divisor);
+        // 短块补一个占位字节，交织时跳过
+        if i < short_blocks {
+            block.push(0);
+        }
+        block.extend(ecc);
+        split.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw);
+    for i in 0..split[0].len() {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_len - ecc_len || j >= short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+/// 编码过程中的模块矩阵；`function` 标出定位图形等不放数据的模块
+#[derive(Clone)]
+struct Matrix {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl Matrix {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Matrix {
+            version,
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            self.draw_finder(x, y);
+        }
+        let positions = self.alignment_positions();
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // 与三个定位图形重叠的位置不画
+                let corner = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                if !corner {
+                    self.draw_alignment(x, y);
+                }
+            }
+        }
+        // 先占住格式信息的位置，选好掩码后再写入
+        self.draw_format_bits(0);
+        self.draw_version();
+    }
+
+    /// 7×7 的定位图形连同外圈的分隔带，`(x, y)` 为中心
+    fn draw_finder(&mut self, x: usize, y: usize) {
+        for dy in -4..=4isize {
+            for dx in -4..=4isize {
+                let (xx, yy) = (x as isize + dx, y as isize + dy);
+                if (0..self.size as isize).contains(&xx) && (0..self.size as isize).contains(&yy) {
+                    let dist = dx.abs().max(dy.abs());
+                    self.set_function(xx as usize, yy as usize, dist != 2 && dist != 4);
+                }
+            }
+        }
+    }
+
+    /// 5×5 的校正图形，`(x, y)` 为中心
+    fn draw_alignment(&mut self, x: usize, y: usize) {
+        for dy in -2..=2isize {
+            for dx in -2..=2isize {
+                let dark = dx.abs().max(dy.abs()) != 1;
+                self.set_function((x as isize + dx) as usize, (y as isize + dy) as usize, dark);
+            }
+        }
+    }
+
+    /// 校正图形中心所在的行 / 列，从小到大
+    fn alignment_positions(&self) -> Vec<usize> {
+        if self.version == 1 {
+            return Vec::new();
+        }
+        let count = self.version / 7 + 2;
+        let step = (self.version * 4 + count * 2 + 1) / (count * 2 - 2) * 2;
+        let mut result: Vec<usize> = (0..count - 1).map(|i| self.size - 7 - i * step).collect();
+        result.push(6);
+        result.reverse();
+        result
+    }
+
+    /// 纠错等级 M 与掩码编号的格式信息（BCH 编码后两份）
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = mask; // 等级 M 的两位为 00
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        let size = self.size;
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// 版本 7 以上的版本信息
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let mut rem = self.version as u32;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = (self.version as u32) << 12 | rem;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let (a, b) = (self.size - 11 + i % 3, i / 3);
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// 从右下角起每两列一组上下蛇形填入数据位，跳过功能模块
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            // 第 6 列是定时图形，整组左移一列
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                let y = if upward { size - 1 - vert } else { vert };
+                for x in [right, right - 1] {
+                    if !self.function[y * size + x] && i < data.len() * 8 {
+                        self.modules[y * size + x] = (data[i / 8] >> (7 - i % 8)) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.function[y * self.size + x] {
+                    self.modules[y * self.size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    /// 掩码罚分：同色长串、2×2 同色块、类似定位图形的序列与深浅比例
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let mut result = 0;
+        for horizontal in [true, false] {
+            let at = |line: usize, i: usize| {
+                if horizontal {
+                    self.get(i, line)
+                } else {
+                    self.get(line, i)
+                }
+            };
+            for line in 0..size {
+                let mut run = 1;
+                for i in 1..size {
+                    if at(line, i) == at(line, i - 1) {
+                        run += 1;
+                        if run == 5 {
+                            result += 3;
+                        } else if run > 5 {
+                            result += 1;
+                        }
+                    } else {
+                        run = 1;
+                    }
+                }
+                // 1:1:3:1:1 的深浅序列，一侧带 4 个浅色模块
+                const FINDER: [bool; 7] = [true, false, true, true, true, false, true];
+                for i in 0..size.saturating_sub(6) {
+                    if (0..7).any(|k| at(line, i + k) != FINDER[k]) {
+                        continue;
+                    }
+                    let light = |from: usize, to: usize| (from..to).all(|k| !at(line, k));
+                    if (i >= 4 && light(i - 4, i)) || (i + 11 <= size && light(i + 7, i + 11)) {
+                        result += 40;
+                    }
+                }
+            }
+        }
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.get(x, y);
+                if dark == self.get(x + 1, y)
+                    && dark == self.get(x, y + 1)
+                    && dark == self.get(x + 1, y + 1)
+                {
+                    result += 3;
+                }
+            }
+        }
+        let total = size * size;
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let k = (dark * 20).abs_diff(total * 10).div_ceil(total);
+        result + k.saturating_sub(1) * 10
+    }
+}
@@ -0,0 +1,157 @@
+//! 回放文件（`.sdreplay`）：初始题面加上带时间戳的盘面变化，可在另一台机器上重放。
+//!
+//! 纯文本格式，版本号写在首行，之后每行 `key value`：
+//! ```text
+//! # sudoku replay v1
+//! puzzle 530070000600195000...
+//! elapsed 312.5
+//! set 4.20 2 0 4
+//! set 9.85 5 1 0
+//! ```
+//! `set 时间 x y 值` 表示在该时间把第 x 列第 y 行改为该值（0 为清空）。
+
+use crate::gameboard_controller::{GameEvent, GameboardController};
+use crate::paths;
+use crate::persist;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use sudoku_core::Gameboard;
+
+/// 默认回放文件名（结算界面 E 导出 / I 导入）
+const DEFAULT_REPLAY_FILE: &str = "sudoku.sdreplay";
+
+/// 默认回放文件位置（数据目录中）
+pub fn default_path() -> PathBuf {
+    paths::data_file(DEFAULT_REPLAY_FILE)
+}
+
+/// 当前回放格式版本
+const VERSION: u32 = 1;
+
+/// 一局的回放
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub puzzle: Gameboard,
+    /// 总用时（秒）
+    pub elapsed: f64,
+    pub events: Vec<GameEvent>,
+}
+
+impl Replay {
+    pub fn from_controller(controller: &GameboardController) -> Self {
+        Replay {
+            puzzle: Gameboard::from_cells(controller.initial_cells),
+            elapsed: controller.elapsed,
+            events: controller.events.iter().collect(),
+        }
+    }
+
+    /// 时间 `time` 时的盘面
+    pub fn board_at(&self, time: f64) -> Gameboard {
+        let mut board = self.puzzle.clone();
+        for event in self.events.iter().take_while(|e| e.time <= time) {
+            board.set([event.x, event.y], event.value);
+        }
+        board
+    }
+
+    /// 回放总时长：总用时与最后一次变化中较晚的那个
+    pub fn duration(&self) -> f64 {
+        self.events
+            .iter()
+            .map(|e| e.time)
+            .fold(self.elapsed, f64::max)
+    }
+
+    /// `time` 之后下一次变化的时间（没有时为结尾）
+    pub fn next_time(&self, time: f64) -> f64 {
+        self.events
+            .iter()
+            .map(|e| e.time)
+            .find(|&t| t > time)
+            .unwrap_or_else(|| self.duration())
+    }
+
+    /// `time` 之前上一次变化的时间（没有时为开头）
+    pub fn prev_time(&self, time: f64) -> f64 {
+        self.events
+            .iter()
+            .rev()
+            .map(|e| e.time)
+            .find(|&t| t < time)
+            .unwrap_or(0.0)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "# sudoku replay v{}\npuzzle {}\nelapsed {}\n",
+            VERSION,
+            self.puzzle.to_line(),
+            self.elapsed
+        );
+        for e in &self.events {
+            text.push_str(&format!("set {:.2} {} {} {}\n", e.time, e.x, e.y, e.value));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let version = persist::header_version(text, "sudoku replay")
+            .ok_or_else(|| invalid("not a sudoku replay file".to_string()))?;
+        persist::check_version("replay", version, VERSION)?;
+        let lines = text.lines().skip(1).map(str::trim);
+        let mut puzzle = None;
+        let mut elapsed = 0.0;
+        let mut events = Vec::new();
+        for (i, line) in lines.enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "puzzle" => {
+                    let board = Gameboard::from_line(value)
+                        .map_err(|e| invalid(format!("puzzle: {}", e)))?;
+                    puzzle = Some(board);
+                }
+                "elapsed" => elapsed = value.trim().parse().unwrap_or(0.0),
+                "set" => {
+                    let event = parse_event(value)
+                        .ok_or_else(|| invalid(format!("line {}: bad event", i + 2)))?;
+                    events.push(event);
+                }
+                _ => {}
+            }
+        }
+        let puzzle = puzzle.ok_or_else(|| invalid("replay is missing `puzzle`".to_string()))?;
+        Ok(Replay {
+            puzzle,
+            elapsed,
+            events,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        persist::write_atomic(path, self.to_text())
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+fn parse_event(value: &str) -> Option<GameEvent> {
+    let mut parts = value.split_whitespace();
+    let event = GameEvent {
+        time: parts.next()?.parse().ok()?,
+        x: parts.next()?.parse().ok()?,
+        y: parts.next()?.parse().ok()?,
+        value: parts.next()?.parse().ok()?,
+    };
+    (event.x < 9 && event.y < 9 && event.value <= 9).then_some(event)
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,122 @@
+//! 存档文件：保存 / 读取当前对局（初始题面 + 当前盘面）。
+//!
+//! 纯文本格式，每行 `key value`：
+//! ```text
+//! # sudoku save v1
+//! initial 530070000600195000...
+//! current 534678000600195000...
+//! elapsed 312.5
+//! mistakes 2
+//! sandwich 0,6,0,15,0,10,30,26,3/0,6,0,35,16,20,0,7,8
+//! ```
+//!
+//! `sandwich` 一行只在变体数独的存档中出现：其后是先九行、再九列的外侧提示，
+//! 温度计数独则为 `thermo 65,66,56;27,17,18;...`（每支温度计从底部到顶端的格子，行列从 1 开始）。
+//! 单词数独的存档另有 `letters TWCSHDGAO`：数字 1 – 9 依次代表的字母。
+//!
+//! 没有版本行的旧存档按 v0 读取（字段与 v1 相同）。
+
+use crate::gameboard_controller::GameboardController;
+use crate::paths;
+use crate::persist;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use sudoku_core::wordoku;
+use sudoku_core::{Gameboard, Variant, VariantKind};
+
+/// 默认存档文件名（S 键保存）
+const DEFAULT_SAVE_FILE: &str = "sudoku.sav";
+
+/// 当前存档格式版本
+const VERSION: u32 = 1;
+
+/// 默认存档位置（数据目录中）
+pub fn default_path() -> PathBuf {
+    paths::data_file(DEFAULT_SAVE_FILE)
+}
+
+/// 将当前对局写入存档
+pub fn save(controller: &GameboardController, path: &Path) -> io::Result<()> {
+    persist::write_atomic(path, contents(controller))
+}
+
+/// 存档文件的内容
+pub fn contents(controller: &GameboardController) -> String {
+    let initial = Gameboard::from_cells(controller.initial_cells);
+    let mut contents = format!(
+        "# sudoku save v{}\ninitial {}\ncurrent {}\nelapsed {}\nmistakes {}\n",
+        VERSION,
+        initial.to_line(),
+        controller.gameboard.to_line(),
+        controller.elapsed,
+        controller.mistakes
+    );
+    if let Some(variant) = &controller.variant {
+        contents.push_str(&format!("{}\n", variant));
+    }
+    if let Some(letters) = controller.gameboard.letters {
+        contents.push_str(&format!("letters {}\n", letters.iter().collect::<String>()));
+    }
+    contents
+}
+
+/// 读取存档并恢复为 controller
+pub fn load(path: &Path) -> io::Result<GameboardController> {
+    let contents = fs::read_to_string(path)?;
+    let version = persist::header_version(&contents, "sudoku save").unwrap_or(0);
+    persist::check_version("save file", version, VERSION)?;
+    let mut initial = None;
+    let mut current = None;
+    let mut elapsed = 0.0;
+    let mut mistakes = 0;
+    let mut variant = None;
+    let mut letters = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let board = || {
+            Gameboard::from_line(value)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", key, e)))
+        };
+        match key {
+            "initial" => initial = Some(board()?),
+            "current" => current = Some(board()?),
+            "elapsed" => elapsed = value.trim().parse().unwrap_or(0.0),
+            "mistakes" => mistakes = value.trim().parse().unwrap_or(0),
+            "letters" => {
+                let word = wordoku::parse_word(value).map_err(|e| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("letters: {}", e))
+                })?;
+                letters = Some(word);
+            }
+            _ if key.parse::<VariantKind>().is_ok() => {
+                let clues = line.parse::<Variant>().map_err(|e| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", key, e))
+                })?;
+                variant = Some(clues);
+            }
+            _ => {}
+        }
+    }
+    let mut initial = initial.ok_or_else(|| missing("initial"))?;
+    initial.letters = letters;
+    let current = current.unwrap_or_else(|| initial.clone());
+    let mut controller = GameboardController::from_saved(initial, current);
+    controller.elapsed = elapsed;
+    controller.mistakes = mistakes;
+    if let Some(variant) = variant {
+        controller.set_variant(variant);
+    }
+    Ok(controller)
+}
+
+fn missing(key: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("save file is missing `{}`", key),
+    )
+}
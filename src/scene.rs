@@ -0,0 +1,3316 @@
+//! 场景状态机：主菜单、游戏中、暂停、结算、题目编辑器、统计、回放、排行榜。
+//!
+//! 每个场景各自处理输入与绘制；`App` 持有共享状态（棋盘、视图、配置）并负责场景切换。
+//! 新增界面只需增加一个 `Scene` 分支，不必改动 `GameboardController`。
+
+use crate::about;
+use crate::accessibility::{self, Accessibility};
+use crate::action::{self, Action};
+use crate::analysis::Analysis;
+use crate::audio::Audio;
+use crate::card::{self, Card};
+use crate::config::{AutoPause, Config};
+use crate::daily::{self, Date, Period};
+use crate::drills::{self, Trainer};
+use crate::explain::Explanation;
+use crate::gameboard_controller::{
+    format_time, CellSet, GameboardController, InputAction, Unit, Wheel,
+};
+use crate::gameboard_view::{GameboardView, MAX_SCALE, MIN_SCALE};
+use crate::gamepad::{Gamepad, Pad};
+use crate::history::{GameRecord, History};
+use crate::leaderboard::{self, Leaderboard};
+use crate::net::{self, RoomClient};
+#[cfg(feature = "ocr")]
+use crate::ocr;
+use crate::pack::PuzzlePack;
+use crate::qr::{self, QrCode};
+use crate::replay::{self, Replay};
+use crate::savefile;
+use crate::screen_reader::{Request, ScreenReader};
+#[cfg(feature = "scripting")]
+use crate::scripting::{self, Move, Output, Script};
+use crate::share::{self, Clipboard};
+use crate::telemetry::Telemetry;
+use crate::text::{self, Align};
+use crate::tutorial::{self, Tutorial};
+use crate::update::UpdateCheck;
+use graphics::character::CharacterCache;
+use graphics::{Context, Graphics};
+#[cfg(feature = "ocr")]
+use piston::input::FileDrag;
+use piston::input::{Button, GenericEvent, Key, MouseButton};
+#[cfg(feature = "ocr")]
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use sudoku_core::{rate_with, Difficulty, Drill, Gameboard, Technique, DRILL_BUDGET};
+
+/// Ctrl+= / Ctrl+- 每次调整的界面缩放
+const SCALE_STEP: f64 = 0.25;
+
+/// 面板标题区与每行文字的高度（缩放前）
+const PANEL_HEADER: f64 = 72.0;
+const PANEL_LINE: f64 = 34.0;
+
+/// 快捷键一览最后一行：不在动作注册表中的基本操作
+const HELP_FOOTER: &str =
+    "1 - 9: enter digit   Arrows: move   0 / Backspace: clear   Any key: close";
+
+/// 脚本结果面板最多列出的走法数
+#[cfg(feature = "scripting")]
+const PROPOSAL_LINES: usize = 8;
+
+/// 主菜单条目
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MenuItem {
+    Continue,
+    NewGame,
+    Difficulty,
+    Daily,
+    Tutorial,
+    Drills,
+    Music,
+    Editor,
+    Stats,
+    History,
+    Packs,
+    About,
+    Quit,
+}
+
+/// 当前场景
+pub enum Scene {
+    /// 主菜单；`selected` 为高亮条目
+    Menu {
+        selected: usize,
+    },
+    Playing,
+    /// 暂停：遮住棋盘，计时停止
+    Paused,
+    /// 解题完成后的结算界面；`message` 为导出 / 打开回放的结果
+    Results {
+        message: String,
+    },
+    /// 题目编辑器：在空棋盘上录入自定义题面；`checking` 为后台进行中的唯一解检查
+    Editor {
+        board: Box<GameboardController>,
+        message: String,
+        checking: Option<Receiver<(Gameboard, usize)>>,
+    },
+    /// 关于：版本、构建信息与第三方库；`licenses` 时改为显示字体许可证全文。
+    /// `first` 为窗口放不下时滚动到的第一行
+    About {
+        first: usize,
+        licenses: bool,
+    },
+    /// 统计：本次运行与历史记录（进入时生成文字）
+    Stats {
+        lines: Vec<String>,
+    },
+    /// 使用统计：汇总与文件位置，C 复制统计文件的内容；`message` 为复制的结果
+    Telemetry {
+        message: String,
+    },
+    /// 技巧练习：选择技巧，Enter 在后台生成练习局面；`loading` 为生成中的局面
+    Drills {
+        selected: usize,
+        loading: Option<Receiver<Option<Drill>>>,
+        message: String,
+    },
+    /// 赛后分析（解出或放弃后）；`solved` 决定 Esc 回到结算界面还是棋盘
+    Analysis {
+        lines: Vec<String>,
+        solved: bool,
+    },
+    /// 每日题日历：标出某个月中完成的每日 / 每周题，← / → 切换月份；`done` 为已完成各期的第一天
+    Calendar {
+        year: i64,
+        month: u32,
+        done: Vec<Date>,
+    },
+    /// 本机最佳成绩：某个难度用时最短的前十局，← / → 切换难度
+    BestTimes {
+        difficulty: Difficulty,
+        games: Vec<GameRecord>,
+    },
+    /// 历史对局列表：按难度 / 结果筛选，Enter 重玩选中的题目
+    History {
+        games: Vec<GameRecord>,
+        difficulty: Option<Difficulty>,
+        solved: Option<bool>,
+        selected: usize,
+    },
+    /// 题库列表；`solved` 为各题库已解出的题数
+    Packs {
+        selected: usize,
+        solved: Vec<usize>,
+    },
+    /// 命令面板（Ctrl+P）：输入文字模糊筛选动作，Enter 执行
+    Palette {
+        query: String,
+        selected: usize,
+    },
+    /// 快捷键一览（F1）：由动作注册表与配置 `[keys]` 生成，按任意键或点击关闭
+    Help,
+    /// 复制题目（Ctrl+C）：选择格式，Enter 复制到剪贴板；`selected` 为 `share::choices` 中的位置
+    Export {
+        selected: usize,
+    },
+    /// 确认清空多选的格子或选中格所在的行 / 列 / 宫：`selected` 为 `clear_region_options` 中的位置
+    ClearRegion {
+        selected: usize,
+    },
+    /// 当前题目分享链接的二维码，S 导出为 PNG；`message` 为导出结果
+    Qr {
+        code: QrCode,
+        message: String,
+    },
+    /// 脚本列表（`scripting` feature）：Enter 对当前盘面运行选中的脚本
+    #[cfg(feature = "scripting")]
+    Scripts {
+        scripts: Vec<Script>,
+        selected: usize,
+    },
+    /// 脚本的结果：提出的走法（Enter 全部写入）与说明文字或错误信息
+    #[cfg(feature = "scripting")]
+    Proposal {
+        name: String,
+        moves: Vec<Move>,
+        message: String,
+    },
+    /// 回放：按时间重演一局的盘面变化；`speed` 为播放倍速
+    Replay {
+        replay: Replay,
+        /// 当前时刻的盘面（打开回放时建立，题目只求解一次）
+        board: Box<GameboardController>,
+        time: f64,
+        paused: bool,
+        speed: f64,
+    },
+    /// 每日题排行榜（数据在 `App::leaderboard` 中，后台加载）
+    Leaderboard,
+    /// 退出前的本次游戏小结（数据在 `App::stats` 中），按任意键退出
+    Summary,
+}
+
+/// 本次运行的统计数据
+#[derive(Clone, Debug, Default)]
+pub struct SessionStats {
+    pub started: u32,
+    pub solved: u32,
+    pub best_time: Option<f64>,
+    /// 计时走动的总秒数（暂停、空闲与菜单中的时间不算）
+    pub play_time: f64,
+}
+
+impl SessionStats {
+    /// 退出时显示并打印到终端的小结
+    pub fn summary(&self) -> Vec<String> {
+        vec![
+            format!("Puzzles attempted: {}", self.started),
+            format!("Completed: {}", self.solved),
+            format!("Play time: {}", format_time(self.play_time)),
+            format!(
+                "Best time: {}",
+                self.best_time.map_or("-".to_string(), format_time)
+            ),
+        ]
+    }
+}
+
+/// 应用状态：当前场景加上各场景共享的数据
+pub struct App {
+    pub scene: Scene,
+    /// 同时打开的多道题（标签页），各自有独立的计时与撤销历史
+    pub tabs: Vec<GameboardController>,
+    /// 当前标签页
+    pub active: usize,
+    pub view: GameboardView,
+    pub config: Config,
+    pub stats: SessionStats,
+    /// 对局历史数据库（无法打开时为 None，不影响游戏）
+    pub history: Option<History>,
+    /// 本机使用统计（配置中开启时）
+    telemetry: Option<Telemetry>,
+    /// 启动时读取的题库
+    pub packs: Vec<PuzzlePack>,
+    /// 加入的局域网联机房间（`--host` / `--join`）
+    pub room: Option<RoomClient>,
+    /// 最近打开的每日题排行榜
+    leaderboard: Option<Leaderboard>,
+    /// 进行中的新手教程（只在教程题目的标签页中显示）
+    tutorial: Option<Tutorial>,
+    /// 进行中的技巧练习（只在练习局面的标签页中显示）
+    trainer: Option<Trainer>,
+    /// 刚提交的一局进入本机最佳成绩前十时的 (难度, 名次)，在结算界面显示
+    best: Option<(Difficulty, usize)>,
+    /// 最近一次解出时生成的成绩卡片的结果（保存位置或错误），在结算界面显示
+    card: Option<String>,
+    /// 成绩卡片的一行文字，在结算界面按 C 复制到剪贴板（剪贴板只支持文字）
+    card_text: Option<String>,
+    /// 侧栏中的选中格解释（跟随选中格，再按一次关闭）
+    explanation: Option<Explanation>,
+    /// 侧栏中显示求解器统计（调试用）
+    diagnostics: bool,
+    /// 侧栏中显示冲突列表；`conflicts_collapsed` 时只显示标题行（点击标题切换）
+    conflicts: bool,
+    conflicts_collapsed: bool,
+    /// 上一次的无障碍树（开启读屏输出时用于找出变化）
+    a11y: Accessibility,
+    /// 与系统读屏软件的连接（窗口创建后由 main 接入）
+    pub screen_reader: ScreenReader,
+    /// 系统剪贴板（复制 / 粘贴分享链接）
+    clipboard: Clipboard,
+    /// 背景音乐
+    audio: Audio,
+    /// 窗口是否在前台（不在前台时暂停音乐）
+    focused: bool,
+    /// 当前的暂停是失去焦点时自动进入的（回到窗口时自动继续）
+    auto_paused: bool,
+    /// 距上次输入的秒数（空闲检测用）
+    idle_for: f64,
+    /// 当前标签页是尚未开始的新题（菜单选 New Game 时直接使用，不再重新生成）
+    fresh: bool,
+    /// 手柄状态（数字盘上的数字、摇杆是否推出）
+    gamepad: Gamepad,
+    /// 启动时的检查更新（后台进行中）
+    update_check: Option<UpdateCheck>,
+    /// 窗口底部的提示（如有新版本），点击或按 Esc 关闭
+    toast: Option<String>,
+    /// 按住的修饰键
+    ctrl: bool,
+    shift: bool,
+    /// 鼠标位置（切换标签页时同步给新的 controller）
+    cursor: [f64; 2],
+    /// 画面自上次绘制以来是否有变化
+    dirty: bool,
+}
+
+impl App {
+    /// `playing` 为 true 时直接进入游戏（指定了题目来源），否则从主菜单开始
+    pub fn new(
+        controller: GameboardController,
+        view: GameboardView,
+        config: Config,
+        history: Option<History>,
+        packs: Vec<PuzzlePack>,
+        playing: bool,
+    ) -> Self {
+        let mut stats = SessionStats::default();
+        let scene = if playing {
+            stats.started = 1;
+            Scene::Playing
+        } else {
+            Scene::Menu { selected: 0 }
+        };
+        let mut audio = Audio::default();
+        audio.apply(&config.audio);
+        let update_check = UpdateCheck::start(&config.online);
+        let telemetry = Telemetry::open(config.telemetry);
+        App {
+            scene,
+            tabs: vec![controller],
+            active: 0,
+            view,
+            config,
+            stats,
+            history,
+            telemetry,
+            packs,
+            room: None,
+            leaderboard: None,
+            tutorial: None,
+            trainer: None,
+            best: None,
+            card: None,
+            card_text: None,
+            explanation: None,
+            diagnostics: false,
+            conflicts: false,
+            conflicts_collapsed: false,
+            a11y: Accessibility::default(),
+            screen_reader: ScreenReader::default(),
+            clipboard: Clipboard::default(),
+            audio,
+            focused: true,
+            auto_paused: false,
+            idle_for: 0.0,
+            fresh: !playing,
+            gamepad: Gamepad::default(),
+            update_check,
+            toast: None,
+            ctrl: false,
+            shift: false,
+            cursor: [0.0; 2],
+            dirty: true,
+        }
+    }
+
+    /// 是否有可继续的对局（菜单中显示 Continue）
+    fn has_game(&self) -> bool {
+        !self.fresh && self.tabs.iter().any(|tab| !tab.is_solved())
+    }
+
+    /// 计时器是否在走（仅游戏中）；联机时也需要持续 update 以接收其他玩家的消息
+    pub fn timer_running(&self) -> bool {
+        self.room.is_some()
+            || matches!(self.scene, Scene::Playing) && self.tabs[self.active].timer_running()
+    }
+
+    /// 是否有需要连续刷新的动画（包括播放中的回放），或在等待后台任务
+    pub fn is_animating(&self) -> bool {
+        if self.update_check.is_some() {
+            return true;
+        }
+        match self.scene {
+            Scene::Playing => self.tabs[self.active].is_animating(),
+            Scene::Replay { paused, .. } => !paused,
+            Scene::Editor {
+                checking: Some(_), ..
+            }
+            | Scene::Drills {
+                loading: Some(_), ..
+            } => true,
+            Scene::Leaderboard => self
+                .leaderboard
+                .as_ref()
+                .is_some_and(Leaderboard::is_loading),
+            _ => false,
+        }
+    }
+
+    /// 窗口失去 / 回到焦点时按配置自动暂停计时（`blank` 还会进入暂停界面遮住棋盘），回来时自动继续；
+    /// 手动暂停的游戏回到窗口时仍保持暂停
+    fn focus_changed(&mut self, focused: bool) {
+        self.sync_clock();
+        if !focused {
+            if self.config.auto_pause == AutoPause::Blank
+                && matches!(self.scene, Scene::Playing)
+                && self.tabs[self.active].timer_running()
+            {
+                self.scene = Scene::Paused;
+                self.auto_paused = true;
+            }
+        } else if self.auto_paused {
+            self.auto_paused = false;
+            if matches!(self.scene, Scene::Paused) {
+                self.scene = Scene::Playing;
+            }
+        }
+    }
+
+    /// 是否已超过 `idle_timeout` 没有输入
+    fn is_idle(&self) -> bool {
+        self.config.idle_timeout > 0.0 && self.idle_for >= self.config.idle_timeout
+    }
+
+    /// 按窗口焦点（`auto_pause = "timer"`）与空闲状态暂停或继续各标签页的计时
+    fn sync_clock(&mut self) {
+        let clock_paused = !self.focused && self.config.auto_pause == AutoPause::Timer;
+        let idle = self.is_idle();
+        for tab in &mut self.tabs {
+            tab.clock_paused = clock_paused;
+            tab.idle = idle;
+        }
+    }
+
+    /// 处理 update 事件；暂停及其它场景下计时不前进
+    pub fn update(&mut self, dt: f64) {
+        let (was_animating, clock) = (self.is_animating(), self.clock());
+        let was_idle = self.is_idle();
+        self.idle_for += dt;
+        if self.is_idle() != was_idle {
+            self.sync_clock();
+            self.dirty = true;
+        }
+        self.update_room();
+        if let Some(leaderboard) = &mut self.leaderboard {
+            leaderboard.poll();
+        }
+        if let Some(done) = self.update_check.as_ref().and_then(UpdateCheck::poll) {
+            self.update_check = None;
+            self.toast = done.map(|version| format!("Sudoku {} is available", version));
+        }
+        match &mut self.scene {
+            Scene::Playing => {
+                let tab = &mut self.tabs[self.active];
+                let (was_solved, was_submitted) = (tab.is_solved(), tab.submitted);
+                let before = tab.elapsed;
+                tab.update(dt);
+                self.stats.play_time += (tab.elapsed - before).max(0.0);
+                self.check_finished(self.active, was_solved, was_submitted);
+                self.advance_tutorial();
+                self.check_drill();
+            }
+            Scene::Drills {
+                selected,
+                loading: loading @ Some(_),
+                message,
+            } => match loading.as_ref().map(Receiver::try_recv) {
+                Some(Ok(Some(drill))) => self.start_drill(drill),
+                Some(Ok(None) | Err(TryRecvError::Disconnected)) => {
+                    *loading = None;
+                    *message = format!(
+                        "No {} drill found in time, try again",
+                        drills::TECHNIQUES[*selected]
+                    );
+                }
+                _ => {}
+            },
+            Scene::Replay {
+                replay,
+                time,
+                paused: paused @ false,
+                speed,
+                ..
+            } => {
+                let end = replay.duration();
+                *time = (*time + dt * *speed).min(end);
+                *paused = *time >= end;
+                self.sync_replay_board();
+            }
+            Scene::Editor {
+                message, checking, ..
+            } => {
+                if let Some(Ok((puzzle, count))) = checking.as_ref().map(Receiver::try_recv) {
+                    *checking = None;
+                    match count {
+                        0 => *message = "No solution".to_string(),
+                        1 => self.open_tab(GameboardController::new(puzzle)),
+                        _ => *message = "Puzzle has more than one solution".to_string(),
+                    }
+                }
+            }
+            _ => {}
+        }
+        // 动画、后台任务（开始或结束的这一次也算）、联机进度和计时器的秒数变化时重绘
+        self.dirty |=
+            was_animating || self.is_animating() || self.room.is_some() || self.clock() != clock;
+    }
+
+    /// 当前标签页计时器显示的秒数
+    fn clock(&self) -> u64 {
+        self.tabs[self.active].elapsed as u64
+    }
+
+    /// 自上次调用以来画面是否需要重绘；没有变化时跳过绘制，空闲时不占用 GPU
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::replace(&mut self.dirty, false)
+    }
+
+    /// 联机题目所在的标签页
+    fn room_tab(&self) -> Option<usize> {
+        let room = self.room.as_ref()?;
+        self.tabs
+            .iter()
+            .position(|tab| tab.initial_cells == room.puzzle.cells)
+    }
+
+    /// 接收房间消息；竞速时报告自己的进度（已填空格的百分比），
+    /// 合作时把本地的新事件与光标发给主机、把其他玩家的修改应用到盘面
+    fn update_room(&mut self) {
+        let index = self.room_tab();
+        let Some(room) = &mut self.room else {
+            return;
+        };
+        room.poll();
+        let tabs = &mut self.tabs;
+        let Some(tab) = index.and_then(|i| tabs.get_mut(i)) else {
+            return;
+        };
+        if room.coop {
+            for event in tab.events.iter_from(room.sent_events) {
+                room.send_set([event.x, event.y], event.value);
+            }
+            room.send_cursor(tab.selected_cell);
+            for (cell, value) in room.take_remote() {
+                tab.apply_remote(cell, value);
+            }
+            room.sent_events = tab.events.len();
+        } else {
+            let givens = tab
+                .initial_cells
+                .iter()
+                .flatten()
+                .filter(|&&v| v != 0)
+                .count();
+            let blanks = (81 - givens).max(1);
+            room.send_progress(((tab.filled_count() - givens) * 100 / blanks) as u8);
+        }
+    }
+
+    /// 从头播放回放
+    pub fn open_replay(&mut self, replay: Replay) {
+        let board = GameboardController::new(replay.puzzle.clone());
+        self.scene = Scene::Replay {
+            replay,
+            board: Box::new(board),
+            time: 0.0,
+            paused: false,
+            speed: 1.0,
+        };
+        self.sync_replay_board();
+    }
+
+    /// 回放：空格播放 / 暂停，← / → 跳到上一次 / 下一次变化，+ / - 调整倍速
+    fn replay_event(&mut self, key: Option<Key>) {
+        let Scene::Replay {
+            replay,
+            time,
+            paused,
+            speed,
+            ..
+        } = &mut self.scene
+        else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.open_menu(),
+            Some(Key::Space) => {
+                // 播放完后再按空格从头开始
+                if *paused && *time >= replay.duration() {
+                    *time = 0.0;
+                }
+                *paused = !*paused;
+            }
+            Some(Key::Left) => {
+                *time = replay.prev_time(*time);
+                *paused = true;
+            }
+            Some(Key::Right) => {
+                *time = replay.next_time(*time);
+                *paused = true;
+            }
+            Some(Key::Home) => *time = 0.0,
+            Some(Key::Equals | Key::Plus | Key::NumPadPlus) => *speed = (*speed * 2.0).min(16.0),
+            Some(Key::Minus | Key::NumPadMinus) => *speed = (*speed / 2.0).max(0.25),
+            _ => {}
+        }
+        self.sync_replay_board();
+    }
+
+    /// 把回放的盘面更新到当前时刻
+    fn sync_replay_board(&mut self) {
+        if let Scene::Replay {
+            replay,
+            board,
+            time,
+            ..
+        } = &mut self.scene
+        {
+            board.set_board(&replay.board_at(*time));
+        }
+    }
+
+    /// 结算界面：E 导出当前对局的回放，I 读取默认回放文件并播放
+    fn results_event(&mut self, key: Option<Key>) {
+        let path = replay::default_path();
+        match key {
+            Some(Key::Return) => self.new_game(),
+            Some(k) if k == self.config.keys.new_game => self.new_game(),
+            Some(Key::Escape) => self.open_menu(),
+            // 导出 / 打开回放的结果显示在结算界面中
+            Some(Key::E) => {
+                let message = match Replay::from_controller(&self.tabs[self.active]).save(&path) {
+                    Ok(()) => format!("Replay saved to {}", path.display()),
+                    Err(err) => format!("Could not save the replay: {}", err),
+                };
+                self.scene = Scene::Results { message };
+            }
+            Some(Key::I) => match Replay::load(&path) {
+                Ok(replay) => self.open_replay(replay),
+                Err(err) => {
+                    self.scene = Scene::Results {
+                        message: format!("Could not open {}: {}", path.display(), err),
+                    }
+                }
+            },
+            Some(Key::A) => self.open_analysis(),
+            Some(Key::Q) => self.open_qr(),
+            Some(Key::C) => self.copy_card(),
+            Some(Key::L) if self.tabs[self.active].daily.is_some() => {
+                self.open_leaderboard(None);
+                self.scene = Scene::Leaderboard;
+            }
+            _ => {}
+        }
+    }
+
+    /// 加载当前标签页（每日题）所在一期的排行榜；`time` 为刚解出的用时，允许时一并提交。
+    /// 不提交时已加载的同一期排行榜直接沿用
+    fn open_leaderboard(&mut self, time: Option<f64>) {
+        let tab = &self.tabs[self.active];
+        let Some(key) = tab.daily.clone() else {
+            return;
+        };
+        if time.is_none() && self.leaderboard.as_ref().is_some_and(|b| b.key == key) {
+            return;
+        }
+        self.leaderboard = Some(Leaderboard::load(
+            &self.config.online,
+            key,
+            &Gameboard::from_cells(tab.initial_cells),
+            time,
+            self.history.as_ref(),
+        ));
+    }
+
+    /// 处理输入事件；返回 false 表示退出程序
+    pub fn event<E: GenericEvent>(&mut self, e: &E) -> bool {
+        // 输入事件（按键、鼠标、窗口大小与焦点）都可能改变画面；循环事件由 `update` 判断
+        let looping = e.update_args().is_some()
+            || e.render_args().is_some()
+            || e.after_render_args().is_some()
+            || e.idle_args().is_some();
+        if !looping {
+            self.dirty = true;
+            let was_idle = self.is_idle();
+            self.idle_for = 0.0;
+            if was_idle {
+                self.sync_clock();
+            }
+        }
+        if let Some(p) = e.mouse_cursor_args() {
+            self.cursor = p;
+        }
+        let held = match (e.press_args(), e.release_args()) {
+            (Some(Button::Keyboard(k)), _) => Some((k, true)),
+            (_, Some(Button::Keyboard(k))) => Some((k, false)),
+            _ => None,
+        };
+        match held {
+            Some((Key::LCtrl | Key::RCtrl, down)) => self.ctrl = down,
+            Some((Key::LShift | Key::RShift, down)) => self.shift = down,
+            _ => {}
+        }
+        // 失去焦点时收不到松开事件，清掉修饰键状态
+        if let Some(focused) = e.focus_args() {
+            self.focused = focused;
+            if !focused {
+                self.ctrl = false;
+                self.shift = false;
+            }
+            self.focus_changed(focused);
+            self.screen_reader.set_focused(focused);
+        }
+        if let Some(args) = e.resize_args() {
+            self.screen_reader
+                .set_scale(args.draw_size[0] as f64 / args.window_size[0]);
+        }
+
+        // 提示条：点击提示或按 Esc 关闭（这次输入不再交给场景）
+        if self.toast.is_some() {
+            let [x, y, w, h] = self.toast_rect();
+            let [cx, cy] = self.cursor;
+            let dismissed = match e.press_args() {
+                Some(Button::Keyboard(Key::Escape)) => true,
+                Some(Button::Mouse(MouseButton::Left)) => {
+                    cx >= x && cx < x + w && cy >= y && cy < y + h
+                }
+                _ => false,
+            };
+            if dismissed {
+                self.toast = None;
+                return true;
+            }
+        }
+
+        // 游戏中手柄有自己的操作，其它界面中手柄当作方向键 / Enter / Esc
+        let pad = self.gamepad.event(e, &self.config.gamepad);
+        let playing = matches!(self.scene, Scene::Playing);
+        let key = match e.press_args() {
+            Some(Button::Keyboard(k)) => Some(k),
+            _ => pad
+                .filter(|_| !playing)
+                .and_then(Pad::key)
+                .filter(|&k| k != Key::Escape || !matches!(self.scene, Scene::Menu { .. })),
+        };
+        // 拖入图片：识别后放进题目编辑器（主菜单与编辑器中）
+        #[cfg(feature = "ocr")]
+        if matches!(self.scene, Scene::Menu { .. } | Scene::Editor { .. }) {
+            let dropped = e.with_args(|args| args.downcast_ref::<FileDrag>().cloned());
+            if let Some(FileDrag::Drop(path)) = dropped {
+                self.import_photo(&path);
+                return true;
+            }
+        }
+
+        // 全局快捷键：切换主题（需要输入文字的场景除外）
+        let typing = matches!(self.scene, Scene::Editor { .. } | Scene::Palette { .. });
+        if key == Some(self.config.keys.toggle_theme) && !self.ctrl && !typing {
+            return self.dispatch(Action::ToggleTheme);
+        }
+        // 全局快捷键：Ctrl+= / Ctrl+- 调整界面缩放
+        if self.ctrl {
+            match key {
+                Some(Key::Equals | Key::Plus | Key::NumPadPlus) => {
+                    return self.dispatch(Action::ZoomIn)
+                }
+                Some(Key::Minus | Key::NumPadMinus) => return self.dispatch(Action::ZoomOut),
+                _ => {}
+            }
+        }
+
+        // 练习完成后 Enter 进入下一个练习局面
+        if (key == Some(Key::Return) || pad == Some(Pad::Place))
+            && playing
+            && self.in_drill()
+            && self.trainer.as_ref().is_some_and(Trainer::is_done)
+        {
+            self.next_drill();
+            return true;
+        }
+
+        let tab = &self.tabs[self.active];
+        let (active, was_solved, was_submitted) = (self.active, tab.is_solved(), tab.submitted);
+        let running = match self.scene {
+            Scene::Menu { .. } => self.menu_event(key),
+            Scene::Playing => match pad {
+                Some(pad) => self.pad_event(pad),
+                None => self.playing_event(e, key),
+            },
+            Scene::Palette { .. } => self.palette_event(e, key),
+            Scene::Help => {
+                if key.is_some() || matches!(e.press_args(), Some(Button::Mouse(_))) {
+                    self.scene = Scene::Playing;
+                }
+                true
+            }
+            Scene::Export { .. } => {
+                self.export_event(key);
+                true
+            }
+            Scene::ClearRegion { .. } => {
+                self.clear_region_event(key);
+                true
+            }
+            Scene::Qr { .. } => {
+                self.qr_event(key);
+                true
+            }
+            #[cfg(feature = "scripting")]
+            Scene::Scripts { .. } => {
+                self.scripts_event(key);
+                true
+            }
+            #[cfg(feature = "scripting")]
+            Scene::Proposal { .. } => {
+                self.proposal_event(key);
+                true
+            }
+            Scene::Paused => {
+                match key {
+                    Some(Key::Escape | Key::Return) => self.scene = Scene::Playing,
+                    Some(k) if k == self.config.keys.pause => self.scene = Scene::Playing,
+                    Some(Key::M) => self.open_menu(),
+                    _ => {}
+                }
+                true
+            }
+            Scene::Results { .. } => {
+                // 题库中的题目解完后 Enter 进入下一题
+                if key == Some(Key::Return) && self.next_in_pack(1).is_some() {
+                    return self.dispatch(Action::NextPuzzle);
+                }
+                self.results_event(key);
+                true
+            }
+            Scene::Replay { .. } => {
+                self.replay_event(key);
+                true
+            }
+            Scene::Editor { .. } => {
+                self.editor_event(e, key);
+                true
+            }
+            Scene::History { .. } => {
+                self.history_event(key);
+                true
+            }
+            Scene::Packs { .. } => {
+                self.packs_event(key);
+                true
+            }
+            Scene::About { .. } => {
+                self.about_event(key);
+                true
+            }
+            Scene::Stats { .. } => {
+                match key {
+                    Some(Key::Escape | Key::Return) => self.open_menu(),
+                    Some(Key::B) => self.open_best_times(self.config.difficulty),
+                    Some(Key::C) => self.open_calendar(),
+                    Some(Key::E) if self.telemetry.is_some() => {
+                        self.scene = Scene::Telemetry {
+                            message: String::new(),
+                        }
+                    }
+                    _ => {}
+                }
+                true
+            }
+            Scene::Telemetry { .. } => {
+                match key {
+                    Some(Key::Escape | Key::Return) => self.open_stats(),
+                    Some(Key::C) => self.copy_telemetry(),
+                    _ => {}
+                }
+                true
+            }
+            Scene::Calendar { .. } => {
+                self.calendar_event(key);
+                true
+            }
+            Scene::Drills { .. } => {
+                self.drills_event(key);
+                true
+            }
+            Scene::Analysis { solved, .. } => {
+                if let Some(Key::Escape | Key::Return) = key {
+                    self.scene = if solved {
+                        Scene::Results {
+                            message: String::new(),
+                        }
+                    } else {
+                        Scene::Playing
+                    };
+                }
+                true
+            }
+            Scene::BestTimes { difficulty, .. } => {
+                match key {
+                    Some(Key::Escape | Key::Return) => self.open_stats(),
+                    Some(Key::Left) => self.open_best_times(cycle(difficulty, false)),
+                    Some(Key::Right) => self.open_best_times(cycle(difficulty, true)),
+                    _ => {}
+                }
+                true
+            }
+            Scene::Leaderboard => {
+                if let Some(Key::Escape | Key::Return) = key {
+                    self.scene = Scene::Results {
+                        message: String::new(),
+                    };
+                }
+                true
+            }
+            Scene::Summary => key.is_none(),
+        };
+
+        let running = self.screen_reader_requests() && running;
+        self.check_finished(active, was_solved, was_submitted);
+        self.advance_tutorial();
+        self.check_drill();
+        self.refresh_explanation();
+        // 暂停游戏或窗口不在前台时暂停音乐
+        self.audio
+            .set_paused(!self.focused || matches!(self.scene, Scene::Paused));
+        // 读屏：节点树交给系统读屏软件；开启文字输出时焦点移动或选中格的内容改变时输出描述
+        let terminal = self.config.assist.screen_reader;
+        if (terminal || self.screen_reader.is_active()) && matches!(self.scene, Scene::Playing) {
+            let tree = accessibility::build(&self.tabs[self.active]);
+            self.screen_reader.update(&tree, &self.view);
+            if let Some(text) = self.a11y.update(tree).filter(|_| terminal) {
+                println!("{}", text);
+            }
+        }
+        running
+    }
+
+    /// 执行读屏软件请求的操作（只在游戏中）：移动焦点即选中格子，点击按钮与点击界面中的按钮相同
+    fn screen_reader_requests(&mut self) -> bool {
+        let mut running = true;
+        for request in self.screen_reader.requests() {
+            if !matches!(self.scene, Scene::Playing) {
+                continue;
+            }
+            match request {
+                Request::Select(cell) => self.tabs[self.active].selected_cell = Some(cell),
+                Request::Press(action) => running &= self.dispatch(action),
+            }
+            self.dirty = true;
+        }
+        running
+    }
+
+    /// 当前标签页刚提交时写入历史；刚解完时进入结算。
+    /// 输入后检查一次，排队等待求解的提交在 `update` 中完成后也检查一次
+    fn check_finished(&mut self, active: usize, was_solved: bool, was_submitted: bool) {
+        let tab = &self.tabs[self.active];
+        if active == self.active && !was_submitted && tab.submitted {
+            self.record_game();
+            // 提交了没解对的盘面视为放弃，直接显示分析
+            if !self.tabs[self.active].is_solved() && matches!(self.scene, Scene::Playing) {
+                self.open_analysis();
+            }
+        }
+        let tab = &self.tabs[self.active];
+        if matches!(self.scene, Scene::Playing)
+            && active == self.active
+            && !was_solved
+            && tab.is_solved()
+        {
+            let time = tab.elapsed;
+            if let Some(room) = self.room.as_mut() {
+                if tab.initial_cells == room.puzzle.cells {
+                    room.send_solved(time, &tab.gameboard);
+                }
+            }
+            self.stats.solved += 1;
+            self.stats.best_time = Some(self.stats.best_time.map_or(time, |t| t.min(time)));
+            self.open_leaderboard(Some(time));
+            self.save_card();
+            self.scene = Scene::Results {
+                message: String::new(),
+            };
+            if self.config.assist.screen_reader {
+                println!("Solved in {}", format_time(time));
+            }
+        }
+    }
+
+    /// 为刚解出的一局生成成绩卡片：图片存到数据目录，同样内容的一行文字留待在结算界面按 C 复制
+    fn save_card(&mut self) {
+        let tab = &self.tabs[self.active];
+        let puzzle = Gameboard::from_cells(tab.initial_cells);
+        let difficulty = rate_with(&puzzle, self.config.assist.unique_rectangles).difficulty;
+        let card = Card::new(tab, difficulty);
+        let path = card::default_path();
+        self.card_text = Some(card.text());
+        self.card = Some(match card.save_png(&path) {
+            Ok(()) => format!("Card saved to {}", path.display()),
+            Err(err) => {
+                eprintln!("无法保存成绩卡片 {}: {}", path.display(), err);
+                "Could not save the results card".to_string()
+            }
+        });
+    }
+
+    /// 把成绩卡片的文字复制到剪贴板，结果接在卡片那一行后面
+    fn copy_card(&mut self) {
+        let Some(text) = self.card_text.take() else {
+            return;
+        };
+        let result = match self.clipboard.copy(text) {
+            Ok(()) => "card text copied".to_string(),
+            Err(err) => format!("could not copy the card text: {}", err),
+        };
+        self.card = Some(match self.card.take() {
+            Some(card) => format!("{}; {}", card, result),
+            None => result,
+        });
+    }
+
+    /// 把当前标签页的对局写入历史数据库；解出时记下在本机最佳成绩中的名次
+    fn record_game(&mut self) {
+        self.best = None;
+        if self.history.is_none() && self.telemetry.is_none() {
+            return;
+        }
+        let tab = &self.tabs[self.active];
+        let puzzle = Gameboard::from_cells(tab.initial_cells);
+        let game = GameRecord {
+            puzzle: puzzle.to_line(),
+            difficulty: rate_with(&puzzle, self.config.assist.unique_rectangles).difficulty,
+            seconds: tab.elapsed,
+            mistakes: tab.mistakes,
+            solved: tab.is_solved(),
+            played_at: String::new(),
+            name: self.config.online.name.trim().to_string(),
+            daily: tab.daily.clone(),
+        };
+        if let Some(telemetry) = self.telemetry.as_mut().filter(|_| game.solved) {
+            telemetry.solved(game.difficulty, game.seconds);
+        }
+        let Some(history) = &self.history else {
+            return;
+        };
+        if let Err(err) = history.record(&game) {
+            eprintln!("无法写入历史记录: {}", err);
+            return;
+        }
+        if game.solved {
+            self.best = history
+                .rank(game.difficulty, game.seconds)
+                .ok()
+                .filter(|&rank| rank <= leaderboard::TOP)
+                .map(|rank| (game.difficulty, rank));
+        }
+        if let Some((pack, index)) = tab.pack.as_ref().filter(|_| game.solved) {
+            if let Err(err) = history.mark_pack_solved(pack, *index, tab.elapsed) {
+                eprintln!("无法写入题库进度: {}", err);
+            }
+        }
+    }
+
+    /// 各题库已解出的题数（没有历史数据库时全为 0）
+    fn pack_progress(&self) -> Vec<usize> {
+        self.packs
+            .iter()
+            .map(|pack| {
+                self.history
+                    .as_ref()
+                    .and_then(|h| h.pack_solved(&pack.name).ok())
+                    .map_or(0, |solved| solved.len())
+            })
+            .collect()
+    }
+
+    fn open_packs(&mut self) {
+        self.scene = Scene::Packs {
+            selected: 0,
+            solved: self.pack_progress(),
+        };
+    }
+
+    /// 题库列表：↑ / ↓ 选择，Enter 从第一道未解出的题开始
+    fn packs_event(&mut self, key: Option<Key>) {
+        let Scene::Packs { selected, .. } = &mut self.scene else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.open_menu(),
+            Some(Key::Up) => *selected = selected.saturating_sub(1),
+            Some(Key::Down) => *selected = (*selected + 1).min(self.packs.len().saturating_sub(1)),
+            Some(Key::Return) if *selected < self.packs.len() => {
+                let pack = *selected;
+                let solved = self
+                    .history
+                    .as_ref()
+                    .and_then(|h| h.pack_solved(&self.packs[pack].name).ok())
+                    .unwrap_or_default();
+                let index = (0..self.packs[pack].puzzles.len())
+                    .find(|i| !solved.contains(i))
+                    .unwrap_or(0);
+                let controller = self.pack_controller(pack, index);
+                self.open_tab(controller);
+            }
+            _ => {}
+        }
+    }
+
+    /// 题库 `pack` 第 `index` 题的 controller
+    fn pack_controller(&self, pack: usize, index: usize) -> GameboardController {
+        let pack = &self.packs[pack];
+        let mut controller = GameboardController::new(pack.puzzles[index].clone());
+        controller.pack = Some((pack.name.clone(), index));
+        controller
+    }
+
+    /// 当前题目在题库中往后 / 往前 `step` 题的位置 (题库序号, 题目序号)
+    fn next_in_pack(&self, step: isize) -> Option<(usize, usize)> {
+        let (name, index) = self.tabs[self.active].pack.as_ref()?;
+        let pack = self.packs.iter().position(|p| &p.name == name)?;
+        let next = index.checked_add_signed(step)?;
+        (next < self.packs[pack].puzzles.len()).then_some((pack, next))
+    }
+
+    /// 在当前标签页中切换到题库中的相邻题目
+    fn step_pack(&mut self, step: isize) {
+        if let Some((pack, index)) = self.next_in_pack(step) {
+            let mut controller = self.pack_controller(pack, index);
+            let current = &self.tabs[self.active];
+            controller.holes = current.holes;
+            controller.target = current.target;
+            controller.assist = current.assist;
+            self.tabs[self.active] = controller;
+            self.stats.started += 1;
+            self.scene = Scene::Playing;
+        }
+    }
+
+    /// 统计界面的文字：本次运行、各难度汇总、用时趋势与最近几局
+    fn stats_lines(&self) -> Vec<String> {
+        let best = self.stats.best_time.map_or("-".to_string(), format_time);
+        let mut lines = vec![format!(
+            "This session: {} started, {} solved, best {}, played {}",
+            self.stats.started,
+            self.stats.solved,
+            best,
+            format_time(self.stats.play_time)
+        )];
+        if let Some(telemetry) = &self.telemetry {
+            lines.extend(telemetry.lines());
+        }
+        let Some(history) = &self.history else {
+            lines.push("History unavailable".to_string());
+            return lines;
+        };
+        let time = |t: Option<f64>| t.map_or("-".to_string(), format_time);
+        match history.summary() {
+            Ok(summary) if summary.is_empty() => lines.push("No games recorded yet".to_string()),
+            Ok(summary) => lines.extend(summary.iter().map(|d| {
+                format!(
+                    "{}: {}/{} solved, best {}, avg {}",
+                    d.difficulty,
+                    d.solved,
+                    d.played,
+                    time(d.best),
+                    time(d.average)
+                )
+            })),
+            Err(err) => eprintln!("无法读取历史记录: {}", err),
+        }
+        if let Ok((recent, before @ Some(_))) = history.trend(10) {
+            lines.push(format!(
+                "Last 10 avg {} (previous 10: {})",
+                time(recent),
+                time(before)
+            ));
+        }
+        for (pack, solved) in self.packs.iter().zip(self.pack_progress()) {
+            lines.push(format!(
+                "{}: {}/{} completed",
+                pack.name,
+                solved,
+                pack.puzzles.len()
+            ));
+        }
+        if let Ok(games) = history.recent(5) {
+            lines.extend(games.iter().map(game_line));
+        }
+        lines.push(if self.telemetry.is_some() {
+            "B: best times   C: daily calendar   E: usage stats   Esc: back".to_string()
+        } else {
+            "B: best times   C: daily calendar   Esc: back".to_string()
+        });
+        lines
+    }
+
+    /// 把统计文件的内容复制到剪贴板（方便附在反馈里），结果显示在使用统计界面
+    fn copy_telemetry(&mut self) {
+        let Some(telemetry) = &self.telemetry else {
+            return;
+        };
+        let result = match self.clipboard.copy(telemetry.to_json()) {
+            Ok(()) => "Copied to the clipboard".to_string(),
+            Err(err) => format!("Could not copy: {}", err),
+        };
+        if let Scene::Telemetry { message } = &mut self.scene {
+            *message = result;
+        }
+    }
+
+    /// 关于界面：↑ / ↓（PageUp / PageDown 整页）滚动，L 切换到字体许可证全文，Esc 返回
+    fn about_event(&mut self, key: Option<Key>) {
+        let rows = self.about_rows();
+        let Scene::About { first, licenses } = &mut self.scene else {
+            return;
+        };
+        let max = about_lines(*licenses).len().saturating_sub(rows);
+        match key {
+            Some(Key::Escape | Key::Return) if *licenses => {
+                *licenses = false;
+                *first = 0;
+            }
+            Some(Key::Escape | Key::Return) => self.open_menu(),
+            Some(Key::L) => {
+                *licenses = !*licenses;
+                *first = 0;
+            }
+            Some(Key::Up) => *first = first.saturating_sub(1).min(max),
+            Some(Key::Down) => *first = (*first + 1).min(max),
+            Some(Key::PageUp) => *first = first.saturating_sub(rows).min(max),
+            Some(Key::PageDown) => *first = (*first + rows).min(max),
+            _ => {}
+        }
+    }
+
+    /// 关于界面在当前窗口中能显示的行数（不含最后一行说明）
+    fn about_rows(&self) -> usize {
+        let settings = &self.view.settings;
+        let free = settings.window_size[1]
+            - 32.0
+            - settings.scaled(PANEL_HEADER)
+            - settings.scaled(16.0)
+            - settings.scaled(PANEL_LINE);
+        (free / settings.scaled(PANEL_LINE)).floor().max(1.0) as usize
+    }
+
+    fn open_stats(&mut self) {
+        self.scene = Scene::Stats {
+            lines: self.stats_lines(),
+        };
+    }
+
+    /// 某个难度的本机最佳成绩（前十）
+    fn best_times(&self, difficulty: Difficulty) -> Vec<GameRecord> {
+        let Some(history) = &self.history else {
+            return Vec::new();
+        };
+        history
+            .top_times(difficulty, leaderboard::TOP)
+            .unwrap_or_else(|err| {
+                eprintln!("无法读取历史记录: {}", err);
+                Vec::new()
+            })
+    }
+
+    fn open_best_times(&mut self, difficulty: Difficulty) {
+        self.scene = Scene::BestTimes {
+            difficulty,
+            games: self.best_times(difficulty),
+        };
+    }
+
+    /// 当前标签页的赛后分析
+    fn open_analysis(&mut self) {
+        let tab = &self.tabs[self.active];
+        self.scene = Scene::Analysis {
+            lines: Analysis::new(tab).lines(),
+            solved: tab.is_solved(),
+        };
+    }
+
+    /// 打开每日题日历，显示本月
+    fn open_calendar(&mut self) {
+        let done = match &self.history {
+            Some(history) => history.daily_solved().unwrap_or_else(|err| {
+                eprintln!("无法读取历史记录: {}", err);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        let (year, month, _) = Date::today().ymd();
+        self.scene = Scene::Calendar {
+            year,
+            month,
+            done: done.iter().filter_map(|day| day.parse().ok()).collect(),
+        };
+    }
+
+    /// 每日题日历：← / → 切换月份
+    fn calendar_event(&mut self, key: Option<Key>) {
+        let Scene::Calendar { year, month, .. } = &mut self.scene else {
+            return;
+        };
+        match key {
+            Some(Key::Escape | Key::Return) => self.open_stats(),
+            Some(Key::Left) if *month == 1 => (*year, *month) = (*year - 1, 12),
+            Some(Key::Left) => *month -= 1,
+            Some(Key::Right) if *month == 12 => (*year, *month) = (*year + 1, 1),
+            Some(Key::Right) => *month += 1,
+            _ => {}
+        }
+    }
+
+    /// 打开历史列表（最多显示最近 200 局）
+    fn open_history(&mut self, difficulty: Option<Difficulty>, solved: Option<bool>) {
+        let games = match &self.history {
+            Some(history) => history
+                .query(difficulty, solved, 200)
+                .unwrap_or_else(|err| {
+                    eprintln!("无法读取历史记录: {}", err);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        self.scene = Scene::History {
+            games,
+            difficulty,
+            solved,
+            selected: 0,
+        };
+    }
+
+    /// 历史列表：↑ / ↓ 选择，D 切换难度筛选，R 切换结果筛选，Enter 重玩
+    fn history_event(&mut self, key: Option<Key>) {
+        let Scene::History {
+            games,
+            difficulty,
+            solved,
+            selected,
+        } = &mut self.scene
+        else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.open_menu(),
+            Some(Key::Up) => *selected = selected.saturating_sub(1),
+            Some(Key::Down) => *selected = (*selected + 1).min(games.len().saturating_sub(1)),
+            Some(Key::D) => {
+                // 全部 -> easy -> ... -> expert -> 全部
+                let next = match *difficulty {
+                    None => Some(Difficulty::ALL[0]),
+                    Some(d) => Difficulty::ALL
+                        .iter()
+                        .position(|&x| x == d)
+                        .and_then(|i| Difficulty::ALL.get(i + 1).copied()),
+                };
+                let solved = *solved;
+                self.open_history(next, solved);
+            }
+            Some(Key::R) => {
+                let next = match *solved {
+                    None => Some(true),
+                    Some(true) => Some(false),
+                    Some(false) => None,
+                };
+                let difficulty = *difficulty;
+                self.open_history(difficulty, next);
+            }
+            Some(Key::Return) => {
+                let Some(game) = games.get(*selected) else {
+                    return;
+                };
+                match Gameboard::from_line(&game.puzzle) {
+                    Ok(puzzle) => self.open_tab(GameboardController::new(puzzle)),
+                    Err(err) => eprintln!("历史记录中的题面无效: {}", err),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 执行一个动作（按钮、快捷键、命令面板共用）；返回 false 表示退出程序
+    pub fn dispatch(&mut self, action: Action) -> bool {
+        let n = self.tabs.len();
+        let tab = &mut self.tabs[self.active];
+        let spec = action.spec();
+        if !(spec.enabled)(tab) {
+            return true;
+        }
+        if let Some(input) = spec.input {
+            tab.apply(input);
+            return true;
+        }
+        match action {
+            Action::NewGame => self.new_game(),
+            Action::NewTab => self.new_tab(),
+            Action::CloseTab => self.close_tab(),
+            Action::NextTab => self.switch_tab((self.active + 1) % n),
+            Action::PrevTab => self.switch_tab((self.active + n - 1) % n),
+            Action::NextPuzzle => self.step_pack(1),
+            Action::PrevPuzzle => self.step_pack(-1),
+            Action::Diagnostics => self.diagnostics = !self.diagnostics,
+            Action::Conflicts => self.conflicts = !self.conflicts,
+            Action::Explain => {
+                self.explanation = match (&self.explanation, tab.selected_cell) {
+                    (None, Some(cell)) => Some(Explanation::new(tab, cell)),
+                    _ => None,
+                }
+            }
+            Action::Save => {
+                let path = savefile::default_path();
+                match savefile::save(tab, &path) {
+                    Ok(()) => println!("已保存到 {}", path.display()),
+                    Err(err) => eprintln!("保存失败: {}", err),
+                }
+            }
+            Action::ClearRegion => self.scene = Scene::ClearRegion { selected: 0 },
+            Action::CopyPuzzle => self.scene = Scene::Export { selected: 0 },
+            Action::PasteLink => self.paste_link(),
+            #[cfg(feature = "scripting")]
+            Action::RunScript => {
+                self.scene = Scene::Scripts {
+                    scripts: scripting::discover(),
+                    selected: 0,
+                }
+            }
+            Action::ToggleTheme => {
+                self.config.theme = self.config.theme.toggled();
+                let settings = std::mem::take(&mut self.view.settings);
+                self.view.settings = settings.with_theme(self.config.theme);
+                self.save_config();
+            }
+            Action::ColorMode => {
+                self.config.assist.colors = !self.config.assist.colors;
+                for tab in &mut self.tabs {
+                    tab.assist.colors = self.config.assist.colors;
+                }
+                self.save_config();
+            }
+            Action::CommandPalette => {
+                self.scene = Scene::Palette {
+                    query: String::new(),
+                    selected: 0,
+                }
+            }
+            Action::Help => self.scene = Scene::Help,
+            Action::ZoomIn => self.step_scale(SCALE_STEP),
+            Action::ZoomOut => self.step_scale(-SCALE_STEP),
+            Action::Pause => self.scene = Scene::Paused,
+            Action::Menu => self.open_menu(),
+            Action::Quit => return self.quit(),
+            // 对局操作已在上面交给当前标签页；禁用的动作（如未开启脚本）不会到这里
+            _ => {}
+        }
+        true
+    }
+
+    /// 游戏中的快捷键表：(修饰键前缀, 按键, 动作)，来自动作注册表与配置 `[keys]`
+    fn shortcuts(&self) -> Vec<(&'static str, Key, Action)> {
+        action::REGISTRY
+            .iter()
+            .flat_map(|spec| {
+                spec.shortcuts
+                    .iter()
+                    .map(move |&(prefix, key)| (prefix, key(&self.config.keys), spec.action))
+            })
+            .collect()
+    }
+
+    /// 当前按住的修饰键对应的前缀
+    fn modifiers(&self) -> &'static str {
+        match (self.ctrl, self.shift) {
+            (true, true) => "Ctrl+Shift+",
+            (true, false) => "Ctrl+",
+            (false, true) => "Shift+",
+            (false, false) => "",
+        }
+    }
+
+    /// 快捷键一览的条目：(全部快捷键, 动作名称)，按注册表顺序，没有快捷键的动作不列出
+    fn help_entries(&self) -> Vec<(String, &'static str)> {
+        action::REGISTRY
+            .iter()
+            .filter(|spec| !spec.shortcuts.is_empty())
+            .map(|spec| {
+                let keys: Vec<String> = spec
+                    .shortcuts
+                    .iter()
+                    .map(|&(prefix, key)| format!("{}{:?}", prefix, key(&self.config.keys)))
+                    .collect();
+                (keys.join(" / "), spec.name)
+            })
+            .collect()
+    }
+
+    /// 动作的第一个快捷键（命令面板中显示）
+    fn shortcut_text(&self, action: Action) -> Option<String> {
+        self.shortcuts()
+            .into_iter()
+            .find(|&(_, _, a)| a == action)
+            .map(|(prefix, key, _)| format!("{}{:?}", prefix, key))
+    }
+
+    fn open_menu(&mut self) {
+        self.scene = Scene::Menu { selected: 0 };
+    }
+
+    fn menu_items(&self) -> Vec<MenuItem> {
+        let mut items = Vec::with_capacity(8);
+        if self.has_game() {
+            items.push(MenuItem::Continue);
+        }
+        items.extend([
+            MenuItem::NewGame,
+            MenuItem::Difficulty,
+            MenuItem::Daily,
+            MenuItem::Tutorial,
+            MenuItem::Drills,
+        ]);
+        // 没有编译音频支持时不显示音乐设置
+        if cfg!(feature = "audio") {
+            items.push(MenuItem::Music);
+        }
+        items.extend([
+            MenuItem::Editor,
+            MenuItem::Stats,
+            MenuItem::History,
+            MenuItem::Packs,
+            MenuItem::About,
+            MenuItem::Quit,
+        ]);
+        items
+    }
+
+    fn menu_label(&self, item: MenuItem) -> String {
+        match item {
+            MenuItem::Continue => "Continue".to_string(),
+            MenuItem::NewGame => "New Game".to_string(),
+            MenuItem::Difficulty => format!("< Difficulty: {} >", self.config.difficulty),
+            MenuItem::Daily => match self.config.online.period {
+                Period::Daily => "Daily Puzzle".to_string(),
+                Period::Weekly => "Weekly Puzzle".to_string(),
+            },
+            MenuItem::Tutorial => "Tutorial".to_string(),
+            MenuItem::Drills => "Technique Drills".to_string(),
+            MenuItem::Music if self.config.audio.music => {
+                format!("< Music: {}% >", (self.config.audio.volume * 100.0).round())
+            }
+            MenuItem::Music => "< Music: Off >".to_string(),
+            MenuItem::Editor => "Custom Puzzle".to_string(),
+            MenuItem::Stats => "Statistics".to_string(),
+            MenuItem::History => "History".to_string(),
+            MenuItem::Packs => "Puzzle Packs".to_string(),
+            MenuItem::About => "About".to_string(),
+            MenuItem::Quit => "Quit".to_string(),
+        }
+    }
+
+    fn menu_event(&mut self, key: Option<Key>) -> bool {
+        let Scene::Menu { selected } = self.scene else {
+            return true;
+        };
+        let items = self.menu_items();
+        let selected = selected.min(items.len() - 1);
+        match key {
+            Some(Key::Up) => {
+                self.scene = Scene::Menu {
+                    selected: (selected + items.len() - 1) % items.len(),
+                }
+            }
+            Some(Key::Down) => {
+                self.scene = Scene::Menu {
+                    selected: (selected + 1) % items.len(),
+                }
+            }
+            Some(k @ (Key::Left | Key::Right)) if items[selected] == MenuItem::Difficulty => {
+                self.cycle_difficulty(k == Key::Right);
+            }
+            Some(k @ (Key::Left | Key::Right)) if items[selected] == MenuItem::Music => {
+                self.step_volume(if k == Key::Right { 0.1 } else { -0.1 });
+            }
+            Some(Key::Escape) => return self.quit(),
+            Some(Key::V) if self.ctrl => self.paste_link(),
+            Some(Key::Return) => match items[selected] {
+                MenuItem::Continue => {
+                    // 当前标签页已解完时切到第一个未完成的
+                    if self.tabs[self.active].is_solved() {
+                        if let Some(i) = self.tabs.iter().position(|tab| !tab.is_solved()) {
+                            self.switch_tab(i);
+                        }
+                    }
+                    self.scene = Scene::Playing;
+                }
+                // 当前题还没解完时保留它，在新标签页中开始
+                MenuItem::NewGame if self.has_game() && !self.tabs[self.active].is_solved() => {
+                    self.new_tab()
+                }
+                MenuItem::NewGame => self.new_game(),
+                MenuItem::Difficulty => self.cycle_difficulty(true),
+                MenuItem::Daily => self.open_daily(),
+                MenuItem::Tutorial => self.open_tutorial(),
+                MenuItem::Drills => self.open_drills(0),
+                MenuItem::Music => {
+                    self.config.audio.music = !self.config.audio.music;
+                    self.audio.apply(&self.config.audio);
+                    self.save_config();
+                }
+                MenuItem::Editor => self.open_editor(),
+                MenuItem::Stats => self.open_stats(),
+                MenuItem::History => self.open_history(None, None),
+                MenuItem::Packs => self.open_packs(),
+                MenuItem::About => {
+                    self.scene = Scene::About {
+                        first: 0,
+                        licenses: false,
+                    }
+                }
+                MenuItem::Quit => return self.quit(),
+            },
+            _ => {}
+        }
+        true
+    }
+
+    /// 退出：本次开始过题目时先显示小结（再按任意键才退出），否则直接退出。返回 false 表示退出
+    fn quit(&mut self) -> bool {
+        if self.stats.started == 0 {
+            return false;
+        }
+        self.scene = Scene::Summary;
+        true
+    }
+
+    /// 在菜单中切换难度并写入配置
+    fn cycle_difficulty(&mut self, forward: bool) {
+        let difficulty = cycle(self.config.difficulty, forward);
+        self.config.difficulty = difficulty;
+        for tab in &mut self.tabs {
+            tab.holes = difficulty.holes();
+            tab.target = Some(difficulty);
+        }
+        self.fresh = false;
+        self.save_config();
+    }
+
+    /// 在菜单中调整音乐音量并写入配置；调到 0 时关闭音乐，关闭时调高则按原音量打开
+    fn step_volume(&mut self, step: f32) {
+        let audio = &mut self.config.audio;
+        if !audio.music {
+            if step < 0.0 {
+                return;
+            }
+            audio.music = true;
+            audio.volume = audio.volume.max(step);
+        } else {
+            audio.volume = ((audio.volume + step) * 10.0).round().clamp(0.0, 10.0) / 10.0;
+            audio.music = audio.volume > 0.0;
+        }
+        self.audio.apply(&self.config.audio);
+        self.save_config();
+    }
+
+    /// 调整界面缩放并写入配置
+    fn step_scale(&mut self, step: f64) {
+        let scale = (self.config.scale + step).clamp(MIN_SCALE, MAX_SCALE);
+        self.config.scale = scale;
+        let settings = std::mem::take(&mut self.view.settings);
+        self.view.settings = settings.with_scale(scale);
+        self.save_config();
+    }
+
+    /// 在当前标签页开始新的一局；启动时已生成的新题直接使用
+    fn new_game(&mut self) {
+        if !self.fresh {
+            let tab = &mut self.tabs[self.active];
+            tab.randomize(tab.holes, tab.target);
+        }
+        self.fresh = false;
+        self.stats.started += 1;
+        if let Some(telemetry) = &mut self.telemetry {
+            telemetry.generated(self.tabs[self.active].target);
+        }
+        self.scene = Scene::Playing;
+    }
+
+    /// 新开一个标签页并切换过去
+    fn open_tab(&mut self, mut controller: GameboardController) {
+        let current = &self.tabs[self.active];
+        controller.holes = current.holes;
+        controller.target = current.target;
+        controller.assist = current.assist;
+        if self.fresh {
+            // 启动时的新题还没开始，直接替换
+            self.tabs[self.active] = controller;
+        } else {
+            self.tabs.push(controller);
+            self.switch_tab(self.tabs.len() - 1);
+        }
+        self.fresh = false;
+        self.stats.started += 1;
+        self.scene = Scene::Playing;
+    }
+
+    /// 在新标签页中生成一道随机题
+    fn new_tab(&mut self) {
+        let mut tab = GameboardController::new(Gameboard::new());
+        let current = &self.tabs[self.active];
+        tab.randomize(current.holes, current.target);
+        if let Some(telemetry) = &mut self.telemetry {
+            telemetry.generated(current.target);
+        }
+        self.open_tab(tab);
+    }
+
+    fn switch_tab(&mut self, index: usize) {
+        self.active = index;
+        let tab = &mut self.tabs[index];
+        tab.cursor_pos = self.cursor;
+        tab.mouse_pressed = false;
+    }
+
+    /// 关闭当前标签页（至少保留一个）
+    fn close_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.tabs.remove(self.active);
+            self.switch_tab(self.active.min(self.tabs.len() - 1));
+        }
+    }
+
+    /// 在新标签页中打开当期的每日 / 每周题（后台下载，失败时本地生成）
+    fn open_daily(&mut self) {
+        let online = self.config.online.clone();
+        let today = Date::today();
+        let key = today.period_start(online.period).to_string();
+        let mut tab = GameboardController::new(Gameboard::new());
+        tab.load_async(Some(key), move || daily::fetch(&online, today).puzzle);
+        self.open_tab(tab);
+    }
+
+    /// 在新标签页中打开剪贴板中的分享链接
+    fn paste_link(&mut self) {
+        let puzzle = self.clipboard.paste().and_then(|text| share::parse(&text));
+        match puzzle {
+            Ok(puzzle) => self.open_tab(GameboardController::new(puzzle)),
+            Err(err) => eprintln!("无法打开剪贴板中的链接: {}", err),
+        }
+    }
+
+    /// 在新标签页中打开教程题目，从第一步开始
+    fn open_tutorial(&mut self) {
+        let Ok(puzzle) = Gameboard::from_line(tutorial::PUZZLE) else {
+            return;
+        };
+        self.open_tab(GameboardController::new(puzzle));
+        self.tutorial = Some(Tutorial::default());
+        self.announce_tutorial();
+    }
+
+    /// 当前标签页是否为教程题目
+    fn in_tutorial(&self) -> bool {
+        self.tutorial.is_some()
+            && Gameboard::from_cells(self.tabs[self.active].initial_cells).to_line()
+                == tutorial::PUZZLE
+    }
+
+    /// 完成当前步骤后进入下一步；教程题目提交后结束教程
+    fn advance_tutorial(&mut self) {
+        if !self.in_tutorial() {
+            return;
+        }
+        let Some(tutorial) = &mut self.tutorial else {
+            return;
+        };
+        let step = tutorial.step();
+        if !tutorial.advance(&self.tabs[self.active]) {
+            self.tutorial = None;
+        } else if tutorial.step() != step {
+            self.announce_tutorial();
+        }
+    }
+
+    /// 开启读屏输出时把教程当前步骤的说明打印出来
+    fn announce_tutorial(&self) {
+        if let Some(tutorial) = self
+            .tutorial
+            .as_ref()
+            .filter(|_| self.config.assist.screen_reader)
+        {
+            let [first, second] = tutorial.text(&self.config.keys);
+            println!("{}: {} {}", tutorial.progress(), first, second);
+        }
+    }
+
+    fn open_drills(&mut self, selected: usize) {
+        self.scene = Scene::Drills {
+            selected,
+            loading: None,
+            message: String::new(),
+        };
+    }
+
+    /// 技巧列表：↑ / ↓ 选择，Enter 生成练习局面
+    fn drills_event(&mut self, key: Option<Key>) {
+        let Scene::Drills {
+            selected,
+            loading,
+            message,
+        } = &mut self.scene
+        else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.open_menu(),
+            Some(Key::Up) => *selected = selected.saturating_sub(1),
+            Some(Key::Down) => *selected = (*selected + 1).min(drills::TECHNIQUES.len() - 1),
+            Some(Key::Return) if loading.is_none() => {
+                *loading = Some(spawn_drill(drills::TECHNIQUES[*selected]));
+                message.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// 当前标签页是否为练习局面
+    fn in_drill(&self) -> bool {
+        self.trainer
+            .as_ref()
+            .is_some_and(|t| t.drill.board.cells == self.tabs[self.active].initial_cells)
+    }
+
+    /// 打开生成好的练习局面；正在练习时替换当前标签页，不累积标签页
+    fn start_drill(&mut self, drill: Drill) {
+        let controller = GameboardController::new(drill.board.clone());
+        if self.in_drill() {
+            let current = &self.tabs[self.active];
+            let mut controller = controller;
+            controller.holes = current.holes;
+            controller.target = current.target;
+            controller.assist = current.assist;
+            self.tabs[self.active] = controller;
+            self.stats.started += 1;
+            self.scene = Scene::Playing;
+        } else {
+            self.open_tab(controller);
+        }
+        self.trainer = Some(Trainer::new(drill));
+        self.announce_drill();
+    }
+
+    /// 完成练习后 Enter：同一技巧的下一个局面
+    fn next_drill(&mut self) {
+        let Some(trainer) = &self.trainer else {
+            return;
+        };
+        let technique = trainer.drill.technique;
+        let selected = drills::TECHNIQUES
+            .iter()
+            .position(|&t| t == technique)
+            .unwrap_or(0);
+        self.scene = Scene::Drills {
+            selected,
+            loading: Some(spawn_drill(technique)),
+            message: String::new(),
+        };
+    }
+
+    /// 判断练习局面中新填入的数字
+    fn check_drill(&mut self) {
+        if !self.in_drill() {
+            return;
+        }
+        let Some(trainer) = &mut self.trainer else {
+            return;
+        };
+        if trainer.check(&self.tabs[self.active]) {
+            self.announce_drill();
+        }
+    }
+
+    /// 开启读屏输出时把练习的反馈打印出来
+    fn announce_drill(&self) {
+        if let Some(trainer) = self
+            .trainer
+            .as_ref()
+            .filter(|_| self.config.assist.screen_reader)
+        {
+            println!("{}", trainer.text(&self.config.keys).join(" "));
+        }
+    }
+
+    fn open_editor(&mut self) {
+        let mut board = GameboardController::new(Gameboard::new());
+        board.selected_cell = Some([4, 4]);
+        self.scene = Scene::Editor {
+            board: Box::new(board),
+            message: String::new(),
+            checking: None,
+        };
+    }
+
+    /// 识别照片中的题目，放进题目编辑器供玩家修正
+    #[cfg(feature = "ocr")]
+    pub fn import_photo(&mut self, path: &Path) {
+        self.open_editor();
+        let Scene::Editor { board, message, .. } = &mut self.scene else {
+            return;
+        };
+        match ocr::recognize(path) {
+            Ok(puzzle) => {
+                board.set_board(&puzzle);
+                *message = "Check the digits, then press Enter".to_string();
+            }
+            Err(err) => *message = format!("Could not read the photo: {}", err),
+        }
+    }
+
+    fn playing_event<E: GenericEvent>(&mut self, e: &E, key: Option<Key>) -> bool {
+        if self.wheel_event(e, key) {
+            return true;
+        }
+        if let Some(k) = key {
+            // 单词数独：字母键填入它代表的数字，优先于同一个键上的快捷键
+            if !self.ctrl {
+                let tab = &mut self.tabs[self.active];
+                if let Some(digit) = tab.letter_digit(k) {
+                    tab.apply(InputAction::EnterDigit(digit));
+                    return true;
+                }
+            }
+            let modifiers = self.modifiers();
+            // 没有单独绑定 Shift 组合的键，按住 Shift 时仍按普通快捷键处理
+            let shortcuts = self.shortcuts();
+            let bound = shortcuts
+                .iter()
+                .find(|&&(prefix, bound, _)| prefix == modifiers && bound == k)
+                .or_else(|| {
+                    shortcuts.iter().find(|&&(prefix, bound, _)| {
+                        modifiers == "Shift+" && prefix.is_empty() && bound == k
+                    })
+                })
+                .copied();
+            if let Some((_, _, action)) = bound {
+                return self.dispatch(action);
+            }
+            if self.ctrl {
+                return true;
+            }
+        }
+        // 点击标签栏切换题目，点击底部按钮执行对应动作
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            if let Some(i) = self.view.tab_at(self.tabs.len(), self.cursor) {
+                self.switch_tab(i);
+                return true;
+            }
+            if let Some(action) = self.view.button_at(self.cursor) {
+                self.tabs[self.active].mouse_pressed = true;
+                return self.dispatch(action);
+            }
+            // 色块模式：点击调色板在选中格填入颜色（最后一格为清空）
+            let tab = &mut self.tabs[self.active];
+            if let Some(digit) = self
+                .view
+                .palette_at(self.cursor)
+                .filter(|_| tab.assist.colors)
+            {
+                tab.apply(if digit == 0 {
+                    InputAction::ClearCell
+                } else {
+                    InputAction::EnterDigit(digit)
+                });
+                return true;
+            }
+            // 点击冲突列表：标题行折叠 / 展开，其余行跳到冲突中玩家填写的格子
+            if let Some(line) = self
+                .view
+                .sidebar_line_at(self.cursor)
+                .filter(|_| self.conflicts)
+            {
+                self.conflict_clicked(line);
+                return true;
+            }
+            // Shift / Ctrl 点击格子：加入多选或从中去掉
+            if self.shift || self.ctrl {
+                let settings = &self.view.settings;
+                let tab = &mut self.tabs[self.active];
+                if let Some(cell) = tab.cell_at(settings.position, settings.size) {
+                    tab.apply(InputAction::ToggleMulti(cell));
+                    return true;
+                }
+            }
+        }
+
+        // 其余输入交给 controller（选格、移动与数字输入）
+        let settings = &self.view.settings;
+        self.tabs[self.active].board_event(settings.position, settings.size, e);
+        true
+    }
+
+    /// 点击了侧栏第 `line` 行（冲突列表打开时）
+    fn conflict_clicked(&mut self, line: usize) {
+        if line == 0 {
+            self.conflicts_collapsed = !self.conflicts_collapsed;
+            return;
+        }
+        if self.conflicts_collapsed {
+            return;
+        }
+        let tab = &mut self.tabs[self.active];
+        let Some(conflict) = tab.conflicts().into_iter().nth(line - 1) else {
+            return;
+        };
+        let cell = conflict
+            .cells
+            .iter()
+            .copied()
+            .find(|&[x, y]| tab.initial_cells[y][x] == 0)
+            .unwrap_or(conflict.cells[0]);
+        tab.apply(InputAction::SelectCell(cell));
+    }
+
+    /// 数字轮盘打开时先由它处理输入，处理了返回 true：按着弹出键拖到数字上松开、
+    /// 或松开后再点数字即填入；点在数字以外或按 Esc 关闭。按其它键时关闭轮盘，按键照常处理
+    fn wheel_event<E: GenericEvent>(&mut self, e: &E, key: Option<Key>) -> bool {
+        let tab = &mut self.tabs[self.active];
+        let Some(wheel) = tab.wheel else {
+            return false;
+        };
+        if let Some(k) = key {
+            tab.wheel = None;
+            return k == Key::Escape;
+        }
+        let released = matches!(e.release_args(), Some(Button::Mouse(_)));
+        let pressed = matches!(e.press_args(), Some(Button::Mouse(_)));
+        if !(released && wheel.held || pressed && !wheel.held) {
+            // 松开的不是弹出轮盘的那一下（或只是移动鼠标）
+            return released || pressed;
+        }
+        tab.mouse_pressed = false;
+        match self.view.wheel_digit(wheel.cell, self.cursor) {
+            Some(digit) => {
+                tab.wheel = None;
+                tab.selected_cell = Some(wheel.cell);
+                tab.apply(InputAction::EnterDigit(digit));
+            }
+            None if released => {
+                tab.wheel = Some(Wheel {
+                    held: false,
+                    ..wheel
+                })
+            }
+            None => tab.wheel = None,
+        }
+        true
+    }
+
+    /// 游戏中的手柄操作：移动选中格、转数字盘、把数字盘上的数字填入选中格
+    fn pad_event(&mut self, pad: Pad) -> bool {
+        let tab = &mut self.tabs[self.active];
+        match pad {
+            Pad::Move(dx, dy) => tab.apply(InputAction::MoveSelection(dx, dy)),
+            Pad::Place => tab.apply(InputAction::EnterDigit(self.gamepad.digit)),
+            Pad::Clear => tab.apply(InputAction::ClearCell),
+            Pad::PrevDigit => self.gamepad.turn(-1),
+            Pad::NextDigit => self.gamepad.turn(1),
+            Pad::Undo => return self.dispatch(Action::Undo),
+            Pad::Hint => return self.dispatch(Action::Hint),
+            Pad::Pause => return self.dispatch(Action::Pause),
+        }
+        true
+    }
+
+    /// 按模糊匹配得分排序的可执行动作（查询为空时按注册顺序列出）
+    fn palette_matches(query: &str, tab: &GameboardController) -> Vec<Action> {
+        let mut scored: Vec<(i32, Action)> = action::REGISTRY
+            .iter()
+            // 命令面板自己不列出
+            .filter(|spec| spec.action != Action::CommandPalette && (spec.enabled)(tab))
+            .filter_map(|spec| {
+                action::fuzzy_score(query, spec.name).map(|score| (score, spec.action))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        scored.into_iter().map(|(_, a)| a).collect()
+    }
+
+    fn palette_event<E: GenericEvent>(&mut self, e: &E, key: Option<Key>) -> bool {
+        let Scene::Palette { query, selected } = &mut self.scene else {
+            return true;
+        };
+        if let Some(text) = e.text_args() {
+            query.extend(text.chars().filter(|c| !c.is_control()));
+            *selected = 0;
+        }
+        let matches = Self::palette_matches(query, &self.tabs[self.active]);
+        match key {
+            Some(Key::Escape) => self.scene = Scene::Playing,
+            Some(Key::Backspace) => {
+                query.pop();
+                *selected = 0;
+            }
+            Some(Key::Up) => *selected = selected.saturating_sub(1),
+            Some(Key::Down) => *selected = (*selected + 1).min(matches.len().saturating_sub(1)),
+            Some(Key::Return) => {
+                let chosen = matches.get(*selected).copied();
+                self.scene = Scene::Playing;
+                if let Some(action) = chosen {
+                    return self.dispatch(action);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// 复制格式列表：↑ / ↓ 选择，Enter 复制后回到棋盘
+    fn export_event(&mut self, key: Option<Key>) {
+        let Scene::Export { selected } = &mut self.scene else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.scene = Scene::Playing,
+            Some(Key::Q) => self.open_qr(),
+            Some(Key::Up) => *selected = selected.saturating_sub(1),
+            Some(Key::Down) => *selected = (*selected + 1).min(share::choices().count() - 1),
+            Some(Key::Return) => {
+                let choice = share::choices().nth(*selected).flatten();
+                self.scene = Scene::Playing;
+                let puzzle = Gameboard::from_cells(self.tabs[self.active].initial_cells);
+                let text = share::export(&self.config.online, &puzzle, choice);
+                println!("复制为 {}:\n{}", share::label(choice), text);
+                if let Err(err) = self.clipboard.copy(text) {
+                    eprintln!("无法复制到剪贴板: {}", err);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 清空区域的确认：↑ / ↓ 选择区域，Enter 清空后回到棋盘，Esc 取消
+    fn clear_region_event(&mut self, key: Option<Key>) {
+        let options = self.clear_region_options();
+        let Scene::ClearRegion { selected } = &mut self.scene else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.scene = Scene::Playing,
+            Some(Key::Up) => *selected = selected.saturating_sub(1),
+            Some(Key::Down) => *selected = (*selected + 1).min(options.len().saturating_sub(1)),
+            Some(Key::Return) => {
+                let cells = options.get(*selected).map(|&(_, cells)| cells);
+                self.scene = Scene::Playing;
+                if let Some(cells) = cells {
+                    self.tabs[self.active].apply(InputAction::ClearCells(cells));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 清空区域确认面板的选项（文字, 格子）：有多选时先是多选的格子，再是选中格所在的
+    /// 行、列、宫；文字中给出其中填写的数字个数
+    fn clear_region_options(&self) -> Vec<(String, CellSet)> {
+        let tab = &self.tabs[self.active];
+        let Some([x, y]) = tab.selected_cell else {
+            return Vec::new();
+        };
+        let mut options = Vec::with_capacity(4);
+        if !tab.multi.is_empty() {
+            options.push(("Selection".to_string(), tab.multi));
+        }
+        options.extend(Unit::ALL.iter().map(|&unit| {
+            let label = match unit {
+                Unit::Row => format!("Row {}", y + 1),
+                Unit::Column => format!("Column {}", x + 1),
+                Unit::Box => format!("Box {}", unit.index([x, y]) + 1),
+            };
+            (label, unit.cells([x, y]))
+        }));
+        options
+            .into_iter()
+            .map(|(label, cells)| {
+                let entries = tab.entries_in(cells).len();
+                (format!("{}: {} entries", label, entries), cells)
+            })
+            .collect()
+    }
+
+    /// 脚本列表：↑ / ↓ 选择，Enter 运行，Esc 回到棋盘
+    #[cfg(feature = "scripting")]
+    fn scripts_event(&mut self, key: Option<Key>) {
+        let Scene::Scripts { scripts, selected } = &mut self.scene else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.scene = Scene::Playing,
+            Some(Key::Up) => *selected = selected.saturating_sub(1),
+            Some(Key::Down) => *selected = (*selected + 1).min(scripts.len().saturating_sub(1)),
+            Some(Key::Return) => {
+                let Some(script) = scripts.get(*selected) else {
+                    return;
+                };
+                let (moves, message) = match script.run(&self.tabs[self.active]) {
+                    Ok(Output::Moves(moves)) if moves.is_empty() => {
+                        (moves, "No suggestion".to_string())
+                    }
+                    Ok(Output::Moves(moves)) => (moves, String::new()),
+                    Ok(Output::Message(text)) => (Vec::new(), text),
+                    Err(err) => {
+                        // 面板只放得下一行，完整的错误打印到终端
+                        eprintln!("脚本 {} 出错: {}", script.path().display(), err);
+                        let line: String =
+                            err.lines().next().unwrap_or("").chars().take(48).collect();
+                        (Vec::new(), format!("Error: {}", line))
+                    }
+                };
+                self.scene = Scene::Proposal {
+                    name: script.name.clone(),
+                    moves,
+                    message,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// 脚本的结果：Enter 把走法作为玩家输入写入（可逐步撤销），Esc 放弃
+    #[cfg(feature = "scripting")]
+    fn proposal_event(&mut self, key: Option<Key>) {
+        let Scene::Proposal { moves, .. } = &mut self.scene else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.scene = Scene::Playing,
+            Some(Key::Return) => {
+                let moves = std::mem::take(moves);
+                self.scene = Scene::Playing;
+                let tab = &mut self.tabs[self.active];
+                if !tab.submitted {
+                    for step in &moves {
+                        tab.place(step.cell, step.value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 侧栏内容：冲突列表在最上（标题行之后每行一处冲突，点击时按行号对应），
+    /// 然后是选中格解释与求解器统计；都没打开时为空
+    fn sidebar_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.conflicts {
+            let tab = &self.tabs[self.active];
+            let conflicts = tab.conflicts();
+            let toggle = if self.conflicts_collapsed {
+                "[+]"
+            } else {
+                "[-]"
+            };
+            lines.push(format!("Conflicts ({})  {}", conflicts.len(), toggle));
+            if !self.conflicts_collapsed {
+                if conflicts.is_empty() {
+                    lines.push("No conflicts".to_string());
+                }
+                lines.extend(conflicts.iter().map(|c| c.describe(&tab.gameboard)));
+            }
+        }
+        if let Some(explanation) = &self.explanation {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(explanation.lines.iter().cloned());
+        }
+        if self.diagnostics {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(self.tabs[self.active].diagnostics_lines());
+        }
+        lines
+    }
+
+    /// 选中格或盘面变化后重新解释；取消选中时关闭
+    fn refresh_explanation(&mut self) {
+        let Some(explanation) = &self.explanation else {
+            return;
+        };
+        let tab = &self.tabs[self.active];
+        if explanation.is_stale(tab) {
+            self.explanation = tab.selected_cell.map(|cell| Explanation::new(tab, cell));
+        }
+    }
+
+    /// 当前题目分享链接的二维码
+    fn open_qr(&mut self) {
+        let puzzle = Gameboard::from_cells(self.tabs[self.active].initial_cells);
+        let link = share::link(&self.config.online, &puzzle);
+        match QrCode::encode(&link) {
+            Some(code) => {
+                self.scene = Scene::Qr {
+                    code,
+                    message: String::new(),
+                }
+            }
+            None => eprintln!("链接太长，无法生成二维码: {}", link),
+        }
+    }
+
+    /// 二维码：S 导出 PNG，Esc 回到结算界面（已解出）或棋盘
+    fn qr_event(&mut self, key: Option<Key>) {
+        let Scene::Qr { code, message } = &mut self.scene else {
+            return;
+        };
+        match key {
+            Some(Key::S) => {
+                let path = qr::default_png_path();
+                *message = match code.save_png(&path) {
+                    Ok(()) => format!("Saved to {}", path.display()),
+                    Err(err) => format!("Could not save: {}", err),
+                };
+            }
+            Some(Key::Escape | Key::Return) => {
+                self.scene = if self.tabs[self.active].is_solved() {
+                    Scene::Results {
+                        message: String::new(),
+                    }
+                } else {
+                    Scene::Playing
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn editor_event<E: GenericEvent>(&mut self, e: &E, key: Option<Key>) {
+        let Scene::Editor {
+            board,
+            message,
+            checking,
+        } = &mut self.scene
+        else {
+            return;
+        };
+        match key {
+            Some(Key::Escape) => self.open_menu(),
+            // 唯一解检查可能很慢（题面很空时），在后台进行，结果在 `update` 中处理
+            Some(Key::Return) if checking.is_none() => {
+                let puzzle = board.gameboard.clone();
+                let (sender, receiver) = mpsc::channel();
+                thread::spawn(move || {
+                    let count = puzzle.count_solutions(2);
+                    let _ = sender.send((puzzle, count));
+                });
+                *checking = Some(receiver);
+                *message = "Checking...".to_string();
+            }
+            Some(Key::Return) => {}
+            _ => {
+                let settings = &self.view.settings;
+                board.board_event(settings.position, settings.size, e);
+                if key.is_some() {
+                    message.clear();
+                }
+            }
+        }
+    }
+
+    fn save_config(&self) {
+        if let Err(err) = self.config.save() {
+            eprintln!("无法保存配置: {}", err);
+        }
+    }
+
+    /// 绘制当前场景
+    pub fn draw<G: Graphics, C>(&self, glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        match &self.scene {
+            Scene::Menu { selected } => {
+                let items = self.menu_items();
+                let labels: Vec<String> = items.iter().map(|&i| self.menu_label(i)).collect();
+                let selected = (*selected).min(items.len() - 1);
+                self.draw_panel("Sudoku", &labels, Some(selected), glyphs, c, g);
+            }
+            Scene::Playing => {
+                self.view.draw(&self.tabs[self.active], glyphs, c, g);
+                if self.tabs.len() > 1 {
+                    let labels: Vec<String> = self
+                        .tabs
+                        .iter()
+                        .enumerate()
+                        .map(|(i, tab)| format!("{}  {}", i + 1, tab.elapsed_text()))
+                        .collect();
+                    self.view.draw_tabs(&labels, self.active, glyphs, c, g);
+                }
+                self.draw_room(glyphs, c, g);
+                let tab = &self.tabs[self.active];
+                if let Some([x, y]) = tab.selected_cell.filter(|_| self.gamepad.active) {
+                    if tab.initial_cells[y][x] == 0 && tab.gameboard.cells[y][x] == 0 {
+                        self.view
+                            .draw_preview(tab, [x, y], self.gamepad.digit, glyphs, c, g);
+                    }
+                }
+                if let Some(explanation) = &self.explanation {
+                    self.view.draw_links(&explanation.links, glyphs, c, g);
+                }
+                let sidebar = self.sidebar_lines();
+                if !sidebar.is_empty() {
+                    self.view.draw_sidebar(&sidebar, glyphs, c, g);
+                }
+                if let Some(wheel) = tab.wheel {
+                    self.view
+                        .draw_wheel(tab, wheel.cell, self.cursor, glyphs, c, g);
+                }
+                self.draw_overlays(glyphs, c, g);
+            }
+            Scene::Palette { query, selected } => {
+                self.view.draw(&self.tabs[self.active], glyphs, c, g);
+                self.draw_palette(query, *selected, glyphs, c, g);
+            }
+            Scene::Help => {
+                self.view.draw(&self.tabs[self.active], glyphs, c, g);
+                self.draw_help(glyphs, c, g);
+            }
+            Scene::Qr { code, message } => {
+                self.view.draw_board(&self.tabs[self.active], glyphs, c, g);
+                self.draw_qr(code, message, glyphs, c, g);
+            }
+            #[cfg(feature = "scripting")]
+            Scene::Scripts { scripts, selected } => {
+                self.view.draw(&self.tabs[self.active], glyphs, c, g);
+                let mut lines: Vec<String> = scripts.iter().map(|s| s.name.clone()).collect();
+                if lines.is_empty() {
+                    lines.push("No .rhai files in scripts/".to_string());
+                }
+                lines.push("Enter: run   Esc: back".to_string());
+                let highlight = (!scripts.is_empty()).then_some(*selected);
+                self.draw_panel("Run Script", &lines, highlight, glyphs, c, g);
+            }
+            #[cfg(feature = "scripting")]
+            Scene::Proposal {
+                name,
+                moves,
+                message,
+            } => {
+                self.view.draw(&self.tabs[self.active], glyphs, c, g);
+                // 宏可能一次提出很多步，面板只列前几步
+                let mut lines: Vec<String> = moves
+                    .iter()
+                    .take(PROPOSAL_LINES)
+                    .map(|m| {
+                        let [x, y] = m.cell;
+                        format!("r{}c{} = {}   {}", y + 1, x + 1, m.value, m.reason)
+                    })
+                    .collect();
+                if moves.len() > PROPOSAL_LINES {
+                    lines.push(format!("... and {} more", moves.len() - PROPOSAL_LINES));
+                }
+                if !message.is_empty() {
+                    lines.push(message.clone());
+                }
+                lines.push(if moves.is_empty() {
+                    "Esc: back".to_string()
+                } else {
+                    format!("Enter: fill in {}   Esc: back", moves.len())
+                });
+                self.draw_panel(&format!("Script: {}", name), &lines, None, glyphs, c, g);
+            }
+            Scene::ClearRegion { selected } => {
+                self.view.draw(&self.tabs[self.active], glyphs, c, g);
+                let mut lines: Vec<String> = self
+                    .clear_region_options()
+                    .into_iter()
+                    .map(|(line, _)| line)
+                    .collect();
+                lines.push("Enter: clear   Esc: cancel".to_string());
+                self.draw_panel("Clear Entries", &lines, Some(*selected), glyphs, c, g);
+            }
+            Scene::Export { selected } => {
+                self.view.draw(&self.tabs[self.active], glyphs, c, g);
+                let mut lines: Vec<String> = share::choices()
+                    .map(|choice| share::label(choice).to_string())
+                    .collect();
+                lines.push("Enter: copy   Q: QR code   Esc: back".to_string());
+                self.draw_panel("Copy Puzzle", &lines, Some(*selected), glyphs, c, g);
+            }
+            Scene::Paused => {
+                let lines = [
+                    self.tabs[self.active].elapsed_text(),
+                    "Esc: resume".to_string(),
+                    "M: menu".to_string(),
+                ];
+                self.draw_panel("Paused", &lines, None, glyphs, c, g);
+            }
+            Scene::Results { message } => {
+                let tab = &self.tabs[self.active];
+                self.view.draw_board(tab, glyphs, c, g);
+                let mut lines = vec![format!("Time {}", tab.elapsed_text())];
+                if let Some(word) = tab.hidden_word() {
+                    lines.push(format!("Hidden word: {}", word));
+                }
+                if let Some(card) = &self.card {
+                    lines.push(card.clone());
+                }
+                if !message.is_empty() {
+                    lines.push(message.clone());
+                }
+                // 进入本机前十时列出该难度的最佳成绩，高亮本局
+                let mut highlight = None;
+                if let Some((difficulty, rank)) = self.best {
+                    lines.push(if rank == 1 {
+                        format!("New best time for {}!", difficulty)
+                    } else {
+                        format!("#{} of your {} best times", rank, difficulty)
+                    });
+                    highlight = Some(lines.len() + rank - 1);
+                    let games = self.best_times(difficulty);
+                    lines.extend(games.iter().enumerate().map(|(i, game)| best_line(i, game)));
+                }
+                if let Some((name, index)) = &tab.pack {
+                    lines.push(format!("{} #{}", name, index + 1));
+                }
+                if let Some(date) = &tab.daily {
+                    lines.push(format!("Puzzle of {}   L: leaderboard", date));
+                }
+                lines.push(if self.next_in_pack(1).is_some() {
+                    "Enter: next puzzle".to_string()
+                } else {
+                    "Enter: new game".to_string()
+                });
+                lines.push("E: export replay   I: open replay".to_string());
+                lines.push(if self.card_text.is_some() {
+                    "A: game analysis   Q: QR code   C: copy card text".to_string()
+                } else {
+                    "A: game analysis   Q: QR code".to_string()
+                });
+                lines.push("Esc: menu".to_string());
+                self.draw_panel("Solved!", &lines, highlight, glyphs, c, g);
+                self.draw_room(glyphs, c, g);
+            }
+            Scene::Replay {
+                replay,
+                board,
+                time,
+                paused,
+                speed,
+            } => {
+                self.view.draw_board(board, glyphs, c, g);
+                let status = format!(
+                    "Replay {} / {}  x{}{}",
+                    format_time(*time),
+                    format_time(replay.duration()),
+                    speed,
+                    if *paused { "  (paused)" } else { "" }
+                );
+                let help = "Space: play/pause   Left/Right: step   +/-: speed   Esc: back";
+                let settings = &self.view.settings;
+                let center = settings.position[0] + settings.size / 2.0;
+                let top = settings.position[1] + settings.size;
+                for (i, line) in [status.as_str(), help].iter().enumerate() {
+                    text::draw_anchored(
+                        line,
+                        settings.hud_font_size,
+                        settings.text_color,
+                        [center, top + settings.scaled(28.0 + i as f64 * 24.0)],
+                        Align::Center,
+                        glyphs,
+                        c,
+                        g,
+                    );
+                }
+            }
+            Scene::Editor { board, message, .. } => {
+                self.view.draw_board(board, glyphs, c, g);
+                let help = if !message.is_empty() {
+                    message
+                } else if cfg!(feature = "ocr") {
+                    "Enter: play   Esc: back   Drop a photo to import"
+                } else {
+                    "Enter: play   Esc: back"
+                };
+                let settings = &self.view.settings;
+                let pos = [
+                    settings.position[0] + settings.size / 2.0,
+                    settings.position[1] + settings.size + settings.scaled(28.0),
+                ];
+                text::draw_anchored(
+                    help,
+                    settings.hud_font_size,
+                    settings.text_color,
+                    pos,
+                    Align::Center,
+                    glyphs,
+                    c,
+                    g,
+                );
+            }
+            Scene::Packs { selected, solved } => {
+                let mut lines: Vec<String> = self
+                    .packs
+                    .iter()
+                    .zip(solved)
+                    .map(|(pack, solved)| {
+                        format!("{}  {}/{}", pack.name, solved, pack.puzzles.len())
+                    })
+                    .collect();
+                let highlight = (!lines.is_empty()).then_some(*selected);
+                if lines.is_empty() {
+                    lines.push("No packs found in ./packs".to_string());
+                }
+                lines.push("Enter: play   Esc: back".to_string());
+                self.draw_panel("Puzzle Packs", &lines, highlight, glyphs, c, g);
+            }
+            Scene::Stats { lines } => self.draw_panel("Statistics", lines, None, glyphs, c, g),
+            Scene::Telemetry { message } => {
+                let mut lines = Vec::new();
+                if let Some(telemetry) = &self.telemetry {
+                    lines.extend(telemetry.lines());
+                    lines.push(format!("Saved in {}", telemetry.path().display()));
+                }
+                if !message.is_empty() {
+                    lines.push(message.clone());
+                }
+                lines.push("C: copy as JSON   Esc: back".to_string());
+                self.draw_panel("Usage Statistics", &lines, None, glyphs, c, g);
+            }
+            Scene::About { first, licenses } => {
+                let all = about_lines(*licenses);
+                let shown = self.about_rows().min(all.len());
+                let first = (*first).min(all.len() - shown);
+                let mut lines = all[first..first + shown].to_vec();
+                let scroll = if shown < all.len() {
+                    "Up/Down: scroll   "
+                } else {
+                    ""
+                };
+                lines.push(if *licenses {
+                    format!("{}Esc: back", scroll)
+                } else {
+                    format!("{}L: licenses   Esc: back", scroll)
+                });
+                let title = if *licenses { "Font License" } else { "About" };
+                self.draw_panel(title, &lines, None, glyphs, c, g);
+            }
+            Scene::Summary => {
+                let mut lines = self.stats.summary();
+                lines.push("Press any key to quit".to_string());
+                self.draw_panel("Session Summary", &lines, None, glyphs, c, g);
+            }
+            Scene::Analysis { lines, solved } => {
+                self.view.draw_board(&self.tabs[self.active], glyphs, c, g);
+                let mut lines = lines.clone();
+                lines.push(if *solved {
+                    "Esc: back".to_string()
+                } else {
+                    "Esc: back to the board".to_string()
+                });
+                self.draw_panel("Game Analysis", &lines, None, glyphs, c, g);
+            }
+            Scene::Drills {
+                selected,
+                loading,
+                message,
+            } => {
+                let technique = drills::TECHNIQUES[*selected];
+                let mut lines: Vec<String> = drills::TECHNIQUES
+                    .iter()
+                    .map(Technique::to_string)
+                    .collect();
+                lines.push(drills::explain(technique).to_string());
+                lines.push(if loading.is_some() {
+                    format!("Generating a {} drill...", technique)
+                } else {
+                    message.clone()
+                });
+                lines.push("Enter: start   Esc: back".to_string());
+                self.draw_panel("Technique Drills", &lines, Some(*selected), glyphs, c, g);
+            }
+            Scene::Calendar { year, month, done } => {
+                self.draw_calendar(*year, *month, done, glyphs, c, g)
+            }
+            Scene::BestTimes { difficulty, games } => {
+                let mut lines = vec![format!("< {} >", difficulty)];
+                lines.extend(games.iter().enumerate().map(|(i, game)| best_line(i, game)));
+                if games.is_empty() {
+                    lines.push("No solved games".to_string());
+                }
+                lines.push("Esc: back".to_string());
+                self.draw_panel("Best Times", &lines, None, glyphs, c, g);
+            }
+            Scene::Leaderboard => {
+                let tab = &self.tabs[self.active];
+                self.view.draw_board(tab, glyphs, c, g);
+                self.draw_panel("Leaderboard", &self.leaderboard_lines(), None, glyphs, c, g);
+            }
+            Scene::History {
+                games,
+                difficulty,
+                solved,
+                selected,
+            } => {
+                const VISIBLE: usize = 12;
+                let filter = format!(
+                    "[D] {}   [R] {}",
+                    difficulty.map_or("all difficulties".to_string(), |d| d.to_string()),
+                    match solved {
+                        None => "all results",
+                        Some(true) => "solved",
+                        Some(false) => "unsolved",
+                    }
+                );
+                let first = selected.saturating_sub(VISIBLE - 1);
+                let mut lines = vec![filter];
+                lines.extend(games.iter().skip(first).take(VISIBLE).map(game_line));
+                if games.is_empty() {
+                    lines.push("No games".to_string());
+                }
+                lines.push("Enter: play again   Esc: back".to_string());
+                let highlight = (!games.is_empty()).then(|| selected - first + 1);
+                self.draw_panel("History", &lines, highlight, glyphs, c, g);
+            }
+        }
+        self.draw_toast(glyphs, c, g);
+    }
+
+    /// 排行榜面板的文字
+    fn leaderboard_lines(&self) -> Vec<String> {
+        let Some(board) = &self.leaderboard else {
+            return vec!["Esc: back".to_string()];
+        };
+        let source = if board.online {
+            "online"
+        } else {
+            "this computer"
+        };
+        let mut lines = vec![format!("Puzzle of {} ({})", board.key, source)];
+        lines.extend(board.entries.iter().enumerate().map(|(i, entry)| {
+            format!("{}. {}   {}", i + 1, format_time(entry.seconds), entry.name)
+        }));
+        if board.is_loading() {
+            lines.push("Loading...".to_string());
+        } else if board.entries.is_empty() {
+            lines.push("No times yet".to_string());
+        }
+        lines.push("Esc: back".to_string());
+        lines
+    }
+
+    /// 联机侧栏；合作模式下在联机题目上叠加其他玩家的填写与光标
+    fn draw_room<G: Graphics, C>(&self, glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let Some(room) = &self.room else {
+            return;
+        };
+        if room.coop && self.room_tab() == Some(self.active) {
+            let owners = room
+                .owners
+                .map(|row| row.map(|id| (id != 0 && id != room.id).then(|| net::color_index(id))));
+            let cursors: Vec<(usize, [usize; 2])> = room
+                .cursors
+                .iter()
+                .map(|&(id, cell)| (net::color_index(id), cell))
+                .collect();
+            self.view.draw_peers(&owners, &cursors, c, g);
+        }
+        // 冲突列表、单格解释与求解器统计占用同一位置，打开时先显示它们
+        if self.sidebar_lines().is_empty() {
+            self.view.draw_sidebar(&room.sidebar_lines(), glyphs, c, g);
+        }
+    }
+
+    /// 命令面板：顶部输入行加匹配的动作列表（最多 10 行，随选中项滚动）
+    fn draw_palette<G: Graphics, C>(
+        &self,
+        query: &str,
+        selected: usize,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        const VISIBLE: usize = 10;
+        let settings = &self.view.settings;
+        let font = settings.scaled(16.0) as u32;
+        let row_h = settings.scaled(26.0);
+        let pad = settings.scaled(8.0);
+        let matches = Self::palette_matches(query, &self.tabs[self.active]);
+        let first = selected.saturating_sub(VISIBLE - 1);
+        let shown = &matches[first.min(matches.len())..matches.len().min(first + VISIBLE)];
+
+        let w = (settings.window_size[0] - 32.0).clamp(160.0, settings.scaled(420.0));
+        let h = row_h * (shown.len() + 1) as f64 + 2.0 * pad;
+        let x = (settings.window_size[0] - w) / 2.0;
+        let y = settings.scaled(48.0);
+        Rectangle::new(settings.background_color).draw([x, y, w, h], &c.draw_state, c.transform, g);
+        Rectangle::new_border(settings.board_edge_color, 1.0).draw(
+            [x, y, w, h],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+
+        let baseline = |row: usize| y + pad + row as f64 * row_h + row_h * 0.7;
+        text::draw_anchored(
+            &format!("> {}_", query),
+            font,
+            settings.text_color,
+            [x + pad, baseline(0)],
+            Align::Left,
+            glyphs,
+            c,
+            g,
+        );
+        for (i, &action) in shown.iter().enumerate() {
+            let row = i + 1;
+            if first + i == selected {
+                Rectangle::new(settings.selected_cell_background_color).draw(
+                    [x + pad / 2.0, y + pad + row as f64 * row_h, w - pad, row_h],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            text::draw_anchored(
+                action.name(),
+                font,
+                settings.text_color,
+                [x + pad, baseline(row)],
+                Align::Left,
+                glyphs,
+                c,
+                g,
+            );
+            if let Some(shortcut) = self.shortcut_text(action) {
+                text::draw_anchored(
+                    &shortcut,
+                    settings.hud_font_size,
+                    settings.hud_text_color,
+                    [x + w - pad, baseline(row)],
+                    Align::Right,
+                    glyphs,
+                    c,
+                    g,
+                );
+            }
+        }
+    }
+
+    /// 教程与技巧练习的提示框
+    fn draw_overlays<G: Graphics, C>(&self, glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        if let Some(tutorial) = self.tutorial.as_ref().filter(|_| self.in_tutorial()) {
+            let [first, second] = tutorial.text(&self.config.keys);
+            self.draw_callout(&[tutorial.progress(), first, second], glyphs, c, g);
+        }
+        if let Some(trainer) = self.trainer.as_ref().filter(|_| self.in_drill()) {
+            self.draw_callout(&trainer.text(&self.config.keys), glyphs, c, g);
+        }
+    }
+
+    /// 棋盘上的提示框（第一行为标题）：画在与选中格相对的一半，避免挡住正在操作的格子
+    fn draw_callout<G: Graphics, C>(&self, lines: &[String], glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let settings = &self.view.settings;
+        let [bx, by, bw, bh] = self.view.board_rect();
+        let margin = settings.scaled(16.0);
+        let line_h = settings.scaled(24.0);
+        let h = lines.len() as f64 * line_h + margin;
+        let lower = self.tabs[self.active]
+            .selected_cell
+            .is_none_or(|[_, y]| y < 5);
+        let y = if lower {
+            by + bh - h - margin
+        } else {
+            by + margin
+        };
+        let rect = [bx + margin, y, bw - 2.0 * margin, h];
+        Rectangle::new(settings.hud_bg_color)
+            .border(graphics::rectangle::Border {
+                color: settings.board_edge_color,
+                radius: 1.0,
+            })
+            .draw(rect, &c.draw_state, c.transform, g);
+        let size = settings.scaled(16.0) as u32;
+        for (i, line) in lines.iter().enumerate() {
+            let color = if i == 0 {
+                settings.progress_fill_color
+            } else {
+                settings.hud_text_color
+            };
+            text::draw_centered(
+                line,
+                size,
+                color,
+                [
+                    rect[0],
+                    y + margin / 2.0 + i as f64 * line_h,
+                    rect[2],
+                    line_h,
+                ],
+                glyphs,
+                c,
+                g,
+            );
+        }
+    }
+
+    /// 窗口底部提示条的矩形
+    fn toast_rect(&self) -> [f64; 4] {
+        let settings = &self.view.settings;
+        let [win_w, win_h] = settings.window_size;
+        let w = (win_w - 32.0).clamp(120.0, settings.scaled(480.0));
+        let h = settings.scaled(32.0);
+        [(win_w - w) / 2.0, win_h - h - settings.scaled(8.0), w, h]
+    }
+
+    /// 提示条（如有新版本），盖在任何界面上
+    fn draw_toast<G: Graphics, C>(&self, glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let Some(toast) = &self.toast else {
+            return;
+        };
+        let settings = &self.view.settings;
+        let rect = self.toast_rect();
+        Rectangle::new(settings.hud_bg_color)
+            .border(graphics::rectangle::Border {
+                color: settings.progress_fill_color,
+                radius: 1.0,
+            })
+            .draw(rect, &c.draw_state, c.transform, g);
+        text::draw_centered(
+            &format!("{}   (click or Esc to dismiss)", toast),
+            settings.scaled(14.0) as u32,
+            settings.hud_text_color,
+            rect,
+            glyphs,
+            c,
+            g,
+        );
+    }
+
+    /// 居中面板的矩形（`lines` 行文字）
+    fn panel_rect(&self, lines: usize) -> [f64; 4] {
+        let settings = &self.view.settings;
+        let w = (settings.window_size[0] - 32.0).clamp(120.0, settings.scaled(480.0));
+        let h = settings.scaled(PANEL_HEADER)
+            + lines as f64 * settings.scaled(PANEL_LINE)
+            + settings.scaled(16.0);
+        let x = (settings.window_size[0] - w) / 2.0;
+        let y = (settings.window_size[1] - h) / 2.0;
+        [x, y, w, h]
+    }
+
+    /// 快捷键一览：每条左边是按键、右边是动作名称，窗口放不下时分成几栏
+    fn draw_help<G: Graphics, C>(&self, glyphs: &mut C, c: &Context, g: &mut G)
+    where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let settings = &self.view.settings;
+        let entries = self.help_entries();
+        let font = settings.scaled(15.0) as u32;
+        let row_h = settings.scaled(22.0);
+        let pad = settings.scaled(12.0);
+        let header = settings.scaled(48.0);
+        let [win_w, win_h] = settings.window_size;
+        // 标题与最后一行说明之外能放下的行数
+        let fit = ((win_h - 32.0 - header - row_h - 2.0 * pad) / row_h)
+            .floor()
+            .max(1.0) as usize;
+        let columns = entries.len().div_ceil(fit).max(1);
+        let rows = entries.len().div_ceil(columns);
+        let mut widest = |texts: Vec<&str>| {
+            texts
+                .into_iter()
+                .map(|text| text::measure(glyphs, font, text).width)
+                .fold(0.0, f64::max)
+        };
+        let key_w = widest(entries.iter().map(|(keys, _)| keys.as_str()).collect()) + pad;
+        let name_w = widest(entries.iter().map(|&(_, name)| name).collect()) + 2.0 * pad;
+        let footer_w = widest(vec![HELP_FOOTER]);
+        let column_w = key_w + name_w;
+        let w = (column_w * columns as f64)
+            .max(footer_w)
+            .min(win_w - 16.0 - 2.0 * pad)
+            + 2.0 * pad;
+        let h = header + (rows + 1) as f64 * row_h + 2.0 * pad;
+        let x = (win_w - w) / 2.0;
+        let y = ((win_h - h) / 2.0).max(8.0);
+
+        Rectangle::new(settings.background_color).draw([x, y, w, h], &c.draw_state, c.transform, g);
+        Rectangle::new_border(settings.board_edge_color, 1.0).draw(
+            [x, y, w, h],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        text::draw_anchored(
+            "Keyboard Shortcuts",
+            settings.scaled(24.0) as u32,
+            settings.text_color,
+            [x + w / 2.0, y + pad + settings.scaled(24.0)],
+            Align::Center,
+            glyphs,
+            c,
+            g,
+        );
+        let baseline = |row: usize| y + header + pad + row as f64 * row_h + row_h * 0.7;
+        for (i, (keys, name)) in entries.iter().enumerate() {
+            let left = x + pad + (i / rows) as f64 * column_w;
+            let top = baseline(i % rows);
+            for (text, pos) in [(keys.as_str(), left), (*name, left + key_w)] {
+                text::draw_anchored(
+                    text,
+                    font,
+                    settings.text_color,
+                    [pos, top],
+                    Align::Left,
+                    glyphs,
+                    c,
+                    g,
+                );
+            }
+        }
+        text::draw_anchored(
+            HELP_FOOTER,
+            font,
+            settings.text_color,
+            [x + w / 2.0, baseline(rows)],
+            Align::Center,
+            glyphs,
+            c,
+            g,
+        );
+    }
+
+    /// 二维码面板：上方留空画码（固定黑白两色，深色主题下也能扫描），下面是说明
+    fn draw_qr<G: Graphics, C>(
+        &self,
+        code: &QrCode,
+        message: &str,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        const QR_LINES: usize = 8;
+        let mut lines = vec![String::new(); QR_LINES];
+        lines.push("Scan to open this puzzle".to_string());
+        lines.push(message.to_string());
+        lines.push("S: save PNG   Esc: back".to_string());
+        self.draw_panel("QR Code", &lines, None, glyphs, c, g);
+
+        let settings = &self.view.settings;
+        let [x, y, w, _] = self.panel_rect(lines.len());
+        let side = QR_LINES as f64 * settings.scaled(PANEL_LINE) - settings.scaled(8.0);
+        let left = x + (w - side) / 2.0;
+        let top = y + settings.scaled(PANEL_HEADER);
+        Rectangle::new([1.0; 4]).draw([left, top, side, side], &c.draw_state, c.transform, g);
+        // 四周留出两个模块的白边
+        let module = side / (code.size() + 4) as f64;
+        let dark = Rectangle::new([0.0, 0.0, 0.0, 1.0]);
+        for row in 0..code.size() {
+            for col in 0..code.size() {
+                if code.is_dark(col, row) {
+                    let rect = [
+                        left + (col + 2) as f64 * module,
+                        top + (row + 2) as f64 * module,
+                        module,
+                        module,
+                    ];
+                    dark.draw(rect, &c.draw_state, c.transform, g);
+                }
+            }
+        }
+    }
+
+    /// 每日题日历：月历网格上标出完成的日子（每周题标出整周），下面是连续完成的期数
+    fn draw_calendar<G: Graphics, C>(
+        &self,
+        year: i64,
+        month: u32,
+        done: &[Date],
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        const MONTHS: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+        let period = self.config.online.period;
+        let today = Date::today();
+        let (current, longest) = daily::streaks(done, today, period);
+        let unit = match period {
+            Period::Daily => "days",
+            Period::Weekly => "weeks",
+        };
+        let first = Date::from_ymd(year, month, 1);
+        let next = if month == 12 {
+            Date::from_ymd(year + 1, 1, 1)
+        } else {
+            Date::from_ymd(year, month + 1, 1)
+        };
+        let lead = i64::from(first.weekday());
+        let weeks = (lead + next.0 - first.0 + 6) / 7;
+
+        // 星期一行与每周一行先留空，面板画好后再画网格
+        let mut lines = vec![format!("< {} {} >", MONTHS[month as usize - 1], year)];
+        lines.extend((0..=weeks).map(|_| String::new()));
+        lines.push(format!("Current streak: {} {}", current, unit));
+        lines.push(format!("Longest streak: {} {}", longest, unit));
+        lines.push("Esc: back".to_string());
+        self.draw_panel("Daily Streaks", &lines, None, glyphs, c, g);
+
+        let settings = &self.view.settings;
+        let [x, y, w, _] = self.panel_rect(lines.len());
+        let line_h = settings.scaled(PANEL_LINE);
+        let margin = settings.scaled(16.0);
+        let top = y + settings.scaled(PANEL_HEADER) + line_h;
+        let cell_w = (w - 2.0 * margin) / 7.0;
+        let size = settings.scaled(16.0) as u32;
+        let cell = |row: i64, col: i64| {
+            [
+                x + margin + col as f64 * cell_w + 2.0,
+                top + row as f64 * line_h,
+                cell_w - 4.0,
+                line_h - 4.0,
+            ]
+        };
+        for (col, name) in WEEKDAYS.iter().enumerate() {
+            text::draw_centered(
+                name,
+                size,
+                settings.text_color,
+                cell(0, col as i64),
+                glyphs,
+                c,
+                g,
+            );
+        }
+        for day in first.0..next.0 {
+            let date = Date(day);
+            let offset = lead + day - first.0;
+            let rect = cell(offset / 7 + 1, offset % 7);
+            if done.contains(&date.period_start(period)) {
+                Rectangle::new(settings.progress_verified_color).draw(
+                    rect,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            if date == today {
+                Rectangle::new_border(settings.board_edge_color, 1.0).draw(
+                    rect,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            text::draw_centered(
+                &(day - first.0 + 1).to_string(),
+                size,
+                settings.text_color,
+                rect,
+                glyphs,
+                c,
+                g,
+            );
+        }
+    }
+
+    /// 在窗口中央绘制带标题的面板，`selected` 行高亮
+    fn draw_panel<G: Graphics, C>(
+        &self,
+        title: &str,
+        lines: &[String],
+        selected: Option<usize>,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        use graphics::Rectangle;
+
+        let settings = &self.view.settings;
+        let title_size = settings.scaled(32.0) as u32;
+        let line_size = settings.scaled(18.0) as u32;
+        let line_h = settings.scaled(PANEL_LINE);
+        let header = settings.scaled(PANEL_HEADER);
+        let margin = settings.scaled(16.0);
+        let [x, y, w, h] = self.panel_rect(lines.len());
+        let center = x + w / 2.0;
+
+        Rectangle::new(settings.background_color).draw([x, y, w, h], &c.draw_state, c.transform, g);
+        Rectangle::new_border(settings.board_edge_color, 1.0).draw(
+            [x, y, w, h],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        text::draw_anchored(
+            title,
+            title_size,
+            settings.text_color,
+            [center, y + settings.scaled(52.0)],
+            Align::Center,
+            glyphs,
+            c,
+            g,
+        );
+        for (i, line) in lines.iter().enumerate() {
+            let top = y + header + i as f64 * line_h;
+            if selected == Some(i) {
+                Rectangle::new(settings.selected_cell_background_color).draw(
+                    [x + margin, top, w - 2.0 * margin, line_h - 4.0],
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            text::draw_centered(
+                line,
+                line_size,
+                settings.text_color,
+                [x, top, w, line_h - 4.0],
+                glyphs,
+                c,
+                g,
+            );
+        }
+    }
+}
+
+/// 一局历史记录的单行摘要
+/// 在后台线程中生成 `technique` 的练习局面（限时 `DRILL_BUDGET`）
+fn spawn_drill(technique: Technique) -> Receiver<Option<Drill>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(Drill::generate(technique, DRILL_BUDGET));
+    });
+    receiver
+}
+
+/// 循环切换到下一个 / 上一个难度
+fn cycle(difficulty: Difficulty, forward: bool) -> Difficulty {
+    let all = Difficulty::ALL;
+    let i = all.iter().position(|&d| d == difficulty).unwrap_or(0);
+    let i = if forward {
+        (i + 1) % all.len()
+    } else {
+        (i + all.len() - 1) % all.len()
+    };
+    all[i]
+}
+
+/// 关于界面的文字：`licenses` 时为字体许可证全文
+fn about_lines(licenses: bool) -> Vec<String> {
+    if licenses {
+        about::license_lines()
+    } else {
+        about::lines()
+    }
+}
+
+/// 最佳成绩表的一行：名次、用时、名字（可为空）与日期
+fn best_line(index: usize, game: &GameRecord) -> String {
+    let date = game.played_at.get(..10).unwrap_or(&game.played_at);
+    if game.name.is_empty() {
+        format!("{}. {}   {}", index + 1, format_time(game.seconds), date)
+    } else {
+        format!(
+            "{}. {}   {}   {}",
+            index + 1,
+            format_time(game.seconds),
+            game.name,
+            date
+        )
+    }
+}
+
+fn game_line(game: &GameRecord) -> String {
+    format!(
+        "{}  {}  {}  {}",
+        game.played_at.get(..16).unwrap_or(&game.played_at),
+        game.difficulty,
+        format_time(game.seconds),
+        if game.solved {
+            format!("{} mistakes", game.mistakes)
+        } else {
+            "unsolved".to_string()
+        }
+    )
+}
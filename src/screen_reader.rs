@@ -0,0 +1,453 @@
+//! 系统读屏软件接口：把 `accessibility.rs` 的节点树转换成 AccessKit 的树，交给各平台的适配层
+//! （Windows 的 UI Automation、macOS 的 NSAccessibility、Linux 的 AT-SPI），
+//! Narrator、VoiceOver、Orca 由此读出棋盘、选中格与底部按钮，并可以把焦点移到某格或按下按钮。
+//!
+//! 需要开启 `screen-reader` feature（默认开启）。Windows 与 macOS 上适配层挂在原生窗口上，
+//! 只支持 glutin 后端；Linux 上的 AT-SPI 不需要窗口句柄，两种后端都可以。
+//! macOS 的适配层还没有在 macOS 上测试过，要另外开启 `screen-reader-macos`。
+//! 适配层在读屏软件连接后才激活，之前不生成树。读屏软件请求的操作可能来自其它线程，
+//! 经通道交给主循环，由 `App` 在处理输入时执行。
+
+use crate::accessibility::Tree;
+#[cfg(feature = "screen-reader")]
+use crate::accessibility::{Role, FIRST_CELL};
+#[cfg(feature = "screen-reader")]
+use crate::action;
+use crate::action::Action;
+use crate::gameboard_view::GameboardView;
+use crate::window::AppWindow;
+#[cfg(feature = "screen-reader")]
+use piston::window::Window;
+#[cfg(feature = "screen-reader")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "screen-reader")]
+use std::sync::mpsc::{self, Receiver, Sender};
+#[cfg(feature = "screen-reader")]
+use std::sync::{Arc, Mutex};
+
+/// 读屏软件请求的操作
+#[cfg_attr(not(feature = "screen-reader"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Request {
+    /// 焦点移到格子 `[x, y]`
+    Select([usize; 2]),
+    /// 按下底部按钮
+    Press(Action),
+}
+
+/// 与平台适配层的连接；未开启 feature 或平台不支持时什么也不做
+#[derive(Default)]
+pub struct ScreenReader {
+    #[cfg(feature = "screen-reader")]
+    bridge: Option<Bridge>,
+}
+
+#[cfg(feature = "screen-reader")]
+struct Bridge {
+    adapter: platform::Adapter,
+    /// 最近一次发送的完整树，读屏软件（重新）连接时由适配层取用
+    latest: Arc<Mutex<Option<accesskit::TreeUpdate>>>,
+    /// 读屏软件是否已连接（适配层已激活）
+    active: Arc<AtomicBool>,
+    requests: Receiver<accesskit::ActionRequest>,
+    /// 最近一次发送的节点树与各节点位置，没有变化时不再发送
+    last: Option<(Tree, Vec<[f64; 4]>)>,
+    /// 窗口像素与界面坐标之比（高分屏上大于 1）
+    scale: f64,
+}
+
+impl ScreenReader {
+    /// 为刚创建的窗口接入平台适配层；Windows 上要求窗口还没有显示过，所以先隐藏再重新显示
+    pub fn attach(window: &mut AppWindow) -> Self {
+        #[cfg(feature = "screen-reader")]
+        {
+            let latest = Arc::new(Mutex::new(None));
+            let active = Arc::new(AtomicBool::new(false));
+            let (sender, requests) = mpsc::channel();
+            let handlers = Handlers {
+                latest: Arc::clone(&latest),
+                active: Arc::clone(&active),
+                requests: sender,
+            };
+            let scale = window.draw_size().width / window.size().width;
+            let bridge = platform::Adapter::new(window, handlers).map(|adapter| Bridge {
+                adapter,
+                latest,
+                active,
+                requests,
+                last: None,
+                scale: if scale.is_finite() && scale > 0.0 {
+                    scale
+                } else {
+                    1.0
+                },
+            });
+            ScreenReader { bridge }
+        }
+        #[cfg(not(feature = "screen-reader"))]
+        {
+            let _ = window;
+            ScreenReader::default()
+        }
+    }
+
+    /// 是否有读屏软件连接；连接时主循环保持低频刷新，以便及时执行读屏软件请求的操作。
+    /// Windows 与 macOS 的适配层不通知断开，连接过一次后一直视为连接
+    pub fn is_active(&self) -> bool {
+        #[cfg(feature = "screen-reader")]
+        if let Some(bridge) = &self.bridge {
+            return bridge.active.load(Ordering::Relaxed);
+        }
+        false
+    }
+
+    /// 窗口获得 / 失去焦点
+    pub fn set_focused(&mut self, focused: bool) {
+        #[cfg(feature = "screen-reader")]
+        if let Some(bridge) = &mut self.bridge {
+            bridge.adapter.set_focused(focused);
+        }
+        #[cfg(not(feature = "screen-reader"))]
+        let _ = focused;
+    }
+
+    /// 窗口大小改变：`scale` 为窗口像素与界面坐标之比
+    pub fn set_scale(&mut self, scale: f64) {
+        #[cfg(feature = "screen-reader")]
+        if let Some(bridge) = self.bridge.as_mut().filter(|_| scale > 0.0) {
+            if bridge.scale != scale {
+                bridge.scale = scale;
+                bridge.last = None;
+            }
+        }
+        #[cfg(not(feature = "screen-reader"))]
+        let _ = scale;
+    }
+
+    /// 取出读屏软件请求的操作
+    pub fn requests(&mut self) -> Vec<Request> {
+        #[cfg(feature = "screen-reader")]
+        if let Some(bridge) = &self.bridge {
+            return bridge.requests.try_iter().filter_map(request).collect();
+        }
+        Vec::new()
+    }
+
+    /// 把当前的节点树交给适配层（与上次相同时不发送）
+    pub fn update(&mut self, tree: &Tree, view: &GameboardView) {
+        #[cfg(feature = "screen-reader")]
+        if let Some(bridge) = &mut self.bridge {
+            let bounds = bounds(view);
+            if bridge
+                .last
+                .as_ref()
+                .is_some_and(|(last, last_bounds)| last == tree && *last_bounds == bounds)
+            {
+                return;
+            }
+            let update = tree_update(tree, &bounds, bridge.scale);
+            if let Ok(mut latest) = bridge.latest.lock() {
+                *latest = Some(update.clone());
+            }
+            bridge.adapter.update(update);
+            bridge.last = Some((tree.clone(), bounds));
+        }
+        #[cfg(not(feature = "screen-reader"))]
+        let _ = (tree, view);
+    }
+}
+
+/// 根节点（窗口）；`Tree::nodes[i]` 的 id 为 `i + 1`
+#[cfg(feature = "screen-reader")]
+const ROOT: accesskit::NodeId = accesskit::NodeId(0);
+
+#[cfg(feature = "screen-reader")]
+fn node_id(index: usize) -> accesskit::NodeId {
+    accesskit::NodeId(index as u64 + 1)
+}
+
+/// 每个节点在界面中的位置 [x, y, w, h]：棋盘、81 个格子、底部按钮
+#[cfg(feature = "screen-reader")]
+fn bounds(view: &GameboardView) -> Vec<[f64; 4]> {
+    let mut bounds = vec![view.board_rect()];
+    bounds.extend((0..81).map(|i| view.cell_rect([i % 9, i / 9])));
+    bounds.extend(view.button_rects());
+    bounds
+}
+
+/// 由节点树生成完整的 AccessKit 树
+#[cfg(feature = "screen-reader")]
+fn tree_update(tree: &Tree, bounds: &[[f64; 4]], scale: f64) -> accesskit::TreeUpdate {
+    use accesskit::{Action, Affine, Node, Rect};
+
+    let mut root = Node::new(accesskit::Role::Window);
+    root.set_label("Sudoku");
+    root.set_transform(Affine::scale(scale));
+    let mut nodes = Vec::with_capacity(tree.nodes.len() + 1);
+    for (i, node) in tree.nodes.iter().enumerate() {
+        let mut platform = Node::new(match node.role {
+            Role::Grid => accesskit::Role::Grid,
+            Role::Cell => accesskit::Role::Cell,
+            Role::Button => accesskit::Role::Button,
+        });
+        platform.set_label(node.name.as_str());
+        if !node.value.is_empty() {
+            platform.set_value(node.value.as_str());
+        }
+        if let Some(&[x, y, w, h]) = bounds.get(i) {
+            platform.set_bounds(Rect::new(x, y, x + w, y + h));
+        }
+        match node.role {
+            Role::Grid => {
+                platform.set_row_count(9);
+                platform.set_column_count(9);
+                platform.set_children(
+                    (FIRST_CELL..FIRST_CELL + 81)
+                        .map(node_id)
+                        .collect::<Vec<_>>(),
+                );
+            }
+            Role::Cell => {
+                platform.set_row_index((i - FIRST_CELL) / 9);
+                platform.set_column_index((i - FIRST_CELL) % 9);
+                platform.add_action(Action::Focus);
+            }
+            Role::Button => platform.add_action(Action::Click),
+        }
+        // 棋盘与按钮直接挂在窗口下，格子挂在棋盘下
+        if node.role != Role::Cell {
+            root.push_child(node_id(i));
+        }
+        nodes.push((node_id(i), platform));
+    }
+    nodes.push((ROOT, root));
+    let mut info = accesskit::Tree::new(ROOT);
+    info.toolkit_name = Some("piston".to_string());
+    accesskit::TreeUpdate {
+        nodes,
+        tree: Some(info),
+        focus: node_id(tree.focus),
+    }
+}
+
+/// 把适配层的请求换成界面中的操作；不支持的请求忽略
+#[cfg(feature = "screen-reader")]
+fn request(request: accesskit::ActionRequest) -> Option<Request> {
+    let index = (request.target.0 as usize).checked_sub(1)?;
+    let cell = index.checked_sub(FIRST_CELL).filter(|&i| i < 81);
+    match (request.action, cell) {
+        (accesskit::Action::Focus, Some(i)) => Some(Request::Select([i % 9, i / 9])),
+        (accesskit::Action::Click, None) => {
+            let button = index.checked_sub(FIRST_CELL + 81)?;
+            action::buttons().nth(button).map(Request::Press)
+        }
+        _ => None,
+    }
+}
+
+/// 适配层的回调：读屏软件连接时取最近的树，请求的操作经通道交给主循环
+#[cfg(feature = "screen-reader")]
+struct Handlers {
+    latest: Arc<Mutex<Option<accesskit::TreeUpdate>>>,
+    active: Arc<AtomicBool>,
+    requests: Sender<accesskit::ActionRequest>,
+}
+
+/// 读屏软件连接：还没有树时返回 None，由主循环在下一帧（保持刷新后）发送
+#[cfg(feature = "screen-reader")]
+struct Activation {
+    latest: Arc<Mutex<Option<accesskit::TreeUpdate>>>,
+    active: Arc<AtomicBool>,
+}
+
+#[cfg(feature = "screen-reader")]
+impl accesskit::ActivationHandler for Activation {
+    fn request_initial_tree(&mut self) -> Option<accesskit::TreeUpdate> {
+        self.active.store(true, Ordering::Relaxed);
+        self.latest.lock().ok()?.clone()
+    }
+}
+
+#[cfg(feature = "screen-reader")]
+struct Actions(Sender<accesskit::ActionRequest>);
+
+#[cfg(feature = "screen-reader")]
+impl accesskit::ActionHandler for Actions {
+    fn do_action(&mut self, request: accesskit::ActionRequest) {
+        // 主循环已退出时丢弃
+        let _ = self.0.send(request);
+    }
+}
+
+#[cfg(feature = "screen-reader")]
+struct Deactivation(Arc<AtomicBool>);
+
+#[cfg(feature = "screen-reader")]
+impl accesskit::DeactivationHandler for Deactivation {
+    fn deactivate_accessibility(&mut self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "screen-reader")]
+impl Handlers {
+    fn split(self) -> (Activation, Actions, Deactivation) {
+        (
+            Activation {
+                latest: self.latest,
+                active: Arc::clone(&self.active),
+            },
+            Actions(self.requests),
+            Deactivation(self.active),
+        )
+    }
+}
+
+/// Linux / BSD：AT-SPI，不需要窗口句柄；回调在适配层自己的线程中调用
+#[cfg(all(feature = "screen-reader", unix, not(target_os = "macos")))]
+mod platform {
+    use super::Handlers;
+    use crate::window::AppWindow;
+
+    pub struct Adapter(accesskit_unix::Adapter);
+
+    impl Adapter {
+        pub fn new(_window: &mut AppWindow, handlers: Handlers) -> Option<Self> {
+            let (activation, actions, deactivation) = handlers.split();
+            Some(Adapter(accesskit_unix::Adapter::new(
+                activation,
+                actions,
+                deactivation,
+            )))
+        }
+
+        pub fn update(&mut self, update: accesskit::TreeUpdate) {
+            self.0.update_if_active(|| update);
+        }
+
+        pub fn set_focused(&mut self, focused: bool) {
+            self.0.update_window_focus_state(focused);
+        }
+    }
+}
+
+/// Windows：UI Automation，子类化 glutin 窗口以响应 WM_GETOBJECT
+#[cfg(all(feature = "screen-reader", windows))]
+mod platform {
+    use super::Handlers;
+    use crate::window::AppWindow;
+    use piston::window::AdvancedWindow;
+
+    pub struct Adapter(accesskit_windows::SubclassingAdapter);
+
+    impl Adapter {
+        pub fn new(window: &mut AppWindow, handlers: Handlers) -> Option<Self> {
+            let hwnd = super::native_view(window)?;
+            let (activation, actions, _) = handlers.split();
+            // 适配层只能接入还没有显示过的窗口；此时事件循环还没有运行，隐藏又显示看不出来
+            window.hide();
+            let adapter = accesskit_windows::SubclassingAdapter::new(
+                accesskit_windows::HWND(hwnd),
+                activation,
+                actions,
+            );
+            window.show();
+            Some(Adapter(adapter))
+        }
+
+        pub fn update(&mut self, update: accesskit::TreeUpdate) {
+            if let Some(events) = self.0.update_if_active(|| update) {
+                events.raise();
+            }
+        }
+
+        pub fn set_focused(&mut self, _focused: bool) {}
+    }
+}
+
+/// macOS：NSAccessibility，子类化 glutin 窗口的内容视图。
+/// 还没有在 macOS 上测试过，只在另外开启 `screen-reader-macos` 时编译
+#[cfg(all(feature = "screen-reader-macos", target_os = "macos"))]
+mod platform {
+    use super::Handlers;
+    use crate::window::AppWindow;
+
+    pub struct Adapter(accesskit_macos::SubclassingAdapter);
+
+    impl Adapter {
+        pub fn new(window: &mut AppWindow, handlers: Handlers) -> Option<Self> {
+            let view = super::native_view(window)?;
+            let (activation, actions, _) = handlers.split();
+            // SAFETY: `view` 是窗口的内容视图，与窗口（及适配层所在的 `App`）一同存在到程序结束
+            let adapter =
+                unsafe { accesskit_macos::SubclassingAdapter::new(view, activation, actions) };
+            Some(Adapter(adapter))
+        }
+
+        pub fn update(&mut self, update: accesskit::TreeUpdate) {
+            if let Some(events) = self.0.update_if_active(|| update) {
+                events.raise();
+            }
+        }
+
+        pub fn set_focused(&mut self, focused: bool) {
+            if let Some(events) = self.0.update_view_focus_state(focused) {
+                events.raise();
+            }
+        }
+    }
+}
+
+/// 其它平台没有适配层（macOS 未开启 `screen-reader-macos` 时也是）
+#[cfg(all(
+    feature = "screen-reader",
+    not(any(
+        all(unix, not(target_os = "macos")),
+        windows,
+        all(feature = "screen-reader-macos", target_os = "macos"),
+    )),
+))]
+mod platform {
+    use super::Handlers;
+    use crate::window::AppWindow;
+
+    pub enum Adapter {}
+
+    impl Adapter {
+        pub fn new(_window: &mut AppWindow, _handlers: Handlers) -> Option<Self> {
+            None
+        }
+
+        pub fn update(&mut self, _update: accesskit::TreeUpdate) {
+            match *self {}
+        }
+
+        pub fn set_focused(&mut self, _focused: bool) {
+            match *self {}
+        }
+    }
+}
+
+/// 原生窗口（Windows 的 HWND、macOS 的 NSView）；sdl2 后端不提供，不接入
+#[cfg(all(
+    feature = "screen-reader",
+    any(windows, all(feature = "screen-reader-macos", target_os = "macos")),
+))]
+fn native_view(window: &AppWindow) -> Option<*mut std::ffi::c_void> {
+    #[cfg(feature = "glutin")]
+    {
+        use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+        match window.ctx.window().raw_window_handle() {
+            #[cfg(windows)]
+            RawWindowHandle::Windows(handle) => Some(handle.hwnd),
+            #[cfg(target_os = "macos")]
+            RawWindowHandle::MacOS(handle) => Some(handle.ns_view),
+            _ => None,
+        }
+    }
+    #[cfg(not(feature = "glutin"))]
+    {
+        let _ = window;
+        None
+    }
+}
@@ -0,0 +1,215 @@
+//! 脚本（`scripting` feature）：用 rhai 编写自定义辅助与自动解题宏，从脚本目录读取。
+//!
+//! `scripts/`（运行目录）与数据目录下 `scripts/` 中的每个 `.rhai` 文件是一个脚本，
+//! 在命令面板的 "Run script..." 中列出，每次运行都重新读取文件。脚本运行在沙箱中：
+//! 不能读写文件、联网或加载其它模块，运算步数、调用深度与字符串 / 数组大小都有上限。
+//!
+//! 脚本可以读取这些常量（行列从 0 开始）：
+//! - `cells`：`cells[row][col]` 为当前数字，0 为空格
+//! - `givens`：题面，格式同 `cells`
+//! - `candidates`：`candidates[row][col]` 为排除同行 / 列 / 宫后剩下的数字（已填的格子为空数组）
+//! - `selected`：选中格 `[row, col]`，未选中时为 `()`
+//!
+//! 脚本的值（最后一个表达式或 `return`）是它提出的走法：
+//! `#{row: 2, col: 4, value: 7, reason: "..."}`、这样的数组（宏一次提出多步）、
+//! 一段文字（只显示）或 `()`（没有建议）。走法由玩家确认后才写入棋盘，可以逐步撤销。
+//! ```text
+//! for row in 0..9 {
+//!     for col in 0..9 {
+//!         if candidates[row][col].len() == 1 {
+//!             return #{row: row, col: col, value: candidates[row][col][0], reason: "last digit"};
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::gameboard_controller::GameboardController;
+use crate::paths;
+use rhai::{Array, Dynamic, Engine, Map, Scope, INT};
+use std::fs;
+use std::path::{Path, PathBuf};
+use sudoku_core::{LogicSolver, SIZE};
+
+/// 脚本文件的扩展名
+const EXTENSION: &str = "rhai";
+
+/// 一次运行最多执行的运算步数（防止死循环卡住界面）
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+/// 脚本目录中的一个脚本
+#[derive(Clone, Debug)]
+pub struct Script {
+    /// 文件名（不含扩展名）
+    pub name: String,
+    path: PathBuf,
+}
+
+/// 脚本提出的一步：在 `cell` = [x, y] 填入 `value`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub cell: [usize; 2],
+    pub value: u8,
+    pub reason: String,
+}
+
+/// 脚本运行的结果
+#[derive(Clone, Debug)]
+pub enum Output {
+    Moves(Vec<Move>),
+    Message(String),
+}
+
+/// 默认的脚本目录（存在的才会读取）
+pub fn default_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("scripts")];
+    if let Some(dir) = paths::data_dir() {
+        dirs.push(dir.join("scripts"));
+    }
+    dirs
+}
+
+/// 列出默认目录中的全部脚本，按名字排序；同名脚本只保留第一个
+pub fn discover() -> Vec<Script> {
+    let mut scripts: Vec<Script> = Vec::new();
+    for dir in default_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let Some(name) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            if !scripts.iter().any(|s| s.name == name) {
+                scripts.push(Script { name, path });
+            }
+        }
+    }
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    scripts
+}
+
+impl Script {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 在沙箱中对当前盘面运行脚本
+    pub fn run(&self, controller: &GameboardController) -> Result<Output, String> {
+        let source = fs::read_to_string(&self.path)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+        let engine = sandbox();
+        let ast = engine.compile(&source).map_err(|e| e.to_string())?;
+        let mut scope = board_scope(controller);
+        let value: Dynamic = engine
+            .eval_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| e.to_string())?;
+        output(value, controller)
+    }
+}
+
+/// 没有文件、网络与模块访问，并限制资源用量的引擎；`print` / `debug` 输出到终端
+fn sandbox() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 64);
+    engine.set_max_string_size(10_000);
+    engine.set_max_array_size(10_000);
+    engine.set_max_map_size(1_000);
+    engine.disable_symbol("eval");
+    engine.disable_symbol("import");
+    engine.on_print(|text| println!("[脚本] {}", text));
+    engine.on_debug(|text, _, pos| println!("[脚本] {:?}: {}", pos, text));
+    engine
+}
+
+/// 盘面常量：`cells`、`givens`、`candidates` 与 `selected`
+fn board_scope(controller: &GameboardController) -> Scope<'static> {
+    let grid = |cells: &[[u8; SIZE]; SIZE]| -> Array {
+        cells
+            .iter()
+            .map(|row| Dynamic::from_array(row.iter().map(|&v| Dynamic::from(v as INT)).collect()))
+            .collect()
+    };
+    let solver = LogicSolver::new(&controller.gameboard);
+    let candidates: Array = (0..SIZE)
+        .map(|row| {
+            let cells = (0..SIZE).map(|col| {
+                let mask = solver.candidates((row, col));
+                let digits = (1..=9).filter(|d| mask & (1 << d) != 0);
+                Dynamic::from_array(digits.map(|d| Dynamic::from(d as INT)).collect())
+            });
+            Dynamic::from_array(cells.collect())
+        })
+        .collect();
+    let selected = controller.selected_cell.map_or(Dynamic::UNIT, |[x, y]| {
+        Dynamic::from_array(vec![Dynamic::from(y as INT), Dynamic::from(x as INT)])
+    });
+
+    let mut scope = Scope::new();
+    scope.push_constant("cells", grid(&controller.gameboard.cells));
+    scope.push_constant("givens", grid(&controller.initial_cells));
+    scope.push_constant("candidates", candidates);
+    scope.push_constant_dynamic("selected", selected);
+    scope
+}
+
+/// 把脚本的值转成走法；每一步都必须落在空的、可编辑的格子上
+fn output(value: Dynamic, controller: &GameboardController) -> Result<Output, String> {
+    if value.is_unit() {
+        return Ok(Output::Moves(Vec::new()));
+    }
+    if value.is_string() {
+        return Ok(Output::Message(value.to_string()));
+    }
+    let items = if value.is_array() {
+        value.cast::<Array>()
+    } else {
+        vec![value]
+    };
+    let moves = items
+        .into_iter()
+        .map(|item| {
+            let map = item.try_cast::<Map>().ok_or(
+                "a script must return #{row, col, value}, an array of them, a string or ()",
+            )?;
+            parse_move(&map, controller)
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Output::Moves(moves))
+}
+
+fn parse_move(map: &Map, controller: &GameboardController) -> Result<Move, String> {
+    let int = |key: &str| {
+        map.get(key)
+            .and_then(|v| v.as_int().ok())
+            .ok_or_else(|| format!("move is missing an integer {:?}", key))
+    };
+    let (row, col, value) = (int("row")?, int("col")?, int("value")?);
+    if !(0..SIZE as INT).contains(&row) || !(0..SIZE as INT).contains(&col) {
+        return Err(format!("row and col must be 0-8, got {}, {}", row, col));
+    }
+    if !(1..=9).contains(&value) {
+        return Err(format!("value must be 1-9, got {}", value));
+    }
+    let [x, y] = [col as usize, row as usize];
+    if controller.initial_cells[y][x] != 0 || controller.gameboard.cells[y][x] != 0 {
+        return Err(format!("r{}c{} is not an empty cell", y + 1, x + 1));
+    }
+    let reason = map
+        .get("reason")
+        .filter(|v| v.is_string())
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+    Ok(Move {
+        cell: [x, y],
+        value: value as u8,
+        reason,
+    })
+}
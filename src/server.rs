@@ -0,0 +1,321 @@
+//! HTTP 接口（`sudoku serve`）：把生成、求解、评级与校验以 JSON 提供给其它工具和网页前端。
+//!
+//! 只实现了够用的 HTTP/1.1 子集：每个连接处理一个请求后关闭。题目可以放在查询参数
+//! `puzzle` 中，也可以作为请求体（POST）发送，格式与命令行相同（81 字符题面、分享链接、
+//! `.ss` 网格或 CSV）：
+//! ```text
+//! GET  /generate?difficulty=hard&seed=42  → {"puzzle": "...", "solution": "...", "difficulty": "hard"}
+//! GET  /solve?puzzle=53..7....            → {"solution": "..."}
+//! POST /rate        （请求体为题目）       → {"difficulty": "medium", "hardest": "naked pair", "backdoor": 1, ...}
+//! POST /validate    （请求体为盘面）       → {"conflicts": ["r1c2", ...], "solutions": "unique", ...}
+//! ```
+//! `/rate?unique_rectangles=1` 让求解器也用唯一矩形（只对唯一解的题目成立），
+//! `/rate?backdoor=1` 才计算后门大小（搜索较慢，与 `rate --backdoor` 相同默认不算）。
+//! `/solve` 与 `/rate` 只接受至少 17 个提示数、有唯一解的题目，避免稀疏或恶意的盘面长时间占用线程。
+//! 出错时返回 4xx 状态码与 `{"error": "..."}`。
+
+use crate::share;
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+use serde::Serialize;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+use sudoku_core::{backdoor_size, rate_with, Difficulty, Gameboard, Gem, MIN_CLUES, RATED_BUDGET};
+
+/// 默认端口
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// 请求体的最大长度（字节），题目文本远小于此
+const MAX_BODY: usize = 64 * 1024;
+
+/// 读取请求的超时，防止空闲连接一直占着线程
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 在 `bind:port` 上监听，每个连接一个线程；只在无法监听时返回
+pub fn run(bind: &str, port: u16) -> io::Result<()> {
+    let listener = TcpListener::bind((bind, port))?;
+    println!("HTTP 接口已启动: http://{}", listener.local_addr()?);
+    for stream in listener.incoming().flatten() {
+        thread::spawn(move || {
+            if let Err(err) = handle(stream) {
+                eprintln!("HTTP 连接出错: {}", err);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// 读一个请求、写回响应
+fn handle(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(());
+    };
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let (status, body) = if length > MAX_BODY {
+        error(413, "request body too large")
+    } else {
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        respond(method, target, &String::from_utf8_lossy(&body))
+    };
+
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
+
+/// 按路径分派，返回状态码与 JSON 响应体
+fn respond(method: &str, target: &str, body: &str) -> (u16, String) {
+    if method != "GET" && method != "POST" {
+        return error(405, "only GET and POST are supported");
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let param = |key: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| decode(v))
+    };
+    if path == "/generate" {
+        return generate(param("difficulty"), param("seed"));
+    }
+    let input = param("puzzle").unwrap_or_else(|| body.trim().to_string());
+    let puzzle = match path {
+        "/solve" | "/rate" | "/validate" if input.is_empty() => {
+            return error(400, "missing puzzle (query parameter or request body)")
+        }
+        "/solve" | "/rate" | "/validate" => match share::parse(&input) {
+            Ok(puzzle) => puzzle,
+            Err(err) => return error(400, &err),
+        },
+        _ => {
+            return error(
+                404,
+                "unknown endpoint (/generate, /solve, /rate, /validate)",
+            )
+        }
+    };
+    if path == "/validate" {
+        return validate(&puzzle);
+    }
+    if let Err(response) = check_unique(&puzzle) {
+        return response;
+    }
+    let flag = |key: &str| param(key).is_some_and(|v| v != "0");
+    match path {
+        "/solve" => solve(&puzzle),
+        _ => rating(&puzzle, flag("unique_rectangles"), flag("backdoor")),
+    }
+}
+
+/// 求解与评级前的检查：提示数不少于 `MIN_CLUES`、没有冲突且解唯一。
+/// 解的计数每次先试候选数最少的格子，这些盘面上很快
+fn check_unique(puzzle: &Gameboard) -> Result<(), (u16, String)> {
+    if puzzle.filled_count() < MIN_CLUES {
+        return Err(error(
+            422,
+            &format!("too few clues (at least {} needed)", MIN_CLUES),
+        ));
+    }
+    match puzzle.count_solutions(2) {
+        0 => Err(error(422, "no solution")),
+        1 => Ok(()),
+        _ => Err(error(422, "puzzle has more than one solution")),
+    }
+}
+
+#[derive(Serialize)]
+struct Generated {
+    puzzle: String,
+    solution: String,
+    /// 实际评级；限时内没生成目标难度时与请求的不同
+    difficulty: Difficulty,
+}
+
+/// 按难度评级生成一题（与游戏中的新题相同，最多尝试 `RATED_BUDGET`）
+fn generate(difficulty: Option<String>, seed: Option<String>) -> (u16, String) {
+    let target = match difficulty.as_deref().map(str::parse::<Difficulty>) {
+        None => Difficulty::default(),
+        Some(Ok(target)) => target,
+        Some(Err(err)) => return error(400, &err),
+    };
+    let (puzzle, rated) = match seed.as_deref().map(str::parse::<u64>) {
+        None => Gameboard::generate_rated_with_rng(target, RATED_BUDGET, &mut thread_rng()),
+        Some(Ok(seed)) => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            Gameboard::generate_rated_with_rng(target, RATED_BUDGET, &mut rng)
+        }
+        Some(Err(_)) => return error(400, "seed must be a non-negative integer"),
+    };
+    let solution = puzzle
+        .first_solution()
+        .map(Gameboard::from_cells)
+        .expect("generated puzzles have a solution");
+    json(&Generated {
+        puzzle: puzzle.to_line(),
+        solution: solution.to_line(),
+        difficulty: rated,
+    })
+}
+
+#[derive(Serialize)]
+struct Solved {
+    solution: String,
+}
+
+/// 题目已由 `check_unique` 确认有唯一解
+fn solve(puzzle: &Gameboard) -> (u16, String) {
+    match puzzle.first_solution() {
+        Some(cells) => json(&Solved {
+            solution: Gameboard::from_cells(cells).to_line(),
+        }),
+        None => error(422, "no solution"),
+    }
+}
+
+#[derive(Serialize)]
+struct Rated {
+    difficulty: Difficulty,
+    /// 用到的最难技巧；题目已填满时为 null
+    hardest: Option<&'static str>,
+    solved_by_logic: bool,
+    steps: usize,
+    /// "pearl"、"diamond"；普通题目为 null
+    gem: Option<Gem>,
+    /// 后门大小：只靠唯一数（naked / hidden single）解完之前最少要猜的格子数；
+    /// 没有请求（`backdoor=1`）或超过 `MAX_BACKDOOR` 时为 null
+    backdoor: Option<usize>,
+}
+
+/// `unique_rectangles` 时求解器也用唯一矩形（只对唯一解的题目成立）；
+/// `backdoor` 时才计算后门大小
+fn rating(puzzle: &Gameboard, unique_rectangles: bool, backdoor: bool) -> (u16, String) {
+    let rating = rate_with(puzzle, unique_rectangles);
+    json(&Rated {
+        difficulty: rating.difficulty,
+        hardest: rating.hardest.map(|t| t.name()),
+        solved_by_logic: rating.solved_by_logic,
+        steps: rating.steps,
+        gem: rating.gem,
+        backdoor: if backdoor {
+            backdoor_size(puzzle)
+        } else {
+            None
+        },
+    })
+}
+
+#[derive(Serialize)]
+struct Validation {
+    /// 与同行 / 列 / 宫重复的格子，如 "r3c5"
+    conflicts: Vec<String>,
+    filled: usize,
+    complete: bool,
+    /// "none"、"unique" 或 "multiple"；有冲突时为 "none"
+    solutions: &'static str,
+}
+
+/// 与 `sudoku check` 相同的检查
+fn validate(puzzle: &Gameboard) -> (u16, String) {
+    let conflicts: Vec<String> = puzzle
+        .conflicting_cells()
+        .iter()
+        .map(|&(r, c)| format!("r{}c{}", r + 1, c + 1))
+        .collect();
+    let solutions = if !conflicts.is_empty() {
+        "none"
+    } else {
+        match puzzle.count_solutions(2) {
+            0 => "none",
+            1 => "unique",
+            _ => "multiple",
+        }
+    };
+    json(&Validation {
+        conflicts,
+        filled: puzzle.filled_count(),
+        complete: puzzle.is_complete(),
+        solutions,
+    })
+}
+
+fn json<T: Serialize>(value: &T) -> (u16, String) {
+    let body = serde_json::to_string(value).expect("response types always serialize");
+    (200, body)
+}
+
+#[derive(Serialize)]
+struct Error<'a> {
+    error: &'a str,
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    let body = serde_json::to_string(&Error { error: message }).expect("strings always serialize");
+    (status, body)
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        _ => "Error",
+    }
+}
+
+/// 查询参数的百分号解码（`+` 为空格）
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
@@ -0,0 +1,121 @@
+//! 分享链接：把题面压缩成分享码放进链接，发到聊天里点一下就能打开同一道题。
+//!
+//! 默认生成 `sudoku://puzzle/<分享码>`（需要系统把 `sudoku://` 关联到本程序，
+//! 链接作为命令行参数传入）；在配置文件 `[online]` 中设置 `share_url` 后改为网页链接，
+//! 其中 `{code}` 替换为分享码，例如 `https://example.org/sudoku?p={code}`。
+//! 分享码的格式见 `sudoku_core::formats`。
+//!
+//! 读取时接受上述两种链接以及复制时可选的全部文本格式（分享码、81 字符题面、网格、`.ss`、CSV）。
+//! 游戏中 Ctrl+C 选择格式后复制当前题目（链接或 `sudoku_core::Format` 中的文本格式），
+//! Ctrl+V 打开剪贴板中的链接或题面（未开启 `clipboard` feature 时复制只打印到终端，粘贴不可用）。
+
+use crate::daily::OnlineConfig;
+#[cfg(feature = "clipboard")]
+use copypasta::{ClipboardContext, ClipboardProvider};
+use sudoku_core::{Format, Gameboard};
+
+/// 自定义协议的链接前缀
+pub const SCHEME: &str = "sudoku://";
+
+/// 题目的分享链接
+pub fn link(config: &OnlineConfig, puzzle: &Gameboard) -> String {
+    let code = puzzle.to_share_code();
+    if config.share_url.is_empty() {
+        format!("{}puzzle/{}", SCHEME, code)
+    } else {
+        config.share_url.replace("{code}", &code)
+    }
+}
+
+/// 复制题目时可选的格式：分享链接（`None`）加上全部文本格式
+pub fn choices() -> impl Iterator<Item = Option<Format>> {
+    std::iter::once(None).chain(Format::ALL.iter().copied().map(Some))
+}
+
+/// 格式选择列表中的名称
+pub fn label(choice: Option<Format>) -> &'static str {
+    match choice {
+        None => "Share link",
+        Some(Format::Line) => "81-character line",
+        Some(Format::Grid) => "Grid",
+        Some(Format::SimpleSudoku) => "Simple Sudoku (.ss)",
+        Some(Format::Csv) => "CSV",
+        Some(Format::ShareCode) => "Share code",
+    }
+}
+
+/// 按所选格式写出题目
+pub fn export(config: &OnlineConfig, puzzle: &Gameboard, choice: Option<Format>) -> String {
+    match choice {
+        None => link(config, puzzle),
+        Some(format) => puzzle.to_format(format),
+    }
+}
+
+/// 从链接、分享码、81 字符题面、`.ss` 网格或 CSV 中读出题目
+pub fn parse(text: &str) -> Result<Gameboard, String> {
+    let text = text.trim();
+    let board = Gameboard::from_line(text)
+        .or_else(|_| Gameboard::from_ss(text))
+        .or_else(|_| Gameboard::from_csv(text));
+    if let Ok(board) = board {
+        return Ok(board);
+    }
+    let code = match text.split_once("://") {
+        Some((_, rest)) => code_in_url(rest).ok_or("link contains no puzzle")?,
+        None => text,
+    };
+    Gameboard::from_share_code(code).map_err(|e| format!("invalid share code: {}", e))
+}
+
+/// 链接中的分享码：查询参数 `p` / `puzzle`，否则为路径的最后一段
+fn code_in_url(rest: &str) -> Option<&str> {
+    let rest = rest.split('#').next().unwrap_or(rest);
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let param = query
+        .split('&')
+        .find_map(|pair| match pair.split_once('=') {
+            Some(("p" | "puzzle", value)) => Some(value),
+            _ => None,
+        });
+    param.or_else(|| path.rsplit('/').find(|s| !s.is_empty()))
+}
+
+/// 系统剪贴板（首次使用时打开）。
+/// X11 下复制的内容由持有剪贴板的程序提供，所以要一直保留，不能用完即弃。
+#[derive(Default)]
+pub struct Clipboard {
+    #[cfg(feature = "clipboard")]
+    context: Option<ClipboardContext>,
+}
+
+#[cfg(feature = "clipboard")]
+impl Clipboard {
+    fn context(&mut self) -> Result<&mut ClipboardContext, String> {
+        if self.context.is_none() {
+            self.context = Some(ClipboardContext::new().map_err(|e| e.to_string())?);
+        }
+        Ok(self.context.as_mut().unwrap())
+    }
+
+    pub fn copy(&mut self, text: String) -> Result<(), String> {
+        self.context()?
+            .set_contents(text)
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn paste(&mut self) -> Result<String, String> {
+        self.context()?.get_contents().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+impl Clipboard {
+    pub fn copy(&mut self, _text: String) -> Result<(), String> {
+        Err("built without the `clipboard` feature".to_string())
+    }
+
+    pub fn paste(&mut self) -> Result<String, String> {
+        Err("built without the `clipboard` feature".to_string())
+    }
+}
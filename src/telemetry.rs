@@ -0,0 +1,155 @@
+//! 使用统计：在配置文件中设置 `telemetry = true` 后（默认关闭），把汇总的游玩数据记在本机，
+//! 统计界面中显示；按 E 查看文件位置，再按 C 才把文件内容复制给别人（例如附在问题反馈里）。
+//!
+//! 只记录各难度生成的题目数与解题的总用时 / 次数，不含题面、名字、时间或任何可识别身份的信息，
+//! 也从不自动上传。文件为 JSON，位于平台数据目录（Linux 为 `~/.local/share/sudoku/telemetry.json`），
+//! 可以直接打开查看或删除：
+//! ```json
+//! {
+//!   "version": 1,
+//!   "generated": { "hard": 4, "medium": 12 },
+//!   "solved": { "medium": { "count": 9, "seconds": 3820.5 } }
+//! }
+//! ```
+//! 按题量（`--holes`）而非难度生成的题目记为 `random`。
+
+use crate::gameboard_controller::format_time;
+use crate::paths;
+use crate::persist;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use sudoku_core::Difficulty;
+
+/// 统计文件名
+const TELEMETRY_FILE: &str = "telemetry.json";
+
+/// 统计文件格式版本
+const TELEMETRY_VERSION: u32 = 1;
+
+/// 不按难度生成的题目的键
+const RANDOM: &str = "random";
+
+/// 某个难度解出的局数与总用时
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Solves {
+    pub count: u32,
+    pub seconds: f64,
+}
+
+/// 汇总数据（键为难度名）
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Metrics {
+    pub version: u32,
+    pub generated: BTreeMap<String, u32>,
+    pub solved: BTreeMap<String, Solves>,
+}
+
+/// 开启时的统计（每次变化都写回文件）
+pub struct Telemetry {
+    path: PathBuf,
+    pub metrics: Metrics,
+}
+
+impl Telemetry {
+    /// 允许统计时读取已有的数据；无法确定数据目录或读取失败时为 None（打印原因，不覆盖原文件）
+    pub fn open(enabled: bool) -> Option<Self> {
+        if !enabled {
+            return None;
+        }
+        let path = paths::data_dir()?.join(TELEMETRY_FILE);
+        let metrics = match read(&path) {
+            Ok(metrics) => metrics,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Metrics::default(),
+            Err(err) => {
+                eprintln!("无法读取使用统计 {}，本次不记录: {}", path.display(), err);
+                return None;
+            }
+        };
+        Some(Telemetry { path, metrics })
+    }
+
+    /// 记一道新生成的题目；`target` 为 None 时按题量生成
+    pub fn generated(&mut self, target: Option<Difficulty>) {
+        let key = target.map_or(RANDOM, Difficulty::name);
+        *self.metrics.generated.entry(key.to_string()).or_default() += 1;
+        self.save();
+    }
+
+    /// 记一局解出的用时
+    pub fn solved(&mut self, difficulty: Difficulty, seconds: f64) {
+        let solves = self
+            .metrics
+            .solved
+            .entry(difficulty.name().to_string())
+            .or_default();
+        solves.count += 1;
+        solves.seconds += seconds;
+        self.save();
+    }
+
+    /// 统计文件的位置
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 统计文件的内容（复制分享用）
+    pub fn to_json(&self) -> String {
+        let metrics = Metrics {
+            version: TELEMETRY_VERSION,
+            ..self.metrics.clone()
+        };
+        serde_json::to_string_pretty(&metrics).expect("metrics always serialize")
+    }
+
+    /// 统计界面的文字
+    pub fn lines(&self) -> Vec<String> {
+        let generated: Vec<String> = self
+            .metrics
+            .generated
+            .iter()
+            .map(|(key, count)| format!("{} {}", key, count))
+            .collect();
+        let averages: Vec<String> = self
+            .metrics
+            .solved
+            .iter()
+            .filter(|(_, solves)| solves.count > 0)
+            .map(|(key, solves)| {
+                format!(
+                    "{} {}",
+                    key,
+                    format_time(solves.seconds / solves.count as f64)
+                )
+            })
+            .collect();
+        let join = |items: Vec<String>| {
+            if items.is_empty() {
+                "-".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        vec![
+            format!("Generated: {}", join(generated)),
+            format!("Average solve: {}", join(averages)),
+        ]
+    }
+
+    fn save(&self) {
+        if let Err(err) = persist::write_atomic(&self.path, self.to_json()) {
+            eprintln!("无法写入使用统计 {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+fn read(path: &Path) -> io::Result<Metrics> {
+    let text = fs::read_to_string(path)?;
+    let metrics: Metrics = serde_json::from_str(&text)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    persist::check_version("telemetry", metrics.version, TELEMETRY_VERSION)?;
+    Ok(metrics)
+}
@@ -0,0 +1,178 @@
+//! Text helpers: measure strings and draw them centered or anchored.
+//!
+//! All glyph / baseline math lives here so cell digits, button labels, the HUD
+//! and overlays position text the same way.
+
+use graphics::character::CharacterCache;
+use graphics::types::{Color, FontSize};
+use graphics::{Context, Graphics, Image, Text, Transformed};
+
+/// Horizontal alignment relative to the anchor point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Measured extents of a string (pixels).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Metrics {
+    /// Sum of glyph advances
+    pub width: f64,
+    /// Highest ink above the baseline
+    pub ascent: f64,
+    /// Lowest ink below the baseline
+    pub descent: f64,
+}
+
+impl Metrics {
+    /// Ink height (ascent + descent)
+    pub fn height(&self) -> f64 {
+        self.ascent + self.descent
+    }
+}
+
+/// Measure `text` at `size`. Missing glyphs are skipped.
+pub fn measure<C: CharacterCache>(glyphs: &mut C, size: FontSize, text: &str) -> Metrics {
+    let mut m = Metrics::default();
+    for ch in text.chars() {
+        if let Ok(glyph) = glyphs.character(size, ch) {
+            m.width += glyph.advance_width();
+            m.ascent = m.ascent.max(glyph.top());
+            m.descent = m.descent.max(glyph.atlas_size[1] - glyph.top());
+        }
+    }
+    m
+}
+
+/// Draw `text` with its baseline at `pos[1]`, aligned horizontally to `pos[0]`.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_anchored<G: Graphics, C>(
+    text: &str,
+    size: FontSize,
+    color: Color,
+    pos: [f64; 2],
+    align: Align,
+    glyphs: &mut C,
+    c: &Context,
+    g: &mut G,
+) where
+    C: CharacterCache<Texture = G::Texture>,
+{
+    let x = match align {
+        Align::Left => pos[0],
+        Align::Center => pos[0] - measure(glyphs, size, text).width / 2.0,
+        Align::Right => pos[0] - measure(glyphs, size, text).width,
+    };
+    let _ = Text::new_color(color, size).draw(
+        text,
+        glyphs,
+        &c.draw_state,
+        c.transform.trans(x, pos[1]),
+        g,
+    );
+}
+
+/// Draw `text` centered (by ink extents) inside `rect` = [x, y, w, h].
+pub fn draw_centered<G: Graphics, C>(
+    text: &str,
+    size: FontSize,
+    color: Color,
+    rect: [f64; 4],
+    glyphs: &mut C,
+    c: &Context,
+    g: &mut G,
+) where
+    C: CharacterCache<Texture = G::Texture>,
+{
+    let m = measure(glyphs, size, text);
+    let x = rect[0] + (rect[2] - m.width) / 2.0;
+    let baseline = rect[1] + (rect[3] - m.height()) / 2.0 + m.ascent;
+    let _ = Text::new_color(color, size).draw(
+        text,
+        glyphs,
+        &c.draw_state,
+        c.transform.trans(x, baseline),
+        g,
+    );
+}
+
+/// Positions of the digits 1..=9 centered in a square cell, measured once per
+/// font / cell size so drawing a digit is a single glyph lookup and image draw.
+/// A Wordoku board lays out its letters in place of the digits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DigitLayout {
+    size: FontSize,
+    cell_size: f64,
+    /// Character drawn for each digit
+    symbols: [char; 9],
+    /// Top-left of each digit's glyph image relative to the cell's top-left
+    offsets: [[f64; 2]; 9],
+}
+
+impl DigitLayout {
+    /// Measure `symbols` (the characters for 1..=9) at `size`, centered (by ink
+    /// extents) in a `cell_size` cell.
+    pub fn new<C: CharacterCache>(
+        glyphs: &mut C,
+        size: FontSize,
+        cell_size: f64,
+        symbols: [char; 9],
+    ) -> Self {
+        let mut offsets = [[0.0; 2]; 9];
+        for (&ch, offset) in symbols.iter().zip(offsets.iter_mut()) {
+            let mut buf = [0u8; 4];
+            let m = measure(glyphs, size, ch.encode_utf8(&mut buf));
+            if let Ok(glyph) = glyphs.character(size, ch) {
+                let x = (cell_size - m.width) / 2.0;
+                let baseline = (cell_size - m.height()) / 2.0 + m.ascent;
+                *offset = [x + glyph.left(), baseline - glyph.top()];
+            }
+        }
+        DigitLayout {
+            size,
+            cell_size,
+            symbols,
+            offsets,
+        }
+    }
+
+    /// Whether this layout was measured for `size`, `cell_size` and `symbols`.
+    pub fn matches(&self, size: FontSize, cell_size: f64, symbols: [char; 9]) -> bool {
+        self.size == size && self.cell_size == cell_size && self.symbols == symbols
+    }
+
+    /// Draw digit `val` (1..=9, as its symbol) in the cell whose top-left is `origin`; other values draw nothing.
+    pub fn draw<G: Graphics, C>(
+        &self,
+        val: u8,
+        color: Color,
+        origin: [f64; 2],
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        if !(1..=9).contains(&val) {
+            return;
+        }
+        let [dx, dy] = self.offsets[val as usize - 1];
+        if let Ok(glyph) = glyphs.character(self.size, self.symbols[val as usize - 1]) {
+            Image::new_color(color)
+                .src_rect([
+                    glyph.atlas_offset[0],
+                    glyph.atlas_offset[1],
+                    glyph.atlas_size[0],
+                    glyph.atlas_size[1],
+                ])
+                .draw(
+                    glyph.texture,
+                    &c.draw_state,
+                    c.transform.trans(origin[0] + dx, origin[1] + dy),
+                    g,
+                );
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! 终端（TUI）模式：用 crossterm 在终端中绘制棋盘并处理键盘输入。
+//!
+//! 与图形界面共用 `GameboardController` 的全部游戏逻辑，只替换输入与绘制。
+
+use crate::gameboard_controller::{Flash, GameboardController, InputAction};
+use crate::savefile;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{
+    Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::{cursor, execute, queue, terminal};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const HELP: &str = "方向键 移动  1-9 填写  0/Del 清除  u 撤销  y 重做  r 重置  g 新题";
+const HELP2: &str =
+    "h 提示  Enter 采纳提示  a 显示答案  c/C/v 检查本格/行列宫/全部  x 提交  s 保存  q 退出";
+
+/// 恢复终端状态（raw mode / 备用屏幕），即使中途出错也会执行
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// 运行终端模式，直到玩家按 q / Esc 退出
+pub fn run(controller: &mut GameboardController) -> io::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut out = io::stdout();
+    if controller.selected_cell.is_none() {
+        controller.selected_cell = Some([4, 4]);
+    }
+    let mut status = String::new();
+    let mut last_tick = Instant::now();
+
+    loop {
+        draw(&mut out, controller, &status)?;
+        // 每半秒刷新一次计时器
+        let ready = event::poll(Duration::from_millis(500))?;
+        controller.update(last_tick.elapsed().as_secs_f64());
+        last_tick = Instant::now();
+        if !ready {
+            continue;
+        }
+        let Event::Key(KeyEvent { code, kind, .. }) = event::read()? else {
+            continue;
+        };
+        if kind == KeyEventKind::Release {
+            continue;
+        }
+        status.clear();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('s') => {
+                let path = savefile::default_path();
+                status = match savefile::save(controller, &path) {
+                    Ok(()) => format!("已保存到 {}", path.display()),
+                    Err(err) => format!("保存失败: {}", err),
+                };
+            }
+            _ => {
+                if let Some(action) = key_action(code) {
+                    controller.apply(action);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 终端中按键对应的对局操作
+fn key_action(code: KeyCode) -> Option<InputAction> {
+    let action = match code {
+        KeyCode::Up => InputAction::MoveSelection(0, -1),
+        KeyCode::Down => InputAction::MoveSelection(0, 1),
+        KeyCode::Left => InputAction::MoveSelection(-1, 0),
+        KeyCode::Right => InputAction::MoveSelection(1, 0),
+        KeyCode::Char(ch @ '1'..='9') => InputAction::EnterDigit(ch as u8 - b'0'),
+        KeyCode::Char('0') | KeyCode::Char(' ') | KeyCode::Backspace | KeyCode::Delete => {
+            InputAction::ClearCell
+        }
+        KeyCode::Char('u') => InputAction::Undo,
+        KeyCode::Char('y') => InputAction::Redo,
+        KeyCode::Char('r') => InputAction::Reset,
+        KeyCode::Char('g') => InputAction::NewPuzzle,
+        KeyCode::Char('h') => InputAction::Hint,
+        KeyCode::Enter => InputAction::ConfirmHint,
+        KeyCode::Char('a') => InputAction::ToggleShowAll,
+        KeyCode::Char('c') => InputAction::CheckCell,
+        KeyCode::Char('C') => InputAction::CheckUnit,
+        KeyCode::Char('v') => InputAction::CheckAll,
+        KeyCode::Char('x') => InputAction::Submit,
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// 棋盘格 (x, y) 在终端中的列 / 行
+fn cell_origin(x: usize, y: usize) -> (u16, u16) {
+    let col = 2 + x * 2 + (x / 3) * 2;
+    let row = 2 + y + y / 3;
+    (col as u16, row as u16)
+}
+
+fn draw<W: Write>(out: &mut W, controller: &GameboardController, status: &str) -> io::Result<()> {
+    queue!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0),
+        SetAttribute(Attribute::Bold),
+        Print("Sudoku"),
+        SetAttribute(Attribute::Reset),
+    )?;
+
+    // 边框
+    let seg = "───────";
+    for i in 0..13u16 {
+        let text = match i {
+            0 => format!("┌{seg}┬{seg}┬{seg}┐"),
+            4 | 8 => format!("├{seg}┼{seg}┼{seg}┤"),
+            12 => format!("└{seg}┴{seg}┴{seg}┘"),
+            _ => "│       │       │       │".to_string(),
+        };
+        queue!(out, cursor::MoveTo(0, i + 1), Print(text))?;
+    }
+
+    for y in 0..9 {
+        for x in 0..9 {
+            let (col, row) = cell_origin(x, y);
+            let val = controller.gameboard.cells[y][x];
+            // 单词数独显示字母（输入仍用数字键）
+            let symbol = |v: u8| controller.gameboard.symbol(v).unwrap_or('.');
+            let given = controller.initial_cells[y][x] != 0;
+            let (ch, color) = if given {
+                (symbol(val), Color::White)
+            } else if controller.show_all {
+                match controller.solution {
+                    Some(solved) => (symbol(solved[y][x]), Color::Cyan),
+                    None => ('.', Color::DarkGrey),
+                }
+            } else if val != 0 {
+                let wrong = controller.invalid_cells.contains([x, y]);
+                let color = if controller.wrong.contains([x, y]) {
+                    Color::Magenta
+                } else if controller.checked.contains([x, y]) || controller.submitted && !wrong {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                (symbol(val), color)
+            } else if let Some((_, hv)) = controller.hint.filter(|h| h.0 == [x, y]) {
+                (symbol(hv), Color::Blue)
+            } else {
+                ('.', Color::DarkGrey)
+            };
+
+            queue!(out, cursor::MoveTo(col, row), SetForegroundColor(color))?;
+            if given {
+                queue!(out, SetAttribute(Attribute::Bold))?;
+            }
+            // 闪烁提示用背景色标出（代替选中格的反色）
+            match controller.flash.filter(|f| f.0 == [x, y]) {
+                Some((_, flash, _)) => {
+                    let bg = match flash {
+                        Flash::Warning => Color::Yellow,
+                        Flash::Rejected => Color::Red,
+                    };
+                    queue!(
+                        out,
+                        SetForegroundColor(Color::Black),
+                        SetBackgroundColor(bg)
+                    )?;
+                }
+                None if controller.selected_cell == Some([x, y]) => {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+                None => {}
+            }
+            queue!(out, Print(ch), SetAttribute(Attribute::Reset), ResetColor)?;
+        }
+    }
+
+    let mut progress = format!(
+        "{}  {}/81",
+        controller.elapsed_text(),
+        controller.filled_count()
+    );
+    if let Some(ok) = controller.verified_count() {
+        progress.push_str(&format!("  Correct {}", ok));
+    }
+    if let Some(fallback) = controller.fallback_text() {
+        progress.push_str(&format!("  {}", fallback));
+    }
+    if controller.pending.is_some() {
+        progress.push_str("  生成新题中…");
+    }
+    queue!(
+        out,
+        cursor::MoveTo(0, 15),
+        Print(progress),
+        cursor::MoveTo(0, 16),
+        Print(controller.digit_counts_text()),
+        cursor::MoveTo(0, 17),
+        Print(status),
+        cursor::MoveTo(0, 18),
+        SetForegroundColor(Color::DarkGrey),
+        Print(HELP),
+        cursor::MoveTo(0, 19),
+        Print(HELP2),
+        ResetColor,
+    )?;
+    out.flush()
+}
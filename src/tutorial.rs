@@ -0,0 +1,110 @@
+//! 新手教程：在一道固定的简单题上用浮动提示框一步步介绍选格、填数、提示与检查。
+//!
+//! 每一步有一段说明和完成条件，玩家完成后自动进入下一步；提交或换到其它题目时教程结束。
+//! 笔记（候选数标记）还没有实现，教程中暂不包含这一步。
+
+use crate::config::KeyBindings;
+use crate::gameboard_controller::GameboardController;
+
+/// 教程用的题目（简单，唯一解）
+pub const PUZZLE: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+/// 教程的步骤，按顺序进行
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Select,
+    Enter,
+    Hint,
+    ConfirmHint,
+    Check,
+    Finish,
+}
+
+impl Step {
+    const ALL: [Step; 6] = [
+        Step::Select,
+        Step::Enter,
+        Step::Hint,
+        Step::ConfirmHint,
+        Step::Check,
+        Step::Finish,
+    ];
+
+    /// 提示框中的说明（两行，界面字体只含西文字符）
+    fn text(self, keys: &KeyBindings) -> [String; 2] {
+        match self {
+            Step::Select => [
+                "Click an empty cell to select it,".to_string(),
+                "or move around with the arrow keys.".to_string(),
+            ],
+            Step::Enter => [
+                "Type a digit 1-9 to fill the cell in.".to_string(),
+                "Red means it clashes with its row, column or box.".to_string(),
+            ],
+            Step::Hint => [
+                format!("Stuck? Press {:?} for a hint:", keys.hint),
+                "the easiest cell lights up in blue.".to_string(),
+            ],
+            Step::ConfirmHint => [
+                format!("Press {:?} or click the blue digit", keys.confirm_hint),
+                format!("to fill it in ({:?} hides the hint).", keys.hint),
+            ],
+            Step::Check => [
+                format!("Press {:?} to check the selected cell,", keys.check_cell),
+                format!("or {:?} to check every entry so far.", keys.check_all),
+            ],
+            Step::Finish => [
+                "That's it! Finish the puzzle, then".to_string(),
+                format!("press {:?} to submit your solution.", keys.submit),
+            ],
+        }
+    }
+
+    /// 这一步是否已经完成
+    fn done(self, controller: &GameboardController) -> bool {
+        match self {
+            Step::Select => controller
+                .selected_cell
+                .is_some_and(|[x, y]| controller.initial_cells[y][x] == 0),
+            Step::Enter => !controller.changes.is_empty(),
+            Step::Hint => controller.hint.is_some(),
+            Step::ConfirmHint => controller.hint.is_none(),
+            Step::Check => !controller.checked.is_empty() || !controller.wrong.is_empty(),
+            Step::Finish => controller.submitted,
+        }
+    }
+}
+
+/// 进行中的教程
+#[derive(Clone, Debug, Default)]
+pub struct Tutorial {
+    /// 当前步骤在 `Step::ALL` 中的位置
+    step: usize,
+}
+
+impl Tutorial {
+    pub fn step(&self) -> Step {
+        Step::ALL[self.step]
+    }
+
+    /// "Tutorial 2/6"
+    pub fn progress(&self) -> String {
+        format!("Tutorial {}/{}", self.step + 1, Step::ALL.len())
+    }
+
+    pub fn text(&self, keys: &KeyBindings) -> [String; 2] {
+        self.step().text(keys)
+    }
+
+    /// 当前步骤完成时进入下一步（一次操作可能同时完成几步）；返回 false 表示教程结束
+    pub fn advance(&mut self, controller: &GameboardController) -> bool {
+        while self.step().done(controller) {
+            if self.step + 1 == Step::ALL.len() {
+                return false;
+            }
+            self.step += 1;
+        }
+        true
+    }
+}
@@ -0,0 +1,88 @@
+//! 检查更新：在配置文件 `[online]` 中设置 `check_updates = true` 后，启动时在后台查询 GitHub
+//! 上最新发布的版本，比当前版本新时在窗口底部显示一条提示（点击或按 Esc 关闭）。
+//!
+//! 只读取发布的 `tag_name`（如 `v0.2.0`，前面的 `v` 可有可无），不上传任何数据；
+//! 无法联网或未开启 `online` feature 时只在终端打印原因，窗口中不显示提示。
+
+use crate::daily::OnlineConfig;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// 最新发布的查询地址
+#[cfg(feature = "online")]
+const RELEASES_URL: &str = "https://api.github.com/repos/dubyann/Sudoku/releases/latest";
+
+/// 下载超时（秒）
+#[cfg(feature = "online")]
+const TIMEOUT_SECS: u64 = 5;
+
+/// 后台进行中的检查
+pub struct UpdateCheck {
+    receiver: Receiver<Result<String, String>>,
+}
+
+impl UpdateCheck {
+    /// 允许检查时在后台查询最新版本
+    pub fn start(config: &OnlineConfig) -> Option<Self> {
+        if !config.check_updates {
+            return None;
+        }
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(latest());
+        });
+        Some(UpdateCheck { receiver })
+    }
+
+    /// 检查完成时返回 `Some`：有更新时为新版本号；失败时打印原因
+    pub fn poll(&self) -> Option<Option<String>> {
+        match self.receiver.try_recv() {
+            Ok(Ok(tag)) => Some(is_newer(&tag, env!("CARGO_PKG_VERSION")).then_some(tag)),
+            Ok(Err(err)) => {
+                eprintln!("无法检查更新: {}", err);
+                Some(None)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+/// `tag` 的版本号是否比 `current` 新（按点分隔的数字逐段比较，无法解析时视为不新）
+fn is_newer(tag: &str, current: &str) -> bool {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        let version = version.trim().trim_start_matches('v');
+        // 忽略预发布与构建后缀（`1.2.0-beta`、`1.2.0+abc`）
+        let core = version.split(['-', '+']).next()?;
+        core.split('.').map(|part| part.parse().ok()).collect()
+    };
+    match (parse(tag), parse(current)) {
+        (Some(tag), Some(current)) => tag > current,
+        _ => false,
+    }
+}
+
+#[cfg(feature = "online")]
+fn latest() -> Result<String, String> {
+    #[derive(serde::Deserialize)]
+    struct Release {
+        tag_name: String,
+    }
+
+    let text = ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(TIMEOUT_SECS))
+        .build()
+        .get(RELEASES_URL)
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    let release: Release = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    Ok(release.tag_name)
+}
+
+#[cfg(not(feature = "online"))]
+fn latest() -> Result<String, String> {
+    Err("built without the `online` feature".to_string())
+}
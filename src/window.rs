@@ -0,0 +1,52 @@
+//! 窗口后端抽象：通过 feature 选择 glutin_window（默认）或 sdl2_window。
+//!
+//! 主循环只依赖 piston 的 `Window` / `AdvancedWindow` trait（`Events::next`
+//! 对任何后端都适用），因此切换后端只需改变这里的 `AppWindow`。
+
+use piston::window::{AdvancedWindow, BuildFromWindowSettings, Window, WindowSettings};
+use std::error::Error;
+
+#[cfg(not(any(feature = "glutin", feature = "sdl2")))]
+compile_error!("enable a window backend feature: `glutin` (default) or `sdl2`");
+
+/// 可用作游戏窗口的后端
+pub trait WindowBackend: Window + AdvancedWindow + BuildFromWindowSettings {
+    /// 后端名称（用于错误信息）
+    const NAME: &'static str;
+
+    /// 根据设置创建窗口
+    fn create(settings: WindowSettings) -> Result<Self, Box<dyn Error>> {
+        check_display()?;
+        settings.build()
+    }
+}
+
+/// 在 Linux/BSD 上先检查是否有图形环境：没有显示服务器时窗口库会直接 abort，
+/// 无法返回错误
+fn check_display() -> Result<(), Box<dyn Error>> {
+    let needs_display = cfg!(all(unix, not(target_os = "macos")));
+    let has_display = ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()));
+    if needs_display && !has_display {
+        return Err("没有检测到图形环境（DISPLAY / WAYLAND_DISPLAY 未设置）".into());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "glutin")]
+impl WindowBackend for glutin_window::GlutinWindow {
+    const NAME: &'static str = "glutin";
+}
+
+#[cfg(feature = "sdl2")]
+impl WindowBackend for sdl2_window::Sdl2Window {
+    const NAME: &'static str = "sdl2";
+}
+
+/// 当前编译选择的窗口类型（两个 feature 同时开启时优先 glutin）
+#[cfg(feature = "glutin")]
+pub type AppWindow = glutin_window::GlutinWindow;
+
+#[cfg(all(feature = "sdl2", not(feature = "glutin")))]
+pub type AppWindow = sdl2_window::Sdl2Window;
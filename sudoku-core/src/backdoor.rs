@@ -0,0 +1,48 @@
+//! Backdoor size: how many cells must be guessed before naked and hidden
+//! singles alone finish a puzzle.
+//!
+//! This is an advanced metric next to the technique rating. Two puzzles that
+//! both defeat the logic solver can still differ in whether one lucky guess
+//! cracks them or three are needed. Guesses are always the solution's digits,
+//! so the search only has to choose *which* cells to reveal.
+
+use crate::gameboard::{Gameboard, SIZE};
+use crate::logic::{LogicSolver, Pos};
+
+/// Largest backdoor `backdoor_size` looks for; the search grows combinatorially.
+pub const MAX_BACKDOOR: usize = 3;
+
+/// The fewest cells that must be filled in (with their solution digits) before
+/// singles finish the puzzle; 0 if singles already do.
+/// `None` if the puzzle has no unique solution or needs more than `MAX_BACKDOOR`.
+pub fn backdoor_size(board: &Gameboard) -> Option<usize> {
+    let solution = board.unique_solution()?;
+    // singles never need a guess, so only cells they leave open are worth revealing
+    let mut start = LogicSolver::new(board);
+    start.fill_singles();
+    let open: Vec<Pos> = (0..SIZE)
+        .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+        .filter(|&(r, c)| start.cells()[r][c] == 0)
+        .collect();
+    (0..=MAX_BACKDOOR).find(|&size| reveals(&start, &open, &solution, size))
+}
+
+/// Whether revealing `size` of the `open` cells (in order, without repeats) lets
+/// singles finish the puzzle from `solver`.
+fn reveals(solver: &LogicSolver, open: &[Pos], solution: &[[u8; SIZE]; SIZE], size: usize) -> bool {
+    if solver.is_solved() {
+        return true;
+    }
+    if size == 0 {
+        return false;
+    }
+    open.iter().enumerate().any(|(i, &(r, c))| {
+        if solver.cells()[r][c] != 0 {
+            return false;
+        }
+        let mut next = solver.clone();
+        next.place((r, c), solution[r][c]);
+        next.fill_singles();
+        reveals(&next, &open[i + 1..], solution, size - 1)
+    })
+}
@@ -0,0 +1,72 @@
+//! Difficulty levels.
+
+use crate::generator::DEFAULT_HOLES;
+use std::fmt;
+use std::str::FromStr;
+
+/// Puzzle difficulty. Currently mapped to a number of holes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// Number of empty cells for this level.
+    pub fn holes(self) -> usize {
+        match self {
+            Difficulty::Easy => 32,
+            Difficulty::Medium => DEFAULT_HOLES,
+            Difficulty::Hard => 48,
+            Difficulty::Expert => 54,
+        }
+    }
+
+    /// Position in `ALL`, from 0 (easy) to 3 (expert).
+    pub fn level(self) -> usize {
+        self as usize
+    }
+
+    /// Lowercase name, as accepted by `FromStr`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .iter()
+            .copied()
+            .find(|d| d.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown difficulty {:?} (easy, medium, hard, expert)", s))
+    }
+}
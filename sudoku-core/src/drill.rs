@@ -0,0 +1,105 @@
+//! Technique drills: positions where exactly one technique makes progress.
+//!
+//! A drill is found by walking random puzzles forward with the logic solver
+//! and stopping at a position where the requested technique applies and no
+//! other one does. The answers are the placements the technique leads to, so
+//! drills work without pencil marks: elimination techniques are practised by
+//! finding the digit they unlock.
+
+use crate::gameboard::{Gameboard, SIZE};
+use crate::logic::{LogicSolver, Pos, Step, Technique};
+use rand::{thread_rng, Rng};
+use std::time::{Duration, Instant};
+
+/// Time allowed for finding a drill; rarer techniques may need several puzzles.
+pub const DRILL_BUDGET: Duration = Duration::from_secs(5);
+
+/// Holes in the puzzles drills are taken from.
+const DRILL_HOLES: usize = 54;
+
+/// A position to practise one technique on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Drill {
+    pub board: Gameboard,
+    pub technique: Technique,
+    /// The deduction the player is expected to see
+    pub step: Step,
+    /// Placements that count as a correct answer: `(pos, value)`
+    pub answers: Vec<(Pos, u8)>,
+}
+
+impl Drill {
+    /// Check whether `technique` is the only one making progress on `board`.
+    pub fn at(board: &Gameboard, technique: Technique) -> Option<Self> {
+        let solver = LogicSolver::new(board);
+        let others = Technique::ALL.iter().filter(|&&t| t != technique);
+        if others.clone().any(|&t| solver.find(t).is_some()) {
+            return None;
+        }
+        let step = solver.find(technique)?;
+        let answers = if step.placements.is_empty() {
+            // nothing else applied before, so every single afterwards is unlocked by the step
+            let mut after = solver.clone();
+            after.apply(&step);
+            after.singles()
+        } else {
+            solver.singles()
+        };
+        if answers.is_empty() {
+            return None;
+        }
+        Some(Drill {
+            board: board.clone(),
+            technique,
+            step,
+            answers,
+        })
+    }
+
+    /// Look for a drill in random puzzles for up to `budget`.
+    pub fn generate(technique: Technique, budget: Duration) -> Option<Self> {
+        Self::generate_with_rng(technique, budget, &mut thread_rng())
+    }
+
+    pub fn generate_with_rng<R: Rng>(
+        technique: Technique,
+        budget: Duration,
+        rng: &mut R,
+    ) -> Option<Self> {
+        let start = Instant::now();
+        while start.elapsed() < budget {
+            let puzzle = Gameboard::generate_with_rng(DRILL_HOLES, rng);
+            if let Some(drill) = Self::walk(puzzle, technique) {
+                return Some(drill);
+            }
+        }
+        None
+    }
+
+    /// Solve `board` placement by placement, stopping at the first drill position.
+    fn walk(mut board: Gameboard, technique: Technique) -> Option<Self> {
+        loop {
+            if let Some(drill) = Self::at(&board, technique) {
+                return Some(drill);
+            }
+            // eliminations are not part of the board, so run on to the next placement
+            let mut solver = LogicSolver::new(&board);
+            loop {
+                let step = solver.next_step()?;
+                solver.apply(&step);
+                if !step.placements.is_empty() {
+                    break;
+                }
+            }
+            board = Gameboard::from_cells(*solver.cells());
+            if board.is_complete() {
+                return None;
+            }
+        }
+    }
+
+    /// Whether placing `value` at `[x, y]` answers the drill.
+    pub fn is_answer(&self, [x, y]: [usize; 2], value: u8) -> bool {
+        x < SIZE && y < SIZE && self.answers.contains(&((y, x), value))
+    }
+}
@@ -0,0 +1,384 @@
+//! Text formats for boards.
+//!
+//! The canonical exchange format is a single 81-character line, row-major,
+//! with `1`-`9` for digits and `0` or `.` for empty cells.
+//!
+//! Simple Sudoku's `.ss` grid, common on forums, is also read and written:
+//! `.` or `X` for empty cells, with `|`, `-`, `+` and `*` drawing the boxes.
+//!
+//! ```text
+//! *-----------*
+//! |53.|.7.|...|
+//! |6..|195|...|
+//! |.98|...|.6.|
+//! |---+---+---|
+//! ...
+//! *-----------*
+//! ```
+//!
+//! For copying elsewhere a board can also be written as a spaced grid with
+//! `|` and `-` between the boxes (read back like an `.ss` grid) or as nine
+//! comma-separated rows with `0` for empty cells.
+//!
+//! The share code is a shorter, URL-safe form for links: digits stand for
+//! themselves and each letter `a`-`z` stands for a run of 1-26 empty cells,
+//! so a typical puzzle fits in about 50 characters.
+
+use crate::gameboard::{Gameboard, SIZE};
+use std::fmt;
+use std::str::FromStr;
+
+/// Error returned when a puzzle string can't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// Wrong number of cells (expected 81)
+    Length(usize),
+    /// Unexpected character at the given cell index
+    InvalidChar(usize, char),
+}
+
+/// Longest run of empty cells a single share-code letter can encode.
+const MAX_RUN: usize = 26;
+
+/// Characters that only draw the box borders of an `.ss` grid.
+const SS_BORDER: [char; 4] = ['|', '-', '+', '*'];
+
+/// Text formats a board can be written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// 81-character line
+    #[default]
+    Line,
+    /// Spaced grid with box separators
+    Grid,
+    /// Simple Sudoku `.ss` grid
+    SimpleSudoku,
+    /// Nine comma-separated rows
+    Csv,
+    /// Share code
+    ShareCode,
+}
+
+impl Format {
+    pub const ALL: [Format; 5] = [
+        Format::Line,
+        Format::Grid,
+        Format::SimpleSudoku,
+        Format::Csv,
+        Format::ShareCode,
+    ];
+
+    /// Short name, as accepted by `FromStr`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Line => "line",
+            Format::Grid => "grid",
+            Format::SimpleSudoku => "ss",
+            Format::Csv => "csv",
+            Format::ShareCode => "code",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .iter()
+            .copied()
+            .find(|f| f.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown format {:?} (line, grid, ss, csv, code)", s))
+    }
+}
+
+/// Read 81 cells from `chars`; `blank` decides which characters are empty cells.
+fn parse_cells(
+    chars: impl Iterator<Item = char>,
+    blank: impl Fn(char) -> bool,
+) -> Result<Gameboard, ParseError> {
+    let mut cells = [[0u8; SIZE]; SIZE];
+    let mut n = 0;
+    for ch in chars {
+        let val = match ch {
+            '1'..='9' => ch as u8 - b'0',
+            _ if blank(ch) => 0,
+            _ => return Err(ParseError::InvalidChar(n, ch)),
+        };
+        if n < SIZE * SIZE {
+            cells[n / SIZE][n % SIZE] = val;
+        }
+        n += 1;
+    }
+    if n != SIZE * SIZE {
+        return Err(ParseError::Length(n));
+    }
+    Ok(Gameboard::from_cells(cells))
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Length(n) => write!(f, "expected 81 cells, found {}", n),
+            ParseError::InvalidChar(i, ch) => {
+                write!(f, "invalid character {:?} at cell {}", ch, i + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Gameboard {
+    /// Parse an 81-character line. Whitespace is ignored so multi-line
+    /// grids of bare digits are accepted too.
+    pub fn from_line(s: &str) -> Result<Self, ParseError> {
+        let chars = s.chars().filter(|c| !c.is_whitespace());
+        parse_cells(chars, |ch| matches!(ch, '0' | '.'))
+    }
+
+    /// Parse a Simple Sudoku `.ss` grid. Border characters and whitespace
+    /// are skipped, so a bare 81-character line is accepted as well.
+    pub fn from_ss(s: &str) -> Result<Self, ParseError> {
+        let chars = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && !SS_BORDER.contains(c));
+        parse_cells(chars, |ch| matches!(ch, '0' | '.' | 'X' | 'x'))
+    }
+
+    /// Format as a Simple Sudoku `.ss` grid (`.` for empty cells, one line per row).
+    pub fn to_ss(&self) -> String {
+        let mut grid = String::from("*-----------*\n");
+        for (r, row) in self.cells.iter().enumerate() {
+            if r == 3 || r == 6 {
+                grid.push_str("|---+---+---|\n");
+            }
+            for (c, &v) in row.iter().enumerate() {
+                if c % 3 == 0 {
+                    grid.push('|');
+                }
+                grid.push(if v == 0 { '.' } else { (v + b'0') as char });
+            }
+            grid.push_str("|\n");
+        }
+        grid.push_str("*-----------*");
+        grid
+    }
+
+    /// Format as a spaced grid (`.` for empty cells, `|` and `-` between boxes).
+    pub fn to_grid(&self) -> String {
+        let rows: Vec<String> = self
+            .cells
+            .iter()
+            .map(|row| {
+                let boxes: Vec<String> = row
+                    .chunks(3)
+                    .map(|part| {
+                        let digits: Vec<String> = part
+                            .iter()
+                            .map(|&v| {
+                                if v == 0 {
+                                    ".".to_string()
+                                } else {
+                                    v.to_string()
+                                }
+                            })
+                            .collect();
+                        digits.join(" ")
+                    })
+                    .collect();
+                boxes.join(" | ")
+            })
+            .collect();
+        let bands: Vec<String> = rows.chunks(3).map(|band| band.join("\n")).collect();
+        bands.join("\n------+-------+------\n")
+    }
+
+    /// Format as nine comma-separated rows (`0` for empty cells).
+    pub fn to_csv(&self) -> String {
+        let rows: Vec<String> = self
+            .cells
+            .iter()
+            .map(|row| {
+                let values: Vec<String> = row.iter().map(u8::to_string).collect();
+                values.join(",")
+            })
+            .collect();
+        rows.join("\n")
+    }
+
+    /// Parse comma-separated values, one row per line; empty fields and `0`
+    /// are empty cells.
+    pub fn from_csv(s: &str) -> Result<Self, ParseError> {
+        let fields = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .flat_map(|line| line.split(','));
+        let mut chars = String::new();
+        for (n, field) in fields.enumerate() {
+            let mut field = field.trim().chars();
+            match (field.next(), field.next()) {
+                (None, _) => chars.push('0'),
+                (Some(ch), None) => chars.push(ch),
+                (Some(_), Some(ch)) => return Err(ParseError::InvalidChar(n, ch)),
+            }
+        }
+        parse_cells(chars.chars(), |ch| ch == '0')
+    }
+
+    /// Format in the given text format.
+    pub fn to_format(&self, format: Format) -> String {
+        match format {
+            Format::Line => self.to_line(),
+            Format::Grid => self.to_grid(),
+            Format::SimpleSudoku => self.to_ss(),
+            Format::Csv => self.to_csv(),
+            Format::ShareCode => self.to_share_code(),
+        }
+    }
+
+    /// Parse text written by [`Gameboard::to_format`].
+    pub fn from_format(s: &str, format: Format) -> Result<Self, ParseError> {
+        match format {
+            Format::Line => Self::from_line(s),
+            Format::Grid | Format::SimpleSudoku => Self::from_ss(s),
+            Format::Csv => Self::from_csv(s),
+            Format::ShareCode => Self::from_share_code(s),
+        }
+    }
+
+    /// Format as an 81-character line (`0` for empty cells).
+    pub fn to_line(&self) -> String {
+        self.cells
+            .iter()
+            .flatten()
+            .map(|&v| (v + b'0') as char)
+            .collect()
+    }
+
+    /// Format as a share code (see the module docs). Trailing empty cells
+    /// are written out too, so the code always covers all 81 cells.
+    pub fn to_share_code(&self) -> String {
+        let mut code = String::new();
+        let mut run = 0;
+        for &v in self.cells.iter().flatten() {
+            if v == 0 {
+                run += 1;
+                if run == MAX_RUN {
+                    code.push('z');
+                    run = 0;
+                }
+                continue;
+            }
+            if run > 0 {
+                code.push((b'a' + run as u8 - 1) as char);
+                run = 0;
+            }
+            code.push((v + b'0') as char);
+        }
+        if run > 0 {
+            code.push((b'a' + run as u8 - 1) as char);
+        }
+        code
+    }
+
+    /// Parse a share code produced by [`Gameboard::to_share_code`].
+    /// Letters are case-insensitive.
+    pub fn from_share_code(s: &str) -> Result<Self, ParseError> {
+        let mut cells = [[0u8; SIZE]; SIZE];
+        let mut n = 0;
+        for ch in s.trim().chars() {
+            let (val, count) = match ch.to_ascii_lowercase() {
+                '1'..='9' => (ch as u8 - b'0', 1),
+                c @ 'a'..='z' => (0, (c as u8 - b'a') as usize + 1),
+                _ => return Err(ParseError::InvalidChar(n, ch)),
+            };
+            for _ in 0..count {
+                if n < SIZE * SIZE {
+                    cells[n / SIZE][n % SIZE] = val;
+                }
+                n += 1;
+            }
+        }
+        if n != SIZE * SIZE {
+            return Err(ParseError::Length(n));
+        }
+        Ok(Self::from_cells(cells))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    #[test]
+    fn every_format_round_trips() {
+        let empty = Gameboard::from_cells([[0; SIZE]; SIZE]);
+        for board in [Gameboard::from_line(PUZZLE).unwrap(), empty] {
+            for format in Format::ALL {
+                let text = board.to_format(format);
+                assert_eq!(
+                    Gameboard::from_format(&text, format),
+                    Ok(board.clone()),
+                    "{} did not round-trip:\n{}",
+                    format,
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ss_accepts_x_and_dot_blanks_and_separator_rows() {
+        let grid = "\
+*-----------*
+|53X|.7.|xxx|
+|6..|195|...|
+|.98|...|.6.|
+|---+---+---|
+|8..|.6.|..3|
+|4..|8.3|..1|
+|7..|.2.|..6|
+|---+---+---|
+|.6.|...|28.|
+|...|419|..5|
+|...|.8.|.79|
+*-----------*
+";
+        assert_eq!(Gameboard::from_ss(grid), Gameboard::from_line(PUZZLE));
+    }
+
+    #[test]
+    fn csv_reads_empty_fields_as_blanks() {
+        let csv = "\
+5,3,,,7,,,,
+6,,,1,9,5,,,
+,9,8,,,,,6,
+
+8,,,,6,,,,3
+4,,,8,,3,,,1
+7,,,,2,,,,6
+,6,,,,,2,8,
+,,,4,1,9,,,5
+,,,,8,,,7,9
+";
+        assert_eq!(Gameboard::from_csv(csv), Gameboard::from_line(PUZZLE));
+    }
+
+    #[test]
+    fn csv_rejects_multi_character_fields() {
+        assert_eq!(
+            Gameboard::from_csv("5,31,0"),
+            Err(ParseError::InvalidChar(1, '1'))
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Board model: cell storage, indexing and move validation.
+
+use crate::peers::peers;
+
+/// Size of game board.
+pub const SIZE: usize = 9;
+
+/// Store game board information.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gameboard {
+    /// Stores the content of the cells, indexed `cells[row][col]`.
+    /// `0` is an empty cell.
+    pub cells: [[u8; SIZE]; SIZE],
+    /// Wordoku: the letter shown for each digit (`letters[d - 1]` for `d`).
+    /// `None` shows the digits themselves.
+    pub letters: Option<[char; SIZE]>,
+}
+
+impl Default for Gameboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gameboard {
+    /// Create a new, empty game board.
+    pub fn new() -> Self {
+        Self {
+            cells: [[0; SIZE]; SIZE],
+            letters: None,
+        }
+    }
+
+    /// Create a game board from an existing 9x9 cell array (`cells[row][col]`).
+    pub fn from_cells(cells: [[u8; SIZE]; SIZE]) -> Self {
+        Self {
+            cells,
+            letters: None,
+        }
+    }
+
+    /// Gets the character at cell location.
+    pub fn char(&self, ind: [usize; 2]) -> Option<char> {
+        // `ind` is [x, y] (column, row) in the rest of the codebase.
+        self.symbol(self.cells[ind[1]][ind[0]])
+    }
+
+    /// The character shown for `val`: its letter on a Wordoku board, else the digit.
+    pub fn symbol(&self, val: u8) -> Option<char> {
+        match (val, self.letters) {
+            (1..=9, Some(letters)) => Some(letters[val as usize - 1]),
+            (1..=9, None) => Some((val + b'0') as char),
+            _ => None,
+        }
+    }
+
+    /// The characters shown for 1..=9.
+    pub fn symbols(&self) -> [char; SIZE] {
+        self.letters
+            .unwrap_or(['1', '2', '3', '4', '5', '6', '7', '8', '9'])
+    }
+
+    /// The digit `ch` stands for (either case on a Wordoku board).
+    pub fn digit_of(&self, ch: char) -> Option<u8> {
+        match self.letters {
+            Some(letters) => letters
+                .iter()
+                .position(|&letter| letter.eq_ignore_ascii_case(&ch))
+                .map(|i| i as u8 + 1),
+            None => ch.to_digit(10).filter(|&d| d != 0).map(|d| d as u8),
+        }
+    }
+
+    /// Set cell value.
+    pub fn set(&mut self, ind: [usize; 2], val: u8) {
+        // interpret ind as [x, y]
+        self.cells[ind[1]][ind[0]] = val;
+    }
+
+    pub fn is_valid_move(&self, row: usize, col: usize, num: u8) -> bool {
+        // A cell is not its own peer, so the value at (row, col) is ignored
+        peers((row, col))
+            .iter()
+            .all(|&(r, c)| self.cells[r][c] != num)
+    }
+
+    /// Filled cells whose value repeats in their row, column or box, as `(row, col)`.
+    pub fn conflicting_cells(&self) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let v = self.cells[row][col];
+                if v != 0 && !self.is_valid_move(row, col, v) {
+                    out.push((row, col));
+                }
+            }
+        }
+        out
+    }
+
+    /// Number of filled cells.
+    pub fn filled_count(&self) -> usize {
+        self.cells.iter().flatten().filter(|&&v| v != 0).count()
+    }
+
+    /// Every cell filled and no conflicts.
+    pub fn is_complete(&self) -> bool {
+        self.filled_count() == SIZE * SIZE && self.conflicting_cells().is_empty()
+    }
+}
@@ -0,0 +1,230 @@
+//! Random puzzle generation: fill a full solution, then punch holes one at a
+//! time, putting back any given whose removal would allow a second solution.
+//!
+//! Difficulty-targeted generation repeats this and grades each candidate with
+//! `rate`, within a time budget so callers never wait long for a rare rating.
+//! Pearls and diamonds are grown from minimal puzzles instead (see
+//! `generate_gem_with_rng`).
+
+use crate::difficulty::Difficulty;
+use crate::gameboard::{Gameboard, SIZE};
+use crate::logic::{rate, Gem, LogicSolver, Technique};
+use crate::peers::peers;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+// Default number of holes (tweak to adjust difficulty)
+pub const DEFAULT_HOLES: usize = 40;
+
+/// Fewest givens a sudoku with a unique solution can have.
+pub const MIN_CLUES: usize = 17;
+
+/// Most holes a generated puzzle can have.
+pub const MAX_HOLES: usize = SIZE * SIZE - MIN_CLUES;
+
+/// Fresh solutions tried when punching holes stops short of the requested
+/// count; the one with the most holes is kept if none gets there.
+const HOLE_ATTEMPTS: usize = 10;
+
+/// Time allowed for difficulty-targeted generation before settling for the closest rating.
+pub const RATED_BUDGET: Duration = Duration::from_millis(500);
+
+/// Time allowed for looking for a pearl or diamond; one usually takes well under a second.
+pub const GEM_BUDGET: Duration = Duration::from_secs(5);
+
+impl Gameboard {
+    pub fn generate_random(holes: usize) -> Self {
+        Self::generate_with_rng(holes, &mut thread_rng())
+    }
+
+    /// Same as `generate_random`, but reproducible for a given seed.
+    pub fn generate_seeded(holes: usize, seed: u64) -> Self {
+        Self::generate_with_rng(holes, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Generate a puzzle with a unique solution and `holes` empty cells (at
+    /// most `MAX_HOLES`). Fewer only when no solution tried could lose that
+    /// many givens and stay unique: targets up to about 58 are met, higher
+    /// ones get as close as the best try.
+    pub fn generate_with_rng<R: Rng>(holes: usize, rng: &mut R) -> Self {
+        Self::generate_punched(holes, rng, Self::generate_full_solution)
+    }
+
+    /// Punch `holes` holes into solutions from `solution` until one has them all.
+    pub(crate) fn generate_punched<R: Rng>(
+        holes: usize,
+        rng: &mut R,
+        mut solution: impl FnMut(&mut R) -> Self,
+    ) -> Self {
+        let holes = holes.min(MAX_HOLES);
+        let mut best: Option<(Self, usize)> = None;
+        for _ in 0..HOLE_ATTEMPTS {
+            let mut board = solution(rng);
+            let removed = board.punch_holes(holes, rng);
+            if removed == holes {
+                return board;
+            }
+            if best.as_ref().is_none_or(|&(_, most)| removed > most) {
+                best = Some((board, removed));
+            }
+        }
+        best.expect("HOLE_ATTEMPTS is not zero").0
+    }
+
+    /// Empty up to `holes` givens in random order, putting back each one whose
+    /// removal would leave more than one solution. Returns how many were emptied.
+    fn punch_holes<R: Rng>(&mut self, holes: usize, rng: &mut R) -> usize {
+        let mut positions: Vec<(usize, usize)> = (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(r, c)| self.cells[r][c] != 0)
+            .collect();
+        positions.shuffle(rng);
+        let mut removed = 0;
+        for (r, c) in positions {
+            if removed == holes {
+                break;
+            }
+            let value = self.cells[r][c];
+            self.cells[r][c] = 0;
+            if self.has_unique_solution() {
+                removed += 1;
+            } else {
+                self.cells[r][c] = value;
+            }
+        }
+        removed
+    }
+
+    /// Generate a puzzle with `target.holes()` holes that rates as `target`.
+    /// Returns the puzzle and its rating, which differs from `target` when none was
+    /// found within `budget`: then the closest-rated candidate is used (easier on ties).
+    pub fn generate_rated(target: Difficulty, budget: Duration) -> (Self, Difficulty) {
+        Self::generate_rated_with_rng(target, budget, &mut thread_rng())
+    }
+
+    pub fn generate_rated_with_rng<R: Rng>(
+        target: Difficulty,
+        budget: Duration,
+        rng: &mut R,
+    ) -> (Self, Difficulty) {
+        let start = Instant::now();
+        let mut best: Option<(Self, Difficulty)> = None;
+        loop {
+            let board = Self::generate_with_rng(target.holes(), rng);
+            let rated = rate(&board).difficulty;
+            if rated == target {
+                return (board, rated);
+            }
+            let closer = best.as_ref().is_none_or(|&(_, best)| {
+                let key = |d: Difficulty| (distance(d, target), d.level());
+                key(rated) < key(best)
+            });
+            if closer {
+                best = Some((board, rated));
+            }
+            if start.elapsed() >= budget {
+                return best.expect("at least one candidate was generated");
+            }
+        }
+    }
+
+    /// Generate a puzzle that is at least a `gem` (a diamond also counts as a
+    /// pearl). `None` if none was found within `budget`.
+    ///
+    /// Gems are far too rare among random puzzles to find by filtering, so each
+    /// candidate is grown from a minimal puzzle that needs more than singles:
+    /// everything placed before its first hardest step becomes a given, which
+    /// makes the hardest technique the first one needed for a placement. Givens
+    /// the class does not depend on are then removed again.
+    pub fn generate_gem_with_rng<R: Rng>(gem: Gem, budget: Duration, rng: &mut R) -> Option<Self> {
+        let start = Instant::now();
+        loop {
+            let minimal = Self::generate_minimal_with_rng(rng);
+            if let Some(mut board) = advance_to_hardest(&minimal) {
+                let keeps = |board: &Self| rate(board).gem.is_some_and(|found| found >= gem);
+                if keeps(&board) {
+                    let mut givens: Vec<(usize, usize)> = (0..SIZE)
+                        .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+                        .filter(|&(r, c)| board.cells[r][c] != 0)
+                        .collect();
+                    givens.shuffle(rng);
+                    for (r, c) in givens {
+                        let value = board.cells[r][c];
+                        board.cells[r][c] = 0;
+                        if !board.has_unique_solution() || !keeps(&board) {
+                            board.cells[r][c] = value;
+                        }
+                    }
+                    return Some(board);
+                }
+            }
+            if start.elapsed() >= budget {
+                return None;
+            }
+        }
+    }
+
+    /// Remove givens in random order as long as the solution stays unique.
+    fn generate_minimal_with_rng<R: Rng>(rng: &mut R) -> Self {
+        let mut board = Self::generate_full_solution(rng);
+        board.punch_holes(MAX_HOLES, rng);
+        board
+    }
+
+    pub(crate) fn generate_full_solution<R: Rng>(rng: &mut R) -> Self {
+        let mut board = [[0u8; SIZE]; SIZE];
+        Self::fill_board(&mut board, rng);
+        Self::from_cells(board)
+    }
+
+    pub(crate) fn fill_board<R: Rng>(board: &mut [[u8; SIZE]; SIZE], rng: &mut R) -> bool {
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                if board[row][col] == 0 {
+                    let mut nums: Vec<u8> = (1..=9).collect();
+                    nums.shuffle(rng);
+                    for &num in &nums {
+                        if is_valid_static(board, row, col, num) {
+                            board[row][col] = num;
+                            if Self::fill_board(board, rng) {
+                                return true;
+                            }
+                            board[row][col] = 0;
+                        }
+                    }
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// `board` with every digit the logic solver places before its first hardest
+/// step filled in; `None` unless logic solves it with more than singles.
+fn advance_to_hardest(board: &Gameboard) -> Option<Gameboard> {
+    let mut solver = LogicSolver::new(board);
+    let steps = solver.run();
+    let hardest = steps.iter().map(|s| s.technique).max()?;
+    if !solver.is_solved() || hardest <= Technique::HiddenSingle {
+        return None;
+    }
+    let mut cells = board.cells;
+    for step in steps.iter().take_while(|s| s.technique != hardest) {
+        for &((r, c), value) in &step.placements {
+            cells[r][c] = value;
+        }
+    }
+    Some(Gameboard::from_cells(cells))
+}
+
+/// How many levels apart two difficulties are.
+fn distance(a: Difficulty, b: Difficulty) -> usize {
+    a.level().abs_diff(b.level())
+}
+
+fn is_valid_static(board: &[[u8; SIZE]; SIZE], row: usize, col: usize, num: u8) -> bool {
+    peers((row, col)).iter().all(|&(r, c)| board[r][c] != num)
+}
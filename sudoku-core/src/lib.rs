@@ -0,0 +1,28 @@
+//! Sudoku core library: board model, solver, generator and text formats.
+//!
+//! Shared by the Piston GUI and any other frontend; has no windowing deps.
+
+pub mod backdoor;
+pub mod difficulty;
+pub mod drill;
+pub mod formats;
+pub mod gameboard;
+pub mod generator;
+pub mod logic;
+pub mod peers;
+pub mod sandwich;
+pub mod solver;
+pub mod thermo;
+pub mod variant;
+pub mod wordoku;
+
+pub use crate::backdoor::{backdoor_size, MAX_BACKDOOR};
+pub use crate::difficulty::Difficulty;
+pub use crate::drill::{Drill, DRILL_BUDGET};
+pub use crate::formats::{Format, ParseError};
+pub use crate::gameboard::{Gameboard, SIZE};
+pub use crate::generator::{DEFAULT_HOLES, GEM_BUDGET, MAX_HOLES, MIN_CLUES, RATED_BUDGET};
+pub use crate::logic::{rate, rate_with, Gem, LogicSolver, Rating, Technique};
+pub use crate::sandwich::Sandwich;
+pub use crate::solver::SolveStats;
+pub use crate::variant::{Variant, VariantKind, VARIANT_BUDGET};
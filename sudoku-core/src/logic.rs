@@ -0,0 +1,744 @@
+//! Human-style logic solver and difficulty grading.
+//!
+//! Candidates are kept as a bitmask per cell (bit `n` set = digit `n` still
+//! possible). Techniques are tried from easiest to hardest; the hardest one a
+//! puzzle needs decides its rating.
+
+use crate::difficulty::Difficulty;
+use crate::gameboard::{Gameboard, SIZE};
+use crate::peers::{is_peer, peers, UNITS};
+use std::fmt;
+use std::str::FromStr;
+
+/// Cell position `(row, col)`.
+pub type Pos = (usize, usize);
+
+/// Solving techniques, ordered from easiest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    LockedCandidates,
+    NakedPair,
+    XWing,
+    UniqueRectangle,
+    XYWing,
+    Swordfish,
+    XYChain,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 9] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::LockedCandidates,
+        Technique::NakedPair,
+        Technique::XWing,
+        Technique::UniqueRectangle,
+        Technique::XYWing,
+        Technique::Swordfish,
+        Technique::XYChain,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "naked single",
+            Technique::HiddenSingle => "hidden single",
+            Technique::LockedCandidates => "locked candidates",
+            Technique::NakedPair => "naked pair",
+            Technique::XWing => "x-wing",
+            Technique::UniqueRectangle => "unique rectangle",
+            Technique::XYWing => "xy-wing",
+            Technique::Swordfish => "swordfish",
+            Technique::XYChain => "xy-chain",
+        }
+    }
+
+    /// Difficulty level implied by needing this technique.
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+            Technique::LockedCandidates | Technique::NakedPair => Difficulty::Medium,
+            Technique::XWing | Technique::UniqueRectangle | Technique::XYWing => Difficulty::Hard,
+            Technique::Swordfish | Technique::XYChain => Difficulty::Expert,
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One logical deduction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    /// Digits placed: `(pos, value)`
+    pub placements: Vec<(Pos, u8)>,
+    /// Candidates removed: `(pos, value)`
+    pub eliminations: Vec<(Pos, u8)>,
+    /// Cells the deduction is based on (for explanations / highlighting)
+    pub cause: Vec<Pos>,
+    /// The chain followed, in order; empty for techniques that are not chains
+    pub links: Vec<Link>,
+}
+
+/// One link of a chain between two candidates `(pos, digit)`. A strong link
+/// means "if `from` is not true, `to` is"; a weak one "if `from` is true, `to`
+/// is not".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Link {
+    pub from: (Pos, u8),
+    pub to: (Pos, u8),
+    pub strong: bool,
+}
+
+/// Result of grading a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    /// Hardest technique used; `None` if the puzzle was already complete
+    pub hardest: Option<Technique>,
+    /// Whether pure logic finished the puzzle (otherwise guessing is needed)
+    pub solved_by_logic: bool,
+    /// Number of steps taken
+    pub steps: usize,
+    /// Pearl / diamond class; `None` for ordinary puzzles
+    pub gem: Option<Gem>,
+}
+
+/// Connoisseur classes of puzzles that need more than singles. In a pearl the
+/// first placement already needs the hardest technique; in a diamond the very
+/// first step does, so every diamond is also a pearl.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Gem {
+    Pearl,
+    Diamond,
+}
+
+impl Gem {
+    pub const ALL: [Gem; 2] = [Gem::Pearl, Gem::Diamond];
+
+    /// Lowercase name, as accepted by `FromStr`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Gem::Pearl => "pearl",
+            Gem::Diamond => "diamond",
+        }
+    }
+}
+
+impl fmt::Display for Gem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Gem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Gem::ALL
+            .iter()
+            .copied()
+            .find(|g| g.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown class {:?} (pearl, diamond)", s))
+    }
+}
+
+const ALL_DIGITS: u16 = 0b11_1111_1110;
+
+fn bit(v: u8) -> u16 {
+    1 << v
+}
+
+fn digits(mask: u16) -> impl Iterator<Item = u8> {
+    (1..=9u8).filter(move |&v| mask & bit(v) != 0)
+}
+
+/// Every way to pick `k` of `items`, keeping their order.
+fn combinations(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for (i, &first) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, first);
+            all.push(rest);
+        }
+    }
+    all
+}
+
+/// Logic solver state: placed digits plus remaining candidates.
+#[derive(Clone)]
+pub struct LogicSolver {
+    cells: [[u8; SIZE]; SIZE],
+    cands: [[u16; SIZE]; SIZE],
+    /// Whether uniqueness-based techniques may be used (see `assume_unique`)
+    unique: bool,
+}
+
+impl LogicSolver {
+    pub fn new(board: &Gameboard) -> Self {
+        let mut solver = Self {
+            cells: [[0; SIZE]; SIZE],
+            cands: [[ALL_DIGITS; SIZE]; SIZE],
+            unique: false,
+        };
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                let v = board.cells[r][c];
+                if v != 0 {
+                    solver.place((r, c), v);
+                }
+            }
+        }
+        solver
+    }
+
+    /// Allow techniques that assume the puzzle has exactly one solution (unique
+    /// rectangles). They are off by default: on a puzzle with several solutions
+    /// they can remove the digits of every one of them.
+    pub fn assume_unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+
+    pub fn cells(&self) -> &[[u8; SIZE]; SIZE] {
+        &self.cells
+    }
+
+    /// Candidate bitmask at `pos` (bit `n` = digit `n`); 0 for filled cells.
+    pub fn candidates(&self, pos: Pos) -> u16 {
+        self.cands[pos.0][pos.1]
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells.iter().flatten().all(|&v| v != 0)
+    }
+
+    pub(crate) fn place(&mut self, pos: Pos, v: u8) {
+        self.cells[pos.0][pos.1] = v;
+        self.cands[pos.0][pos.1] = 0;
+        for &(r, c) in peers(pos) {
+            self.cands[r][c] &= !bit(v);
+        }
+    }
+
+    /// Apply a step produced by `next_step`.
+    pub fn apply(&mut self, step: &Step) {
+        for &(pos, v) in &step.placements {
+            self.place(pos, v);
+        }
+        for &((r, c), v) in &step.eliminations {
+            self.cands[r][c] &= !bit(v);
+        }
+    }
+
+    /// Find the easiest available deduction, if any.
+    pub fn next_step(&self) -> Option<Step> {
+        // an empty cell without candidates means the board is contradictory
+        let stuck = (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .any(|(r, c)| self.cells[r][c] == 0 && self.cands[r][c] == 0);
+        if stuck {
+            return None;
+        }
+        Technique::ALL.iter().find_map(|&t| self.find(t))
+    }
+
+    /// Look for a deduction using one specific technique.
+    pub fn find(&self, technique: Technique) -> Option<Step> {
+        match technique {
+            Technique::NakedSingle => self.naked_single(),
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::LockedCandidates => self.locked_candidates(),
+            Technique::NakedPair => self.naked_pair(),
+            Technique::XWing => self.fish(2, Technique::XWing),
+            Technique::UniqueRectangle if self.unique => self.unique_rectangle(),
+            Technique::UniqueRectangle => None,
+            Technique::XYWing => self.xy_wing(),
+            Technique::Swordfish => self.fish(3, Technique::Swordfish),
+            Technique::XYChain => self.xy_chain(),
+        }
+    }
+
+    /// Apply only the techniques that remove candidates without placing digits,
+    /// until none applies. Afterwards `candidates` holds what can be deduced
+    /// about each cell from the current placements alone.
+    pub fn eliminate(&mut self) {
+        let techniques = [Technique::LockedCandidates, Technique::NakedPair];
+        while let Some(step) = techniques.iter().find_map(|&t| self.find(t)) {
+            self.apply(&step);
+        }
+    }
+
+    /// Apply steps until solved or stuck; returns the steps taken.
+    pub fn run(&mut self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        while let Some(step) = self.next_step() {
+            self.apply(&step);
+            steps.push(step);
+        }
+        steps
+    }
+
+    /// Place naked and hidden singles, a whole batch per round, until none is left.
+    pub(crate) fn fill_singles(&mut self) {
+        loop {
+            let singles = self.singles();
+            if singles.is_empty() {
+                return;
+            }
+            for ((r, c), v) in singles {
+                if self.cells[r][c] == 0 {
+                    self.place((r, c), v);
+                }
+            }
+        }
+    }
+
+    /// Every placement a naked or hidden single allows right now.
+    pub(crate) fn singles(&self) -> Vec<(Pos, u8)> {
+        let mut singles = Vec::new();
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                let mask = self.cands[r][c];
+                if self.cells[r][c] == 0 && mask.count_ones() == 1 {
+                    singles.push(((r, c), mask.trailing_zeros() as u8));
+                }
+            }
+        }
+        for unit in &UNITS {
+            for v in 1..=9u8 {
+                let mut spots = unit
+                    .iter()
+                    .filter(|&&(r, c)| self.cands[r][c] & bit(v) != 0);
+                if let (Some(&pos), None) = (spots.next(), spots.next()) {
+                    if !singles.contains(&(pos, v)) {
+                        singles.push((pos, v));
+                    }
+                }
+            }
+        }
+        singles
+    }
+
+    fn naked_single(&self) -> Option<Step> {
+        for r in 0..SIZE {
+            for c in 0..SIZE {
+                let mask = self.cands[r][c];
+                if self.cells[r][c] == 0 && mask.count_ones() == 1 {
+                    let v = mask.trailing_zeros() as u8;
+                    return Some(Step {
+                        technique: Technique::NakedSingle,
+                        placements: vec![((r, c), v)],
+                        eliminations: Vec::new(),
+                        cause: vec![(r, c)],
+                        links: Vec::new(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn hidden_single(&self) -> Option<Step> {
+        for unit in &UNITS {
+            for v in 1..=9u8 {
+                let mut spots = unit
+                    .iter()
+                    .filter(|&&(r, c)| self.cands[r][c] & bit(v) != 0);
+                if let (Some(&pos), None) = (spots.next(), spots.next()) {
+                    return Some(Step {
+                        technique: Technique::HiddenSingle,
+                        placements: vec![(pos, v)],
+                        eliminations: Vec::new(),
+                        cause: unit.to_vec(),
+                        links: Vec::new(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn locked_candidates(&self) -> Option<Step> {
+        let units = &UNITS;
+        for (i, unit) in units.iter().enumerate() {
+            for v in 1..=9u8 {
+                let spots: Vec<Pos> = unit
+                    .iter()
+                    .copied()
+                    .filter(|&(r, c)| self.cands[r][c] & bit(v) != 0)
+                    .collect();
+                if spots.len() < 2 {
+                    continue;
+                }
+                // the other units every spot shares with each other
+                for (j, other) in units.iter().enumerate() {
+                    if i == j || !spots.iter().all(|p| other.contains(p)) {
+                        continue;
+                    }
+                    let eliminations: Vec<(Pos, u8)> = other
+                        .iter()
+                        .copied()
+                        .filter(|p| !unit.contains(p))
+                        .filter(|&(r, c)| self.cands[r][c] & bit(v) != 0)
+                        .map(|p| (p, v))
+                        .collect();
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique: Technique::LockedCandidates,
+                            placements: Vec::new(),
+                            eliminations,
+                            cause: spots,
+                            links: Vec::new(),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn naked_pair(&self) -> Option<Step> {
+        for unit in &UNITS {
+            for (a, &pa) in unit.iter().enumerate() {
+                let mask = self.cands[pa.0][pa.1];
+                if mask.count_ones() != 2 {
+                    continue;
+                }
+                for &pb in &unit[a + 1..] {
+                    if self.cands[pb.0][pb.1] != mask {
+                        continue;
+                    }
+                    let eliminations: Vec<(Pos, u8)> = unit
+                        .iter()
+                        .copied()
+                        .filter(|&p| p != pa && p != pb)
+                        .flat_map(|p| digits(self.cands[p.0][p.1] & mask).map(move |v| (p, v)))
+                        .collect();
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique: Technique::NakedPair,
+                            placements: Vec::new(),
+                            eliminations,
+                            cause: vec![pa, pb],
+                            links: Vec::new(),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// X-wing (`size` 2) and swordfish (`size` 3): `size` lines whose candidates
+    /// for a digit all fall on the same `size` cross lines claim the digit there.
+    fn fish(&self, size: usize, technique: Technique) -> Option<Step> {
+        // rows as bases eliminate from columns, then the same with the roles swapped
+        for by_rows in [true, false] {
+            let pos = |line: usize, i: usize| if by_rows { (line, i) } else { (i, line) };
+            for v in 1..=9u8 {
+                // candidate positions of `v` along each line, as a bitmask
+                let spots: Vec<u16> = (0..SIZE)
+                    .map(|line| {
+                        (0..SIZE)
+                            .filter(|&i| {
+                                let (r, c) = pos(line, i);
+                                self.cands[r][c] & bit(v) != 0
+                            })
+                            .fold(0, |mask, i| mask | 1 << i)
+                    })
+                    .collect();
+                let bases: Vec<usize> = (0..SIZE)
+                    .filter(|&line| (2..=size as u32).contains(&spots[line].count_ones()))
+                    .collect();
+                for lines in combinations(&bases, size) {
+                    let union = lines.iter().fold(0, |mask, &line| mask | spots[line]);
+                    if union.count_ones() as usize != size {
+                        continue;
+                    }
+                    let cross: Vec<usize> = (0..SIZE).filter(|&i| union & 1 << i != 0).collect();
+                    let eliminations: Vec<(Pos, u8)> = (0..SIZE)
+                        .filter(|line| !lines.contains(line))
+                        .flat_map(|line| cross.iter().map(move |&i| pos(line, i)))
+                        .filter(|&(r, c)| self.cands[r][c] & bit(v) != 0)
+                        .map(|p| (p, v))
+                        .collect();
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique,
+                            placements: Vec::new(),
+                            eliminations,
+                            cause: lines
+                                .iter()
+                                .flat_map(|&line| cross.iter().map(move |&i| pos(line, i)))
+                                .filter(|&(r, c)| self.cands[r][c] & bit(v) != 0)
+                                .collect(),
+                            links: Vec::new(),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Unique rectangle (types 1 and 2): four unsolved corners of a rectangle
+    /// spanning two boxes must not all be left with the same pair {a, b}, or the
+    /// pair could be swapped for a second solution.
+    fn unique_rectangle(&self) -> Option<Step> {
+        let cands = |(r, c): Pos| self.cands[r][c];
+        for r1 in 0..SIZE {
+            for r2 in r1 + 1..SIZE {
+                for c1 in 0..SIZE {
+                    for c2 in c1 + 1..SIZE {
+                        // exactly two boxes: the rows share a band or the columns a stack
+                        if (r1 / 3 == r2 / 3) == (c1 / 3 == c2 / 3) {
+                            continue;
+                        }
+                        let corners = [(r1, c1), (r1, c2), (r2, c1), (r2, c2)];
+                        if corners.iter().any(|&(r, c)| self.cells[r][c] != 0) {
+                            continue;
+                        }
+                        let pair = corners.iter().fold(ALL_DIGITS, |mask, &p| mask & cands(p));
+                        if pair.count_ones() != 2 {
+                            continue;
+                        }
+                        let extra: Vec<Pos> = corners
+                            .iter()
+                            .copied()
+                            .filter(|&p| cands(p) != pair)
+                            .collect();
+                        let eliminations: Vec<(Pos, u8)> = match extra[..] {
+                            // type 1: the only corner with more digits cannot be either of the pair
+                            [corner] => digits(pair).map(|v| (corner, v)).collect(),
+                            // type 2: two corners in one line share a single extra digit,
+                            // which must go in one of them
+                            [a, b]
+                                if cands(a) == cands(b)
+                                    && (cands(a) & !pair).count_ones() == 1
+                                    && (a.0 == b.0 || a.1 == b.1) =>
+                            {
+                                let extra = cands(a) & !pair;
+                                let v = extra.trailing_zeros() as u8;
+                                peers(a)
+                                    .iter()
+                                    .copied()
+                                    .filter(|&p| is_peer(p, b))
+                                    .filter(|&p| cands(p) & extra != 0)
+                                    .map(|p| (p, v))
+                                    .collect()
+                            }
+                            _ => continue,
+                        };
+                        if !eliminations.is_empty() {
+                            return Some(Step {
+                                technique: Technique::UniqueRectangle,
+                                placements: Vec::new(),
+                                eliminations,
+                                cause: corners.to_vec(),
+                                links: Vec::new(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// XY-wing: a pivot with candidates {x, y} sees two pincers {x, z} and {y, z};
+    /// whichever digit the pivot takes, one pincer is z, so cells seeing both
+    /// pincers cannot be z.
+    fn xy_wing(&self) -> Option<Step> {
+        let pairs: Vec<Pos> = (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(r, c)| self.cands[r][c].count_ones() == 2)
+            .collect();
+        let cands = |(r, c): Pos| self.cands[r][c];
+        for &pivot in &pairs {
+            let wings: Vec<Pos> = pairs
+                .iter()
+                .copied()
+                .filter(|&p| is_peer(pivot, p))
+                .filter(|&p| (cands(p) & cands(pivot)).count_ones() == 1)
+                .collect();
+            for (i, &a) in wings.iter().enumerate() {
+                for &b in &wings[i + 1..] {
+                    // the pincers share z, and each holds a different pivot digit
+                    let z = cands(a) & cands(b) & !cands(pivot);
+                    if z.count_ones() != 1 || cands(a) & cands(b) & cands(pivot) != 0 {
+                        continue;
+                    }
+                    let v = z.trailing_zeros() as u8;
+                    let eliminations: Vec<(Pos, u8)> = peers(a)
+                        .iter()
+                        .copied()
+                        .filter(|&p| p != pivot && is_peer(p, b))
+                        .filter(|&p| cands(p) & z != 0)
+                        .map(|p| (p, v))
+                        .collect();
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique: Technique::XYWing,
+                            placements: Vec::new(),
+                            eliminations,
+                            cause: vec![pivot, a, b],
+                            links: self.xy_links(&[a, pivot, b], v),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// XY-chain: bivalue cells, each seeing the next and sharing a digit with it.
+    /// If the first cell is not z it takes its other digit, which forces the
+    /// next cell, and so on until the last cell is left with z; one end is z, so
+    /// cells seeing both ends cannot be z. The shortest such chain is returned.
+    fn xy_chain(&self) -> Option<Step> {
+        let pairs: Vec<Pos> = (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .filter(|&(r, c)| self.cands[r][c].count_ones() == 2)
+            .collect();
+        let cands = |(r, c): Pos| self.cands[r][c];
+        let mut best: Option<Step> = None;
+        for &start in &pairs {
+            for z in digits(cands(start)) {
+                // breadth first over (cell, digit it is forced to), so the chain found
+                // from `start` is the shortest one
+                let mut nodes = vec![(start, cands(start) & !bit(z), usize::MAX)];
+                let mut seen = [[0u16; SIZE]; SIZE];
+                seen[start.0][start.1] = nodes[0].1;
+                let mut i = 0;
+                while i < nodes.len() {
+                    let (cell, forced, _) = nodes[i];
+                    for &next in &pairs {
+                        if next == start || !is_peer(cell, next) || cands(next) & forced == 0 {
+                            continue;
+                        }
+                        let next_forced = cands(next) & !forced;
+                        if seen[next.0][next.1] & next_forced != 0 {
+                            continue;
+                        }
+                        seen[next.0][next.1] |= next_forced;
+                        nodes.push((next, next_forced, i));
+                        if next_forced != bit(z) {
+                            continue;
+                        }
+                        let mut chain = vec![next];
+                        let mut at = i;
+                        while at != usize::MAX {
+                            chain.push(nodes[at].0);
+                            at = nodes[at].2;
+                        }
+                        // two cells are a naked pair, three an xy-wing
+                        if chain.len() < 4
+                            || best.as_ref().is_some_and(|b| b.cause.len() <= chain.len())
+                        {
+                            continue;
+                        }
+                        let eliminations: Vec<(Pos, u8)> = peers(start)
+                            .iter()
+                            .copied()
+                            .filter(|&p| is_peer(p, next))
+                            .filter(|&p| cands(p) & bit(z) != 0)
+                            .map(|p| (p, z))
+                            .collect();
+                        if !eliminations.is_empty() {
+                            chain.reverse();
+                            best = Some(Step {
+                                technique: Technique::XYChain,
+                                placements: Vec::new(),
+                                eliminations,
+                                links: self.xy_links(&chain, z),
+                                cause: chain,
+                            });
+                        }
+                    }
+                    i += 1;
+                }
+            }
+        }
+        best
+    }
+
+    /// Links of a chain through bivalue `cells` that starts by assuming the first
+    /// cell is not `z`: a strong link inside each cell, a weak one to the next.
+    fn xy_links(&self, cells: &[Pos], z: u8) -> Vec<Link> {
+        let mut links = Vec::new();
+        let mut off = z;
+        for (i, &(r, c)) in cells.iter().enumerate() {
+            let on = (self.cands[r][c] & !bit(off)).trailing_zeros() as u8;
+            links.push(Link {
+                from: ((r, c), off),
+                to: ((r, c), on),
+                strong: true,
+            });
+            if let Some(&next) = cells.get(i + 1) {
+                links.push(Link {
+                    from: ((r, c), on),
+                    to: (next, on),
+                    strong: false,
+                });
+            }
+            off = on;
+        }
+        links
+    }
+}
+
+/// Grade a puzzle by the hardest technique needed to solve it.
+pub fn rate(board: &Gameboard) -> Rating {
+    rate_with(board, false)
+}
+
+/// `rate`, optionally allowing uniqueness-based techniques (see
+/// `LogicSolver::assume_unique`).
+pub fn rate_with(board: &Gameboard, assume_unique: bool) -> Rating {
+    let mut solver = LogicSolver::new(board).assume_unique(assume_unique);
+    let steps = solver.run();
+    let hardest = steps.iter().map(|s| s.technique).max();
+    let solved_by_logic = solver.is_solved();
+    let difficulty = if !solved_by_logic {
+        Difficulty::Expert
+    } else {
+        hardest
+            .map(Technique::difficulty)
+            .unwrap_or(Difficulty::Easy)
+    };
+    Rating {
+        difficulty,
+        hardest,
+        solved_by_logic,
+        steps: steps.len(),
+        gem: if solved_by_logic { gem(&steps) } else { None },
+    }
+}
+
+/// Pearl / diamond class of a finished logic solve (`steps` in the order taken).
+fn gem(steps: &[Step]) -> Option<Gem> {
+    let hardest = steps.iter().map(|s| s.technique).max()?;
+    if hardest <= Technique::HiddenSingle {
+        return None;
+    }
+    if steps[0].technique == hardest {
+        return Some(Gem::Diamond);
+    }
+    let opening = steps.iter().position(|s| !s.placements.is_empty())?;
+    let needed = steps[..=opening].iter().map(|s| s.technique).max();
+    (needed == Some(hardest)).then_some(Gem::Pearl)
+}
@@ -0,0 +1,119 @@
+//! Precomputed cell geometry: the 27 units, the units each cell belongs to,
+//! and each cell's 20 peers (the other cells sharing its row, column or box).
+//!
+//! The tables are built at compile time, so validity checks, candidate
+//! updates and highlighting index them instead of redoing the box arithmetic.
+
+use crate::gameboard::SIZE;
+use crate::logic::Pos;
+
+/// Number of peers of every cell: 8 in its row, 8 in its column and the 4
+/// cells of its box outside both.
+pub const PEER_COUNT: usize = 20;
+
+/// The 27 units: rows 0 – 8, then columns, then boxes numbered in reading
+/// order, each listing its cells in reading order.
+pub static UNITS: [[Pos; SIZE]; 3 * SIZE] = build_units();
+
+/// Indices into `UNITS` of each cell's row, column and box, by `[row][col]`.
+static CELL_UNITS: [[[usize; 3]; SIZE]; SIZE] = build_cell_units();
+
+/// Each cell's peers in reading order, by `[row][col]`.
+static PEERS: [[[Pos; PEER_COUNT]; SIZE]; SIZE] = build_peers();
+
+/// Each cell's peers as a bitmask (bit `row * 9 + col`), by `[row][col]`.
+static PEER_MASKS: [[u128; SIZE]; SIZE] = build_peer_masks();
+
+/// Box number of `pos`, 0 – 8 in reading order.
+pub const fn box_of((row, col): Pos) -> usize {
+    row / 3 * 3 + col / 3
+}
+
+/// Indices into `UNITS` of the row, column and box containing `pos`.
+pub fn units_of((row, col): Pos) -> [usize; 3] {
+    CELL_UNITS[row][col]
+}
+
+/// The 20 peers of `pos`, in reading order.
+pub fn peers((row, col): Pos) -> &'static [Pos; PEER_COUNT] {
+    &PEERS[row][col]
+}
+
+/// Whether `a` and `b` are different cells sharing a row, column or box.
+pub fn is_peer(a: Pos, (row, col): Pos) -> bool {
+    PEER_MASKS[a.0][a.1] & 1 << (row * SIZE + col) != 0
+}
+
+const fn shares_unit(a: Pos, b: Pos) -> bool {
+    (a.0 != b.0 || a.1 != b.1) && (a.0 == b.0 || a.1 == b.1 || box_of(a) == box_of(b))
+}
+
+// `const fn` allows neither `for` loops nor iterators, hence the `while` loops.
+
+const fn build_units() -> [[Pos; SIZE]; 3 * SIZE] {
+    let mut units = [[(0, 0); SIZE]; 3 * SIZE];
+    let mut i = 0;
+    while i < SIZE {
+        let mut j = 0;
+        while j < SIZE {
+            units[i][j] = (i, j);
+            units[SIZE + i][j] = (j, i);
+            units[2 * SIZE + i][j] = (i / 3 * 3 + j / 3, i % 3 * 3 + j % 3);
+            j += 1;
+        }
+        i += 1;
+    }
+    units
+}
+
+const fn build_cell_units() -> [[[usize; 3]; SIZE]; SIZE] {
+    let mut cell_units = [[[0; 3]; SIZE]; SIZE];
+    let mut row = 0;
+    while row < SIZE {
+        let mut col = 0;
+        while col < SIZE {
+            cell_units[row][col] = [row, SIZE + col, 2 * SIZE + box_of((row, col))];
+            col += 1;
+        }
+        row += 1;
+    }
+    cell_units
+}
+
+const fn build_peers() -> [[[Pos; PEER_COUNT]; SIZE]; SIZE] {
+    let mut peers = [[[(0, 0); PEER_COUNT]; SIZE]; SIZE];
+    let mut cell = 0;
+    while cell < SIZE * SIZE {
+        let pos = (cell / SIZE, cell % SIZE);
+        let mut count = 0;
+        let mut other = 0;
+        while other < SIZE * SIZE {
+            let peer = (other / SIZE, other % SIZE);
+            if shares_unit(pos, peer) {
+                peers[pos.0][pos.1][count] = peer;
+                count += 1;
+            }
+            other += 1;
+        }
+        assert!(count == PEER_COUNT);
+        cell += 1;
+    }
+    peers
+}
+
+const fn build_peer_masks() -> [[u128; SIZE]; SIZE] {
+    let mut masks = [[0; SIZE]; SIZE];
+    let mut cell = 0;
+    while cell < SIZE * SIZE {
+        let pos = (cell / SIZE, cell % SIZE);
+        let mut other = 0;
+        while other < SIZE * SIZE {
+            if shares_unit(pos, (other / SIZE, other % SIZE)) {
+                masks[pos.0][pos.1] |= 1 << other;
+            }
+            other += 1;
+        }
+        cell += 1;
+    }
+    masks
+}
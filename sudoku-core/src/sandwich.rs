@@ -0,0 +1,163 @@
+//! Sandwich sudoku: outside each row and column is a clue, the sum of the
+//! digits between that line's 1 and 9 (0 when they are next to each other).
+//!
+//! The clues alone say a lot, so a sandwich puzzle can get by with far fewer
+//! givens than a classic one (generation is shared, see `variant.rs`).
+
+use crate::gameboard::{Gameboard, SIZE};
+use std::convert::TryInto;
+use std::fmt;
+use std::str::FromStr;
+
+/// Largest possible clue: every digit from 2 to 8 between the 1 and the 9.
+pub const MAX_CLUE: u8 = 35;
+
+/// A row or column of the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+/// The outside clues of a sandwich puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sandwich {
+    /// Clue left of each row, top to bottom.
+    pub rows: [u8; SIZE],
+    /// Clue above each column, left to right.
+    pub columns: [u8; SIZE],
+}
+
+impl Sandwich {
+    /// Read the clues off a full solution.
+    pub fn from_solution(solution: &Gameboard) -> Self {
+        let mut clues = Sandwich {
+            rows: [0; SIZE],
+            columns: [0; SIZE],
+        };
+        for i in 0..SIZE {
+            clues.rows[i] = sandwich_sum(&row(&solution.cells, i)).unwrap_or(0);
+            clues.columns[i] = sandwich_sum(&column(&solution.cells, i)).unwrap_or(0);
+        }
+        clues
+    }
+
+    /// The clue of `line`.
+    pub fn clue(&self, line: Line) -> u8 {
+        match line {
+            Line::Row(r) => self.rows[r],
+            Line::Column(c) => self.columns[c],
+        }
+    }
+
+    /// Lines whose filled digits can no longer meet their clue: the digits
+    /// between the 1 and the 9 add up to too much or too little, counting
+    /// what the empty cells between them could still hold.
+    pub fn broken(&self, board: &Gameboard) -> Vec<Line> {
+        (0..SIZE)
+            .map(Line::Row)
+            .chain((0..SIZE).map(Line::Column))
+            .filter(|&line| !allows(&line_cells(&board.cells, line), self.clue(line)))
+            .collect()
+    }
+
+    /// Whether the row and column of the digit just placed at (r, c) can still meet their clues.
+    pub(crate) fn accepts(&self, cells: &[[u8; SIZE]; SIZE], r: usize, c: usize) -> bool {
+        allows(&row(cells, r), self.rows[r]) && allows(&column(cells, c), self.columns[c])
+    }
+}
+
+/// Sum of the digits between the 1 and the 9 of a full line; `None` while
+/// either of them or a cell between them is still empty.
+pub fn sandwich_sum(line: &[u8; SIZE]) -> Option<u8> {
+    let a = line.iter().position(|&v| v == 1)?;
+    let b = line.iter().position(|&v| v == 9)?;
+    let between = &line[a.min(b) + 1..a.max(b)];
+    if between.contains(&0) {
+        return None;
+    }
+    Some(between.iter().sum())
+}
+
+/// Whether `line` can still meet `clue`. Only checked once both the 1 and
+/// the 9 are placed: the empty cells between them must be able to make up
+/// the rest of the sum from the digits 2 – 8 not yet in the line.
+fn allows(line: &[u8; SIZE], clue: u8) -> bool {
+    let (Some(a), Some(b)) = (
+        line.iter().position(|&v| v == 1),
+        line.iter().position(|&v| v == 9),
+    ) else {
+        return true;
+    };
+    let between = &line[a.min(b) + 1..a.max(b)];
+    let sum: u8 = between.iter().sum();
+    let open = between.iter().filter(|&&v| v == 0).count();
+    let free: Vec<u8> = (2..=8).filter(|d| !line.contains(d)).collect();
+    if open > free.len() {
+        return false;
+    }
+    let least: u8 = free[..open].iter().sum();
+    let most: u8 = free[free.len() - open..].iter().sum();
+    (sum + least..=sum + most).contains(&clue)
+}
+
+fn line_cells(cells: &[[u8; SIZE]; SIZE], line: Line) -> [u8; SIZE] {
+    match line {
+        Line::Row(r) => row(cells, r),
+        Line::Column(c) => column(cells, c),
+    }
+}
+
+fn row(cells: &[[u8; SIZE]; SIZE], r: usize) -> [u8; SIZE] {
+    cells[r]
+}
+
+fn column(cells: &[[u8; SIZE]; SIZE], c: usize) -> [u8; SIZE] {
+    let mut line = [0; SIZE];
+    for (r, value) in line.iter_mut().enumerate() {
+        *value = cells[r][c];
+    }
+    line
+}
+
+/// `rows/columns`, each a comma-separated list of nine clues,
+/// e.g. `12,0,35,8,17,5,23,9,14/6,11,...`.
+impl fmt::Display for Sandwich {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |clues: &[u8; SIZE]| {
+            clues
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(f, "{}/{}", join(&self.rows), join(&self.columns))
+    }
+}
+
+impl FromStr for Sandwich {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |part: &str| -> Result<[u8; SIZE], String> {
+            let clues: Vec<u8> = part
+                .split(',')
+                .map(|clue| match clue.trim().parse() {
+                    Ok(clue) if clue <= MAX_CLUE => Ok(clue),
+                    _ => Err(format!("invalid sandwich clue {:?}", clue.trim())),
+                })
+                .collect::<Result<_, _>>()?;
+            clues
+                .try_into()
+                .map_err(|clues: Vec<u8>| format!("expected 9 clues, found {}", clues.len()))
+        };
+        let (rows, columns) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| "expected row clues / column clues".to_string())?;
+        Ok(Sandwich {
+            rows: parse(rows)?,
+            columns: parse(columns)?,
+        })
+    }
+}
@@ -0,0 +1,223 @@
+//! Backtracking solver.
+
+use crate::gameboard::{Gameboard, SIZE};
+use crate::peers::peers;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Search statistics for one solver run, for tuning the generator and
+/// comparing the plain backtracking solver with the counting one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Digits tried in an empty cell.
+    pub nodes: u64,
+    /// Tried digits whose subtree held no solution.
+    pub backtracks: u64,
+    /// Wall time of the whole run.
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for SolveStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} backtracks, {:.2} ms",
+            self.nodes,
+            self.backtracks,
+            self.elapsed.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+impl Gameboard {
+    pub fn solve(&mut self) -> bool {
+        self.solve_with_stats().0
+    }
+
+    /// `solve`, also reporting how much searching it took.
+    pub fn solve_with_stats(&mut self) -> (bool, SolveStats) {
+        let start = Instant::now();
+        let mut stats = SolveStats::default();
+        let solved = self.solve_rec(&mut stats);
+        stats.elapsed = start.elapsed();
+        (solved, stats)
+    }
+
+    fn solve_rec(&mut self, stats: &mut SolveStats) -> bool {
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                if self.cells[row][col] == 0 {
+                    for num in 1..=9 {
+                        if self.is_valid_move(row, col, num) {
+                            self.cells[row][col] = num;
+                            stats.nodes += 1;
+                            if self.solve_rec(stats) {
+                                return true;
+                            }
+                            self.cells[row][col] = 0;
+                            stats.backtracks += 1;
+                        }
+                    }
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Gameboard {
+    /// Count solutions, stopping as soon as `limit` is reached.
+    /// Boards whose filled cells already conflict have no solutions.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.count_solutions_with_stats(limit).0
+    }
+
+    /// `count_solutions`, also reporting how much searching it took.
+    pub fn count_solutions_with_stats(&self, limit: usize) -> (usize, SolveStats) {
+        let start = Instant::now();
+        let mut stats = SolveStats::default();
+        let mut count = 0;
+        if self.conflicting_cells().is_empty() {
+            let mut cells = self.cells;
+            count_rec(
+                &mut cells,
+                limit,
+                &mut count,
+                &mut None,
+                &mut stats,
+                &|_, _, _| true,
+            );
+        }
+        stats.elapsed = start.elapsed();
+        (count, stats)
+    }
+
+    /// The solution if there is exactly one, found with the same search as
+    /// `count_solutions` (much faster than `solve` on sparse puzzles).
+    pub fn unique_solution(&self) -> Option<[[u8; SIZE]; SIZE]> {
+        if !self.conflicting_cells().is_empty() {
+            return None;
+        }
+        let mut cells = self.cells;
+        let (mut count, mut first) = (0, None);
+        count_rec(
+            &mut cells,
+            2,
+            &mut count,
+            &mut first,
+            &mut SolveStats::default(),
+            &|_, _, _| true,
+        );
+        first.filter(|_| count == 1)
+    }
+
+    /// The first solution the `count_solutions` search reaches (it tries the
+    /// cell with the fewest candidates first), or `None` if there is none.
+    /// Unlike `solve` this stays fast on sparse boards, so it suits input from
+    /// untrusted sources.
+    pub fn first_solution(&self) -> Option<[[u8; SIZE]; SIZE]> {
+        if !self.conflicting_cells().is_empty() {
+            return None;
+        }
+        let mut cells = self.cells;
+        let (mut count, mut first) = (0, None);
+        count_rec(
+            &mut cells,
+            1,
+            &mut count,
+            &mut first,
+            &mut SolveStats::default(),
+            &|_, _, _| true,
+        );
+        first
+    }
+
+    /// Whether the puzzle has exactly one solution.
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+}
+
+/// Extra rule of a sudoku variant, checked after each digit the search places:
+/// whether `cells` is still allowed with the digit just placed at (row, col).
+pub(crate) type Accept<'a> = &'a dyn Fn(&[[u8; SIZE]; SIZE], usize, usize) -> bool;
+
+/// `count_solutions_with_stats` under a variant's extra rule; also returns the
+/// first solution found. The filled cells must already satisfy `accept`.
+pub(crate) fn count_constrained(
+    board: &Gameboard,
+    limit: usize,
+    accept: Accept,
+) -> (usize, Option<[[u8; SIZE]; SIZE]>, SolveStats) {
+    let start = Instant::now();
+    let mut stats = SolveStats::default();
+    let (mut count, mut first) = (0, None);
+    if board.conflicting_cells().is_empty() {
+        let mut cells = board.cells;
+        count_rec(
+            &mut cells, limit, &mut count, &mut first, &mut stats, accept,
+        );
+    }
+    stats.elapsed = start.elapsed();
+    (count, first, stats)
+}
+
+/// Digits not yet used by any peer of the (empty) cell at `row`, `col`.
+fn candidates(cells: &[[u8; SIZE]; SIZE], row: usize, col: usize) -> u16 {
+    let used = peers((row, col))
+        .iter()
+        .fold(0u16, |acc, &(r, c)| acc | 1 << cells[r][c]);
+    !used & 0b11_1111_1110
+}
+
+fn count_rec(
+    cells: &mut [[u8; SIZE]; SIZE],
+    limit: usize,
+    count: &mut usize,
+    first: &mut Option<[[u8; SIZE]; SIZE]>,
+    stats: &mut SolveStats,
+    accept: Accept,
+) {
+    // pick the empty cell with the fewest candidates
+    let mut best: Option<(usize, usize, u16)> = None;
+    for r in 0..SIZE {
+        for c in 0..SIZE {
+            if cells[r][c] != 0 {
+                continue;
+            }
+            let mask = candidates(cells, r, c);
+            if best.is_none_or(|(_, _, m)| mask.count_ones() < m.count_ones()) {
+                best = Some((r, c, mask));
+                if mask.count_ones() <= 1 {
+                    break;
+                }
+            }
+        }
+    }
+    let Some((r, c, mask)) = best else {
+        if *count == 0 {
+            *first = Some(*cells);
+        }
+        *count += 1;
+        return;
+    };
+    for v in 1..=9u8 {
+        if mask & (1 << v) == 0 {
+            continue;
+        }
+        let before = *count;
+        cells[r][c] = v;
+        stats.nodes += 1;
+        if accept(cells, r, c) {
+            count_rec(cells, limit, count, first, stats, accept);
+        }
+        cells[r][c] = 0;
+        if *count == before {
+            stats.backtracks += 1;
+        }
+        if *count >= limit {
+            return;
+        }
+    }
+}
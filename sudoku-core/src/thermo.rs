@@ -0,0 +1,164 @@
+//! Thermometer sudoku: digits strictly increase along each thermometer, from
+//! the bulb to the tip.
+//!
+//! Layouts are grown on a full solution: each thermometer starts at a random
+//! free cell and keeps stepping to an orthogonal neighbour holding a larger
+//! digit, so the solution always fits. Thermometers never share a cell.
+
+use crate::gameboard::{Gameboard, SIZE};
+use crate::logic::Pos;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// How many thermometers `layout` tries to place.
+const THERMOS: usize = 8;
+
+/// Shortest and longest thermometer `layout` grows.
+const MIN_LENGTH: usize = 3;
+const MAX_LENGTH: usize = 6;
+
+/// One thermometer: its cells from the bulb to the tip, each orthogonally
+/// next to the one before.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Thermo {
+    pub cells: Vec<Pos>,
+}
+
+impl Thermo {
+    /// Whether the filled cells can no longer increase along the thermometer.
+    /// A digit `k` cells further along must be at least `k` larger, so gaps
+    /// count too: with a 3 on the bulb and a 4 two cells on, nothing fits between.
+    pub fn is_broken(&self, cells: &[[u8; SIZE]; SIZE]) -> bool {
+        let len = self.cells.len();
+        let filled: Vec<(usize, u8)> = self
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(i, &(r, c))| (i, cells[r][c]))
+            .filter(|&(_, v)| v != 0)
+            .collect();
+        let out_of_range = filled
+            .iter()
+            .any(|&(i, v)| (v as usize) < i + 1 || v as usize + (len - 1 - i) > 9);
+        out_of_range
+            || filled
+                .windows(2)
+                .any(|pair| (pair[1].1 as usize) < pair[0].1 as usize + (pair[1].0 - pair[0].0))
+    }
+}
+
+/// Indices of the thermometers the filled cells break.
+pub fn broken(thermos: &[Thermo], board: &Gameboard) -> Vec<usize> {
+    (0..thermos.len())
+        .filter(|&i| thermos[i].is_broken(&board.cells))
+        .collect()
+}
+
+/// Whether the thermometer through the digit just placed at (r, c), if any,
+/// can still increase.
+pub(crate) fn accepts(thermos: &[Thermo], cells: &[[u8; SIZE]; SIZE], r: usize, c: usize) -> bool {
+    thermos
+        .iter()
+        .filter(|thermo| thermo.cells.contains(&(r, c)))
+        .all(|thermo| !thermo.is_broken(cells))
+}
+
+/// Grow a random thermometer layout that `solution` satisfies.
+pub fn layout<R: Rng>(solution: &Gameboard, rng: &mut R) -> Vec<Thermo> {
+    let mut used = [[false; SIZE]; SIZE];
+    let mut starts: Vec<Pos> = (0..SIZE)
+        .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+        .collect();
+    starts.shuffle(rng);
+    let mut thermos = Vec::new();
+    for start in starts {
+        if thermos.len() == THERMOS {
+            break;
+        }
+        if used[start.0][start.1] {
+            continue;
+        }
+        let length = rng.gen_range(MIN_LENGTH..=MAX_LENGTH);
+        let mut cells = vec![start];
+        while cells.len() < length {
+            let (r, c) = cells[cells.len() - 1];
+            let value = solution.cells[r][c];
+            let next: Vec<Pos> = neighbours(r, c)
+                .into_iter()
+                .filter(|&(nr, nc)| !used[nr][nc] && solution.cells[nr][nc] > value)
+                .collect();
+            match next.choose(rng) {
+                Some(&cell) => cells.push(cell),
+                None => break,
+            }
+        }
+        if cells.len() >= MIN_LENGTH {
+            for &(r, c) in &cells {
+                used[r][c] = true;
+            }
+            thermos.push(Thermo { cells });
+        }
+    }
+    thermos
+}
+
+/// Orthogonal neighbours of (r, c) on the board.
+fn neighbours(r: usize, c: usize) -> Vec<Pos> {
+    let mut out = Vec::with_capacity(4);
+    if r > 0 {
+        out.push((r - 1, c));
+    }
+    if r + 1 < SIZE {
+        out.push((r + 1, c));
+    }
+    if c > 0 {
+        out.push((r, c - 1));
+    }
+    if c + 1 < SIZE {
+        out.push((r, c + 1));
+    }
+    out
+}
+
+/// Cells from bulb to tip as 1-based row and column digits, e.g. `11,12,22`.
+impl fmt::Display for Thermo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<String> = self
+            .cells
+            .iter()
+            .map(|&(r, c)| format!("{}{}", r + 1, c + 1))
+            .collect();
+        write!(f, "{}", cells.join(","))
+    }
+}
+
+impl FromStr for Thermo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = s
+            .split(',')
+            .map(|cell| {
+                let digits: Vec<usize> = cell
+                    .trim()
+                    .chars()
+                    .map(|ch| ch.to_digit(10).map(|d| d as usize))
+                    .collect::<Option<_>>()
+                    .unwrap_or_default();
+                match digits[..] {
+                    [r @ 1..=9, c @ 1..=9] => Ok((r - 1, c - 1)),
+                    _ => Err(format!("invalid thermometer cell {:?}", cell.trim())),
+                }
+            })
+            .collect::<Result<Vec<Pos>, _>>()?;
+        let connected = cells
+            .windows(2)
+            .all(|pair| pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1) == 1);
+        if cells.len() < 2 || !connected {
+            return Err(format!("{:?} is not a thermometer", s.trim()));
+        }
+        Ok(Thermo { cells })
+    }
+}
@@ -0,0 +1,211 @@
+//! Sudoku variants: extra rules on top of the classic ones, given as clues
+//! read off a full solution (see `sandwich.rs` and `thermo.rs`).
+//!
+//! Generation is the same for every variant: fill a solution, derive the
+//! clues from it, then remove givens as long as the clues and the remaining
+//! givens still allow only one solution.
+
+use crate::gameboard::{Gameboard, SIZE};
+use crate::sandwich::Sandwich;
+use crate::solver::{count_constrained, SolveStats};
+use crate::thermo::{self, Thermo};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Time allowed for removing givens; checking uniqueness gets slow once most
+/// of the board is empty, so generation stops early rather than keep the player waiting.
+pub const VARIANT_BUDGET: Duration = Duration::from_secs(2);
+
+/// Which variant to generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariantKind {
+    Sandwich,
+    Thermo,
+}
+
+/// A variant puzzle's clues.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Sandwich(Sandwich),
+    Thermo(Vec<Thermo>),
+}
+
+impl VariantKind {
+    pub const ALL: [VariantKind; 2] = [VariantKind::Sandwich, VariantKind::Thermo];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VariantKind::Sandwich => "sandwich",
+            VariantKind::Thermo => "thermo",
+        }
+    }
+
+    /// Generate a puzzle with up to `holes` empty cells: fewer when no further
+    /// given can go without losing uniqueness, or when `VARIANT_BUDGET` runs out first.
+    pub fn generate(self, holes: usize) -> (Gameboard, Variant) {
+        self.generate_with_rng(holes, VARIANT_BUDGET, &mut thread_rng())
+    }
+
+    /// Same as `generate`, but reproducible for a given seed (as long as the
+    /// budget does not run out).
+    pub fn generate_seeded(self, holes: usize, seed: u64) -> (Gameboard, Variant) {
+        self.generate_with_rng(holes, VARIANT_BUDGET, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn generate_with_rng<R: Rng>(
+        self,
+        holes: usize,
+        budget: Duration,
+        rng: &mut R,
+    ) -> (Gameboard, Variant) {
+        let deadline = Instant::now() + budget;
+        let mut board = Gameboard::generate_full_solution(rng);
+        let variant = match self {
+            VariantKind::Sandwich => Variant::Sandwich(Sandwich::from_solution(&board)),
+            VariantKind::Thermo => Variant::Thermo(thermo::layout(&board, rng)),
+        };
+        let mut positions: Vec<(usize, usize)> = (0..SIZE)
+            .flat_map(|r| (0..SIZE).map(move |c| (r, c)))
+            .collect();
+        positions.shuffle(rng);
+        let mut removed = 0;
+        for (r, c) in positions {
+            if removed == holes {
+                break;
+            }
+            let value = board.cells[r][c];
+            board.cells[r][c] = 0;
+            // a single uniqueness check can take seconds on a nearly empty board,
+            // so the deadline is checked inside the search, which then gives up
+            let late = Cell::new(false);
+            let (count, _, _) = count_constrained(&board, 2, &|cells, r, c| {
+                if Instant::now() >= deadline {
+                    late.set(true);
+                    return false;
+                }
+                variant.accepts(cells, r, c)
+            });
+            if late.get() {
+                board.cells[r][c] = value;
+                break;
+            }
+            if count == 1 {
+                removed += 1;
+            } else {
+                board.cells[r][c] = value;
+            }
+        }
+        (board, variant)
+    }
+}
+
+impl Variant {
+    pub fn kind(&self) -> VariantKind {
+        match self {
+            Variant::Sandwich(_) => VariantKind::Sandwich,
+            Variant::Thermo(_) => VariantKind::Thermo,
+        }
+    }
+
+    /// Whether the filled cells already break one of the clues.
+    pub fn is_broken(&self, board: &Gameboard) -> bool {
+        match self {
+            Variant::Sandwich(sandwich) => !sandwich.broken(board).is_empty(),
+            Variant::Thermo(thermos) => !thermo::broken(thermos, board).is_empty(),
+        }
+    }
+
+    /// Whether the clues through the digit just placed at (r, c) can still be met.
+    fn accepts(&self, cells: &[[u8; SIZE]; SIZE], r: usize, c: usize) -> bool {
+        match self {
+            Variant::Sandwich(sandwich) => sandwich.accepts(cells, r, c),
+            Variant::Thermo(thermos) => thermo::accepts(thermos, cells, r, c),
+        }
+    }
+
+    /// Count the solutions that also meet the clues, stopping at `limit`.
+    pub fn count_solutions_with_stats(
+        &self,
+        board: &Gameboard,
+        limit: usize,
+    ) -> (usize, SolveStats) {
+        let (count, _, stats) = self.search(board, limit);
+        (count, stats)
+    }
+
+    /// Whether exactly one solution meets the clues.
+    pub fn has_unique_solution(&self, board: &Gameboard) -> bool {
+        self.search(board, 2).0 == 1
+    }
+
+    /// A solution that meets the clues, with the search statistics.
+    pub fn solve_with_stats(&self, board: &Gameboard) -> (Option<[[u8; SIZE]; SIZE]>, SolveStats) {
+        let (_, first, stats) = self.search(board, 1);
+        (first, stats)
+    }
+
+    fn search(
+        &self,
+        board: &Gameboard,
+        limit: usize,
+    ) -> (usize, Option<[[u8; SIZE]; SIZE]>, SolveStats) {
+        if self.is_broken(board) {
+            return (0, None, SolveStats::default());
+        }
+        count_constrained(board, limit, &|cells, r, c| self.accepts(cells, r, c))
+    }
+}
+
+impl fmt::Display for VariantKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for VariantKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VariantKind::ALL
+            .iter()
+            .copied()
+            .find(|v| v.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown variant {:?} (sandwich, thermo)", s))
+    }
+}
+
+/// The kind followed by its clues: `sandwich ROWS/COLUMNS` (see `Sandwich`)
+/// or `thermo` and the thermometers separated by `;` (see `Thermo`).
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::Sandwich(sandwich) => write!(f, "{} {}", self.kind(), sandwich),
+            Variant::Thermo(thermos) => {
+                let thermos: Vec<String> = thermos.iter().map(Thermo::to_string).collect();
+                write!(f, "{} {}", self.kind(), thermos.join(";"))
+            }
+        }
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, clues) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        match kind.parse()? {
+            VariantKind::Sandwich => Ok(Variant::Sandwich(clues.parse()?)),
+            VariantKind::Thermo => clues
+                .split(';')
+                .filter(|thermo| !thermo.trim().is_empty())
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map(Variant::Thermo),
+        }
+    }
+}
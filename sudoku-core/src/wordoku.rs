@@ -0,0 +1,109 @@
+//! Wordoku: the nine letters of a word stand in for the digits 1 – 9.
+//!
+//! The solution is filled around a main diagonal that holds every digit once,
+//! and the letters are assigned so that the diagonal spells the word: the
+//! hidden word shows up once the puzzle is solved.
+
+use crate::gameboard::{Gameboard, SIZE};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+
+/// Words with nine different letters, picked from when no word is given.
+pub const WORDS: [&str; 19] = [
+    "AUTHORIZE",
+    "BACKFIRED",
+    "BLUDGEONS",
+    "COMPLAINS",
+    "COUNTRIES",
+    "DOWNRIGHT",
+    "DUPLICATE",
+    "EDUCATION",
+    "FACTORIES",
+    "FORMULATE",
+    "HARLEQUIN",
+    "LOCKSMITH",
+    "MOTHERING",
+    "NIGHTMARE",
+    "OUTSPREAD",
+    "PATHOGENS",
+    "PROFANITY",
+    "SHOWPLACE",
+    "WATCHDOGS",
+];
+
+/// The letters of `word` in order, upper-cased. The word must have exactly
+/// nine letters, all different.
+pub fn parse_word(word: &str) -> Result<[char; SIZE], String> {
+    let letters: Vec<char> = word
+        .trim()
+        .chars()
+        .map(|ch| ch.to_ascii_uppercase())
+        .collect();
+    if letters.len() != SIZE || !letters.iter().all(char::is_ascii_alphabetic) {
+        return Err(format!("{:?} is not a nine-letter word", word.trim()));
+    }
+    if let Some((_, ch)) = letters
+        .iter()
+        .enumerate()
+        .find(|&(i, ch)| letters[..i].contains(ch))
+    {
+        return Err(format!("{:?} repeats the letter {}", word.trim(), ch));
+    }
+    let mut out = [' '; SIZE];
+    out.copy_from_slice(&letters);
+    Ok(out)
+}
+
+/// A random word from `WORDS`.
+pub fn random_word<R: Rng>(rng: &mut R) -> [char; SIZE] {
+    let word = WORDS.choose(rng).expect("word list is not empty");
+    parse_word(word).expect("word list holds valid words")
+}
+
+impl Gameboard {
+    /// Generate a Wordoku puzzle with `holes` empty cells (as for
+    /// `generate_with_rng`) whose main diagonal spells `word` once solved.
+    pub fn generate_wordoku(word: [char; SIZE], holes: usize) -> Self {
+        Self::generate_wordoku_with_rng(word, holes, &mut thread_rng())
+    }
+
+    /// Same as `generate_wordoku`, but reproducible for a given seed.
+    pub fn generate_wordoku_seeded(word: [char; SIZE], holes: usize, seed: u64) -> Self {
+        Self::generate_wordoku_with_rng(word, holes, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn generate_wordoku_with_rng<R: Rng>(
+        word: [char; SIZE],
+        holes: usize,
+        rng: &mut R,
+    ) -> Self {
+        Self::generate_punched(holes, rng, |rng| {
+            // any diagonal of nine different digits is a relabelled 1 – 9 diagonal,
+            // so the rest of the board can always be filled around it
+            let mut digits: Vec<u8> = (1..=9).collect();
+            digits.shuffle(rng);
+            let mut cells = [[0u8; SIZE]; SIZE];
+            for (i, &digit) in digits.iter().enumerate() {
+                cells[i][i] = digit;
+            }
+            Self::fill_board(&mut cells, rng);
+            let mut letters = [' '; SIZE];
+            for (i, &digit) in digits.iter().enumerate() {
+                letters[digit as usize - 1] = word[i];
+            }
+            Gameboard {
+                cells,
+                letters: Some(letters),
+            }
+        })
+    }
+
+    /// The letters down the main diagonal, top-left to bottom-right: the hidden
+    /// word on a solved Wordoku board. `None` on a digit board or while a
+    /// diagonal cell is empty.
+    pub fn diagonal_word(&self) -> Option<String> {
+        self.letters?;
+        (0..SIZE).map(|i| self.symbol(self.cells[i][i])).collect()
+    }
+}